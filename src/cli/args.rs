@@ -0,0 +1,472 @@
+use crate::cli::error::CliError;
+use crate::client::models::Model;
+use crate::headless::models::{ApprovalPolicy, OutputFormat};
+use crate::permissions::mode::PermissionMode;
+use crate::session::budget::TurnBudget;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// The usage text printed for `--help` and invalid arguments.
+pub const USAGE: &str = r#"Usage: code-g [OPTIONS]
+       code-g run-script <FILE> [OPTIONS]
+       code-g eval <SUITE> [OPTIONS]
+
+Starts an interactive chat session, or runs a single prompt when --prompt is given.
+
+Commands:
+  run-script <FILE>                 Run the conversation scripted in a YAML or JSON file,
+                                    answering approvals and checking assertions as scripted
+  eval <SUITE>                      Run the tasks of a YAML or JSON eval suite on copies of
+                                    their fixtures and report which pass verification
+
+Options:
+  -p, --prompt <PROMPT>             Run a single prompt non-interactively and print the answer
+      --output-format <FORMAT>      Output format for --prompt, run-script and eval: text
+                                    (default) or json
+      --model <MODEL>               Model of an interactive session or --prompt, or to run eval
+                                    tasks with, repeat to compare eval models (default gpt-4o-mini)
+      --editor-model <MODEL>        Model that applies the file changes decided by the chat
+                                    model in an interactive session
+      --approval-policy <POLICY>    How tools that need approval are handled with --prompt:
+                                    deny (default) or approve
+      --permission-mode <MODE>      Permission mode to start in: default, plan, accept-edits
+                                    or full-auto
+      --max-iterations <N>          Maximum number of assistant requests per turn (default 50)
+      --max-tokens <N>              Maximum number of tokens per turn
+      --max-cost <USD>              Maximum cost in US dollars per turn
+      --max-time <SECONDS>          Maximum wall-clock time per turn
+  -h, --help                        Print this help text
+"#;
+
+/// Command line arguments for the CodeG binary.
+///
+/// # Examples
+///
+/// ```rust
+/// use code_g::cli::args::Args;
+/// use code_g::headless::models::{ApprovalPolicy, OutputFormat};
+///
+/// let args = Args::parse(["-p", "Summarize main.rs", "--output-format", "json"]).unwrap();
+///
+/// assert_eq!(args.prompt, Some("Summarize main.rs".to_string()));
+/// assert_eq!(args.output_format, OutputFormat::Json);
+/// assert_eq!(args.approval_policy, ApprovalPolicy::Deny);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Args {
+    /// Prompt to run non-interactively, `None` for an interactive session
+    pub prompt: Option<String>,
+    /// Script file to run non-interactively
+    pub script: Option<PathBuf>,
+    /// Eval suite file to run
+    pub eval: Option<PathBuf>,
+    /// Models to run the eval suite with, or the single model of an interactive or headless session
+    pub models: Vec<Model>,
+    /// Editor model paired with the interactive session's model
+    pub editor_model: Option<Model>,
+    /// Output format used by the headless and script modes
+    pub output_format: OutputFormat,
+    /// Approval policy used by the headless mode
+    pub approval_policy: ApprovalPolicy,
+    /// Permission mode to start in, overriding the settings files
+    pub permission_mode: Option<PermissionMode>,
+    /// Budget applied to every turn
+    pub budget: TurnBudget,
+}
+
+impl Default for Args {
+    fn default() -> Self {
+        Self {
+            prompt: None,
+            script: None,
+            eval: None,
+            models: vec![],
+            editor_model: None,
+            output_format: OutputFormat::Text,
+            approval_policy: ApprovalPolicy::Deny,
+            permission_mode: None,
+            budget: TurnBudget::default(),
+        }
+    }
+}
+
+impl Args {
+    /// Parses command line arguments, excluding the program name.
+    ///
+    /// # Arguments
+    ///
+    /// * `args` - The arguments to parse
+    ///
+    /// # Returns
+    ///
+    /// The parsed [`Args`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`CliError`] if an argument is unknown, is missing its value, has
+    /// an invalid value or is not used by the selected mode, or
+    /// [`CliError::HelpRequested`] if `--help` was given.
+    pub fn parse<I, S>(args: I) -> Result<Self, CliError>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut parsed = Args::default();
+        let mut args = args.into_iter().map(Into::into);
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-h" | "--help" => return Err(CliError::HelpRequested),
+                "-p" | "--prompt" => {
+                    parsed.prompt = Some(Self::value(&arg, args.next())?);
+                }
+                "run-script" => {
+                    parsed.script = Some(PathBuf::from(Self::value(&arg, args.next())?));
+                }
+                "eval" => {
+                    parsed.eval = Some(PathBuf::from(Self::value(&arg, args.next())?));
+                }
+                "--model" => {
+                    let value = Self::value(&arg, args.next())?;
+                    match value.parse() {
+                        Ok(model) => parsed.models.push(model),
+                        Err(_) => return Err(Self::invalid(&arg, value)),
+                    }
+                }
+                "--editor-model" => {
+                    let value = Self::value(&arg, args.next())?;
+                    match value.parse() {
+                        Ok(model) => parsed.editor_model = Some(model),
+                        Err(_) => return Err(Self::invalid(&arg, value)),
+                    }
+                }
+                "--output-format" => {
+                    let value = Self::value(&arg, args.next())?;
+                    parsed.output_format = match value.as_str() {
+                        "text" => OutputFormat::Text,
+                        "json" => OutputFormat::Json,
+                        _ => return Err(Self::invalid(&arg, value)),
+                    };
+                }
+                "--approval-policy" => {
+                    let value = Self::value(&arg, args.next())?;
+                    parsed.approval_policy = match value.as_str() {
+                        "deny" => ApprovalPolicy::Deny,
+                        "approve" => ApprovalPolicy::Approve,
+                        _ => return Err(Self::invalid(&arg, value)),
+                    };
+                }
+                "--permission-mode" => {
+                    let value = Self::value(&arg, args.next())?;
+                    parsed.permission_mode = match value.parse() {
+                        Ok(mode) => Some(mode),
+                        Err(_) => return Err(Self::invalid(&arg, value)),
+                    };
+                }
+                "--max-iterations" => {
+                    parsed.budget.max_iterations = Self::limit(&arg, args.next())?;
+                }
+                "--max-tokens" => {
+                    parsed.budget.max_tokens = Some(Self::limit(&arg, args.next())?);
+                }
+                "--max-cost" => {
+                    parsed.budget.max_cost = Some(Self::limit(&arg, args.next())?);
+                }
+                "--max-time" => {
+                    let seconds = Self::limit(&arg, args.next())?;
+                    parsed.budget.max_duration = Some(Duration::from_secs(seconds));
+                }
+                _ => return Err(CliError::UnknownArgument(arg)),
+            }
+        }
+
+        // Only interactive sessions pair an editor, scripts pick no model and only evals compare several
+        let unsupported = match parsed.mode() {
+            Some(mode) if parsed.editor_model.is_some() => Some(("--editor-model", mode)),
+            Some(mode @ "run-script") if !parsed.models.is_empty() => Some(("--model", mode)),
+            _ => None,
+        };
+        if let Some((argument, mode)) = unsupported {
+            return Err(CliError::UnsupportedArgument {
+                argument: argument.to_string(),
+                mode: mode.to_string(),
+            });
+        }
+        if parsed.eval.is_none() && parsed.models.len() > 1 {
+            return Err(CliError::DuplicateArgument("--model".to_string()));
+        }
+
+        Ok(parsed)
+    }
+
+    /// Returns the argument selecting a non-interactive mode, if one was given.
+    fn mode(&self) -> Option<&'static str> {
+        if self.eval.is_some() {
+            Some("eval")
+        } else if self.script.is_some() {
+            Some("run-script")
+        } else if self.prompt.is_some() {
+            Some("--prompt")
+        } else {
+            None
+        }
+    }
+
+    fn value(argument: &str, value: Option<String>) -> Result<String, CliError> {
+        value.ok_or_else(|| CliError::MissingValue(argument.to_string()))
+    }
+
+    // Budget limits must be positive, or the turn could not make any progress
+    fn limit<T: FromStr + PartialOrd + Default>(
+        argument: &str,
+        value: Option<String>,
+    ) -> Result<T, CliError> {
+        let value = Self::value(argument, value)?;
+        match value.parse() {
+            Ok(limit) if limit > T::default() => Ok(limit),
+            _ => Err(Self::invalid(argument, value)),
+        }
+    }
+
+    fn invalid(argument: &str, value: String) -> CliError {
+        CliError::InvalidValue {
+            argument: argument.to_string(),
+            value,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::providers::openai::schema::Model as OpenAiModel;
+
+    #[test]
+    fn parse_without_arguments_returns_interactive_defaults() {
+        let args = Args::parse(Vec::<String>::new()).unwrap();
+
+        assert_eq!(args, Args::default());
+    }
+
+    #[test]
+    fn parse_reads_prompt_and_headless_options() {
+        let args = Args::parse([
+            "--prompt",
+            "Fix the tests",
+            "--output-format",
+            "json",
+            "--approval-policy",
+            "approve",
+        ])
+        .unwrap();
+
+        assert_eq!(
+            args,
+            Args {
+                prompt: Some("Fix the tests".to_string()),
+                script: None,
+                eval: None,
+                models: vec![],
+                editor_model: None,
+                output_format: OutputFormat::Json,
+                approval_policy: ApprovalPolicy::Approve,
+                permission_mode: None,
+                budget: TurnBudget::default(),
+            }
+        );
+    }
+
+    #[test]
+    fn parse_reads_run_script_command() {
+        let args = Args::parse([
+            "run-script",
+            "scripts/hello.yaml",
+            "--output-format",
+            "json",
+        ])
+        .unwrap();
+
+        assert_eq!(args.script, Some(PathBuf::from("scripts/hello.yaml")));
+        assert_eq!(args.output_format, OutputFormat::Json);
+        assert_eq!(
+            Args::parse(["run-script"]),
+            Err(CliError::MissingValue("run-script".to_string()))
+        );
+    }
+
+    #[test]
+    fn parse_reads_eval_command_and_models() {
+        let args = Args::parse([
+            "eval",
+            "evals/suite.yaml",
+            "--model",
+            "gpt-4o",
+            "--model",
+            "gpt-4o-mini",
+        ])
+        .unwrap();
+
+        assert_eq!(args.eval, Some(PathBuf::from("evals/suite.yaml")));
+        assert_eq!(
+            args.models,
+            vec![
+                Model::OpenAi(OpenAiModel::Gpt4o),
+                Model::OpenAi(OpenAiModel::Gpt4oMini),
+            ]
+        );
+        assert_eq!(
+            Args::parse(["--model", "gpt-5"]),
+            Err(CliError::InvalidValue {
+                argument: "--model".to_string(),
+                value: "gpt-5".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn parse_reads_model_and_editor_model() {
+        let args = Args::parse(["--model", "gpt-4o", "--editor-model", "gpt-4o-mini"]).unwrap();
+
+        assert_eq!(args.models, vec![Model::OpenAi(OpenAiModel::Gpt4o)]);
+        assert_eq!(
+            args.editor_model,
+            Some(Model::OpenAi(OpenAiModel::Gpt4oMini))
+        );
+    }
+
+    #[test]
+    fn parse_rejects_models_in_modes_that_do_not_use_them() {
+        assert_eq!(
+            Args::parse(["run-script", "scripts/fix.yaml", "--model", "gpt-4o"]),
+            Err(CliError::UnsupportedArgument {
+                argument: "--model".to_string(),
+                mode: "run-script".to_string(),
+            })
+        );
+        assert_eq!(
+            Args::parse(["eval", "evals/suite.yaml", "--editor-model", "gpt-4o"]),
+            Err(CliError::UnsupportedArgument {
+                argument: "--editor-model".to_string(),
+                mode: "eval".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn parse_reads_model_for_prompt() {
+        let args = Args::parse(["-p", "Fix the tests", "--model", "gpt-4o"]).unwrap();
+
+        assert_eq!(args.models, vec![Model::OpenAi(OpenAiModel::Gpt4o)]);
+    }
+
+    #[test]
+    fn parse_rejects_several_models_outside_evals() {
+        for args in [
+            vec!["--model", "gpt-4o", "--model", "gpt-4o-mini"],
+            vec![
+                "-p",
+                "Fix the tests",
+                "--model",
+                "gpt-4o",
+                "--model",
+                "gpt-4o",
+            ],
+        ] {
+            assert_eq!(
+                Args::parse(args),
+                Err(CliError::DuplicateArgument("--model".to_string()))
+            );
+        }
+    }
+
+    #[test]
+    fn parse_reads_permission_mode() {
+        let args = Args::parse(["--permission-mode", "plan"]).unwrap();
+
+        assert_eq!(args.permission_mode, Some(PermissionMode::Plan));
+        assert_eq!(
+            Args::parse(["--permission-mode", "yolo"]),
+            Err(CliError::InvalidValue {
+                argument: "--permission-mode".to_string(),
+                value: "yolo".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn parse_reads_turn_budget() {
+        let args = Args::parse([
+            "--max-iterations",
+            "10",
+            "--max-tokens",
+            "20000",
+            "--max-cost",
+            "0.5",
+            "--max-time",
+            "120",
+        ])
+        .unwrap();
+
+        assert_eq!(
+            args.budget,
+            TurnBudget {
+                max_iterations: 10,
+                max_tokens: Some(20000),
+                max_cost: Some(0.5),
+                max_duration: Some(Duration::from_secs(120)),
+            }
+        );
+        assert_eq!(
+            Args::parse(["--max-cost", "cheap"]),
+            Err(CliError::InvalidValue {
+                argument: "--max-cost".to_string(),
+                value: "cheap".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn parse_rejects_turn_budgets_that_are_not_positive() {
+        for (argument, value) in [
+            ("--max-iterations", "0"),
+            ("--max-tokens", "0"),
+            ("--max-cost", "0"),
+            ("--max-cost", "-1.5"),
+            ("--max-cost", "NaN"),
+            ("--max-time", "0"),
+        ] {
+            assert_eq!(
+                Args::parse([argument, value]),
+                Err(CliError::InvalidValue {
+                    argument: argument.to_string(),
+                    value: value.to_string(),
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn parse_returns_error_for_missing_and_invalid_values() {
+        assert_eq!(
+            Args::parse(["-p"]),
+            Err(CliError::MissingValue("-p".to_string()))
+        );
+        assert_eq!(
+            Args::parse(["--output-format", "yaml"]),
+            Err(CliError::InvalidValue {
+                argument: "--output-format".to_string(),
+                value: "yaml".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn parse_returns_error_for_unknown_arguments_and_help() {
+        assert_eq!(
+            Args::parse(["--verbose"]),
+            Err(CliError::UnknownArgument("--verbose".to_string()))
+        );
+        assert_eq!(Args::parse(["--help"]), Err(CliError::HelpRequested));
+    }
+}
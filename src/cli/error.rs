@@ -0,0 +1,38 @@
+use thiserror::Error;
+
+/// Represents errors that can occur while parsing command line arguments.
+///
+/// # Examples
+///
+/// ```rust
+/// use code_g::cli::error::CliError;
+///
+/// let error = CliError::MissingValue("--prompt".to_string());
+/// assert_eq!(error.to_string(), "Missing value for argument '--prompt'");
+/// ```
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum CliError {
+    /// The user asked for the usage text
+    #[error("Help requested")]
+    HelpRequested,
+
+    /// An argument that is not recognized was given
+    #[error("Unknown argument '{0}'")]
+    UnknownArgument(String),
+
+    /// An argument that requires a value was given without one
+    #[error("Missing value for argument '{0}'")]
+    MissingValue(String),
+
+    /// An argument was given a value it does not accept
+    #[error("Invalid value '{value}' for argument '{argument}'")]
+    InvalidValue { argument: String, value: String },
+
+    /// An argument that can only be given once was repeated
+    #[error("Argument '{0}' can only be given once")]
+    DuplicateArgument(String),
+
+    /// An argument was given that the selected mode does not use
+    #[error("Argument '{argument}' cannot be used with {mode}")]
+    UnsupportedArgument { argument: String, mode: String },
+}
@@ -0,0 +1,82 @@
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+
+/// Asks the user whether to trust a project whose settings need trust.
+///
+/// # Arguments
+///
+/// * `input` - Where the answer is read from
+/// * `output` - Where the question is written to
+/// * `project_dir` - The directory of the project
+/// * `settings` - Descriptions of the project settings that need trust
+///
+/// # Returns
+///
+/// `true` if the user answered yes, `false` for any other answer.
+///
+/// # Errors
+///
+/// Returns an [`io::Error`] if the question cannot be written or the answer read.
+///
+/// # Examples
+///
+/// ```rust
+/// use code_g::cli::trust::confirm_trust;
+/// use std::path::Path;
+///
+/// let mut output = Vec::new();
+/// let trusted = confirm_trust(
+///     &mut "y\n".as_bytes(),
+///     &mut output,
+///     Path::new("/work/project"),
+///     &["hook 'cargo fmt'".to_string()],
+/// )
+/// .unwrap();
+///
+/// assert!(trusted);
+/// assert!(String::from_utf8(output).unwrap().contains("- hook 'cargo fmt'"));
+/// ```
+pub fn confirm_trust(
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+    project_dir: &Path,
+    settings: &[String],
+) -> io::Result<bool> {
+    writeln!(
+        output,
+        "The settings of {} run commands or let tools run without asking:",
+        project_dir.display()
+    )?;
+    for setting in settings {
+        writeln!(output, "  - {}", setting)?;
+    }
+    write!(
+        output,
+        "Only trust projects whose settings you have reviewed. Trust this project? [y/N] "
+    )?;
+    output.flush()?;
+
+    let mut answer = String::new();
+    input.read_line(&mut answer)?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn confirm_trust_defaults_to_no() {
+        let mut output = Vec::new();
+
+        let trusted = confirm_trust(
+            &mut "\n".as_bytes(),
+            &mut output,
+            Path::new("/work/project"),
+            &["rule 'allow *'".to_string()],
+        )
+        .unwrap();
+
+        assert!(!trusted);
+    }
+}
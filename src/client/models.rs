@@ -1,6 +1,8 @@
 use crate::client::providers::openai::schema::Model as OpenAiModel;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
 
 /// Represents the result of a chat completion operation.
 ///
@@ -131,12 +133,65 @@ pub enum AssistantMessage {
 /// use code_g::client::providers::openai::schema::Model as OpenAiModel;
 ///
 /// let model = Model::OpenAi(OpenAiModel::Gpt4o);
+/// assert_eq!(model.to_string(), "gpt-4o");
+/// assert_eq!("gpt-4o".parse::<Model>(), Ok(model));
 /// ```
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub enum Model {
     OpenAi(OpenAiModel),
 }
 
+impl Model {
+    /// Calculates the cost of the given token usage for this model.
+    ///
+    /// # Arguments
+    ///
+    /// * `usage` - The token usage to price
+    ///
+    /// # Returns
+    ///
+    /// The cost in US dollars.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use code_g::client::models::{Model, Usage};
+    /// use code_g::client::providers::openai::schema::Model as OpenAiModel;
+    ///
+    /// let usage = Usage {
+    ///     prompt_tokens: 1_000_000,
+    ///     completion_tokens: 0,
+    ///     total_tokens: 1_000_000,
+    /// };
+    /// assert_eq!(Model::OpenAi(OpenAiModel::Gpt4o).cost(&usage), 2.5);
+    /// ```
+    pub fn cost(&self, usage: &Usage) -> f64 {
+        let (prompt_price, completion_price) = match self {
+            Model::OpenAi(model) => model.price_per_million_tokens(),
+        };
+
+        (usage.prompt_tokens as f64 * prompt_price
+            + usage.completion_tokens as f64 * completion_price)
+            / 1_000_000.0
+    }
+}
+
+impl fmt::Display for Model {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Model::OpenAi(model) => write!(f, "{}", model),
+        }
+    }
+}
+
+impl FromStr for Model {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse().map(Model::OpenAi)
+    }
+}
+
 /// Represents a tool or function available to the assistant.
 ///
 /// This struct defines a tool that the OpenAI assistant can call during
@@ -367,3 +422,62 @@ pub struct ToolCall {
     pub name: String,
     pub arguments: HashMap<String, String>,
 }
+
+/// Represents the token usage of one or more chat completions.
+///
+/// Providers report usage per request; the chat session sums the usage of
+/// all requests it makes so callers can inspect the total cost of a turn.
+///
+/// # Fields
+///
+/// * `prompt_tokens` - Number of tokens sent to the model
+/// * `completion_tokens` - Number of tokens generated by the model
+/// * `total_tokens` - Total number of tokens used
+///
+/// # Examples
+///
+/// ```rust
+/// use code_g::client::models::Usage;
+///
+/// let mut usage = Usage::default();
+/// usage.add(&Usage {
+///     prompt_tokens: 10,
+///     completion_tokens: 5,
+///     total_tokens: 15,
+/// });
+/// assert_eq!(usage.total_tokens, 15);
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Usage {
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub total_tokens: u64,
+}
+
+impl Usage {
+    /// Adds the token counts of `other` to this usage.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The usage to add
+    pub fn add(&mut self, other: &Usage) {
+        self.prompt_tokens += other.prompt_tokens;
+        self.completion_tokens += other.completion_tokens;
+        self.total_tokens += other.total_tokens;
+    }
+
+    /// Returns the tokens counted since an earlier snapshot of this usage.
+    ///
+    /// # Arguments
+    ///
+    /// * `earlier` - The usage at the start of the period
+    pub fn since(&self, earlier: &Usage) -> Usage {
+        Usage {
+            prompt_tokens: self.prompt_tokens.saturating_sub(earlier.prompt_tokens),
+            completion_tokens: self
+                .completion_tokens
+                .saturating_sub(earlier.completion_tokens),
+            total_tokens: self.total_tokens.saturating_sub(earlier.total_tokens),
+        }
+    }
+}
@@ -1,5 +1,5 @@
 use crate::client::error::ChatClientError;
-use crate::client::models::{ChatMessage, ChatResult, Model, Tool, ToolCall};
+use crate::client::models::{ChatMessage, ChatResult, Model, Tool, ToolCall, Usage};
 use crate::client::providers::openai::error::OpenAIError;
 use crate::client::providers::openai::schema::{
     ChatCompletionRequest, ChatCompletionResponse, ChatMessageRequest, ContentResponse, JsonSchema,
@@ -153,6 +153,26 @@ impl ChatClient for OpenAIClient {
         chat_history: &[ChatMessage],
         tools: &[Tool],
     ) -> Result<ChatResult, ChatClientError> {
+        self.create_chat_completion_with_usage(model, chat_history, tools)
+            .await
+            .map(|(result, _)| result)
+    }
+
+    /// Creates a chat completion request to the OpenAI API and reports its token usage.
+    ///
+    /// See [`OpenAIClient::create_chat_completion`] for details on the request and
+    /// the possible errors. The usage is taken from the `usage` field of the
+    /// OpenAI response.
+    ///
+    /// # Returns
+    ///
+    /// A tuple of the [`ChatResult`] and the [`Usage`] of the request, if reported.
+    async fn create_chat_completion_with_usage(
+        &self,
+        model: &Model,
+        chat_history: &[ChatMessage],
+        tools: &[Tool],
+    ) -> Result<(ChatResult, Option<Usage>), ChatClientError> {
         if chat_history.is_empty() {
             return Err(ChatClientError::EmptyChatHistory);
         }
@@ -169,7 +189,8 @@ impl ChatClient for OpenAIClient {
                 .map(|m| ChatMessageRequest::try_from(m.clone()))
                 .collect::<Result<Vec<ChatMessageRequest>, serde_json::Error>>()
                 .map_err(|_| ChatClientError::InvalidChatMessageRequest)?,
-            tools: Some(tools.to_vec()),
+            // The API rejects an empty tool list, so omit it instead
+            tools: (!tools.is_empty()).then(|| tools.to_vec()),
             response_format: Some(ResponseFormat {
                 response_format_type: "json_schema".to_string(),
                 json_schema: JsonSchema {
@@ -207,16 +228,20 @@ impl ChatClient for OpenAIClient {
                     .ok_or(ChatClientError::OpenAIError(OpenAIError::NoChoicesFound))?;
 
                 let message = &choice.message;
+                let usage = completions.usage.map(Usage::from);
 
                 if let Some(content) = &message.content {
                     let content_response =
                         ContentResponse::try_from(content.as_str()).map_err(|_| {
                             ChatClientError::OpenAIError(OpenAIError::InvalidContentResponse)
                         })?;
-                    return Ok(ChatResult::Message {
-                        content: content_response.message,
-                        turn_over: content_response.turn_over,
-                    });
+                    return Ok((
+                        ChatResult::Message {
+                            content: content_response.message,
+                            turn_over: content_response.turn_over,
+                        },
+                        usage,
+                    ));
                 }
 
                 if let Some(tool_calls_response) = &message.tool_calls {
@@ -236,7 +261,7 @@ impl ChatClient for OpenAIClient {
                             })
                         })
                         .collect();
-                    return Ok(ChatResult::ToolCalls(tool_calls?));
+                    return Ok((ChatResult::ToolCalls(tool_calls?), usage));
                 }
 
                 Err(ChatClientError::OpenAIError(OpenAIError::NoContentFound))
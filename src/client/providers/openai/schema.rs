@@ -1,8 +1,10 @@
-use crate::client::models::{AssistantMessage, ChatMessage, Tool, ToolCall, ToolType};
+use crate::client::models::{AssistantMessage, ChatMessage, Tool, ToolCall, ToolType, Usage};
 
 use serde::de::Error;
 use serde::{Deserialize, Serialize};
 use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
 
 /// Represents the available OpenAI models for chat completions.
 ///
@@ -39,6 +41,63 @@ pub enum Model {
     GptO4MiniHigh,
 }
 
+impl Model {
+    /// All models, in the order they are listed to the user.
+    pub const ALL: [Model; 5] = [
+        Model::Gpt4o,
+        Model::Gpt4oMini,
+        Model::GptO3,
+        Model::GptO4Mini,
+        Model::GptO4MiniHigh,
+    ];
+
+    /// Returns the price in US dollars per million prompt and completion tokens.
+    ///
+    /// # Returns
+    ///
+    /// A tuple of `(prompt_price, completion_price)`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use code_g::client::providers::openai::schema::Model;
+    ///
+    /// let (prompt_price, completion_price) = Model::Gpt4oMini.price_per_million_tokens();
+    /// assert!(prompt_price < completion_price);
+    /// ```
+    pub fn price_per_million_tokens(&self) -> (f64, f64) {
+        match self {
+            Model::Gpt4o => (2.50, 10.00),
+            Model::Gpt4oMini => (0.15, 0.60),
+            Model::GptO3 => (2.00, 8.00),
+            Model::GptO4Mini | Model::GptO4MiniHigh => (1.10, 4.40),
+        }
+    }
+}
+
+impl fmt::Display for Model {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Model::Gpt4o => write!(f, "gpt-4o"),
+            Model::Gpt4oMini => write!(f, "gpt-4o-mini"),
+            Model::GptO3 => write!(f, "gpt-o3"),
+            Model::GptO4Mini => write!(f, "gpt-o4-mini"),
+            Model::GptO4MiniHigh => write!(f, "gpt-o4-mini-high"),
+        }
+    }
+}
+
+impl FromStr for Model {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Model::ALL
+            .into_iter()
+            .find(|model| model.to_string() == s)
+            .ok_or_else(|| format!("Unknown model '{}'", s))
+    }
+}
+
 /// Represents a chat completion request to the OpenAI API.
 ///
 /// This struct contains all the necessary information to make a chat completion
@@ -68,6 +127,7 @@ pub enum Model {
 pub struct ChatCompletionRequest {
     pub model: Model,
     pub messages: Vec<ChatMessageRequest>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub tools: Option<Vec<Tool>>,
     pub response_format: Option<ResponseFormat>,
 }
@@ -317,6 +377,7 @@ pub enum Role {
 /// * `created` - Unix timestamp when the completion was created
 /// * `model` - The model that generated this response
 /// * `choices` - Array of completion choices (usually contains one choice)
+/// * `usage` - Token usage for the request, if reported
 ///
 /// # Examples
 ///
@@ -334,6 +395,43 @@ pub struct ChatCompletionResponse {
     pub created: u64,
     pub model: String, // Different format than the model enum
     pub choices: Vec<ChoiceResponse>,
+    #[serde(default)]
+    pub usage: Option<UsageResponse>,
+}
+
+/// Represents the token usage reported in an OpenAI chat completion response.
+///
+/// # Fields
+///
+/// * `prompt_tokens` - Number of tokens in the prompt
+/// * `completion_tokens` - Number of tokens in the generated completion
+/// * `total_tokens` - Total number of tokens used by the request
+///
+/// # Examples
+///
+/// ```rust
+/// use code_g::client::providers::openai::schema::UsageResponse;
+///
+/// let json_usage = r#"{"prompt_tokens":12,"completion_tokens":5,"total_tokens":17}"#;
+/// let usage: UsageResponse = serde_json::from_str(json_usage).unwrap();
+/// assert_eq!(usage.total_tokens, 17);
+/// ```
+#[derive(Deserialize, Debug, Serialize, Clone, Copy)]
+pub struct UsageResponse {
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub total_tokens: u64,
+}
+
+impl From<UsageResponse> for Usage {
+    /// Converts the OpenAI usage response into the provider independent [`Usage`].
+    fn from(usage: UsageResponse) -> Self {
+        Usage {
+            prompt_tokens: usage.prompt_tokens,
+            completion_tokens: usage.completion_tokens,
+            total_tokens: usage.total_tokens,
+        }
+    }
 }
 
 /// Represents a single completion choice from the OpenAI API response.
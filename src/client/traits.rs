@@ -1,5 +1,5 @@
 use crate::client::error::ChatClientError;
-use crate::client::models::{ChatMessage, ChatResult, Model, Tool};
+use crate::client::models::{ChatMessage, ChatResult, Model, Tool, Usage};
 use async_trait::async_trait;
 
 /// Trait defining the interface for chat completion clients.
@@ -61,4 +61,34 @@ pub trait ChatClient: Send + Sync {
         chat_history: &[ChatMessage],
         tools: &[Tool],
     ) -> Result<ChatResult, ChatClientError>;
+
+    /// Creates a chat completion request and reports its token usage.
+    ///
+    /// Behaves like [`ChatClient::create_chat_completion`] but also returns the
+    /// token usage reported by the provider. The default implementation reports
+    /// no usage, so clients only need to override it if their provider exposes it.
+    ///
+    /// # Arguments
+    ///
+    /// * `model` - The model to use for the completion
+    /// * `chat_history` - The conversation history as a slice of chat messages
+    /// * `tools` - Available tools/functions that the assistant can call
+    ///
+    /// # Returns
+    ///
+    /// A tuple of the [`ChatResult`] and the [`Usage`] of the request, if known.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ChatClientError`] for the same conditions as
+    /// [`ChatClient::create_chat_completion`].
+    async fn create_chat_completion_with_usage(
+        &self,
+        model: &Model,
+        chat_history: &[ChatMessage],
+        tools: &[Tool],
+    ) -> Result<(ChatResult, Option<Usage>), ChatClientError> {
+        let result = self.create_chat_completion(model, chat_history, tools).await?;
+        Ok((result, None))
+    }
 }
\ No newline at end of file
@@ -0,0 +1,53 @@
+use std::io;
+use std::path::PathBuf;
+use thiserror::Error;
+
+/// Represents errors that can occur while loading or updating settings and instruction files.
+///
+/// # Examples
+///
+/// ```rust
+/// use code_g::config::error::ConfigError;
+/// use std::io;
+/// use std::path::PathBuf;
+///
+/// let error = ConfigError::Read {
+///     path: PathBuf::from(".code-g/settings.json"),
+///     source: io::Error::new(io::ErrorKind::PermissionDenied, "denied"),
+/// };
+/// assert!(error.to_string().contains(".code-g/settings.json"));
+/// ```
+#[derive(Error, Debug)]
+pub enum ConfigError {
+    /// The settings file exists but could not be read
+    #[error("Failed to read settings file {path}: {source}")]
+    Read {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+
+    /// The settings file is not valid JSON or has an unexpected shape
+    #[error("Invalid settings file {path}: {source}")]
+    Parse {
+        path: PathBuf,
+        #[source]
+        source: serde_json::Error,
+    },
+
+    /// A project instruction file exists but could not be read
+    #[error("Failed to read instruction file {path}: {source}")]
+    ReadInstructions {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+
+    /// The settings file could not be written
+    #[error("Failed to write settings file {path}: {source}")]
+    Write {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+}
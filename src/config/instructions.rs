@@ -0,0 +1,186 @@
+use crate::config::error::ConfigError;
+use crate::config::settings::Settings;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Names of the instruction files, in the order they are read from a directory.
+pub const INSTRUCTION_FILES: [&str; 2] = ["CODEG.md", "AGENTS.md"];
+
+/// Directory of the user's global instruction files, relative to the home directory.
+pub const USER_INSTRUCTIONS_DIR: &str = ".code-g";
+
+/// An instruction file and its content.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InstructionFile {
+    /// Where the file was found
+    pub path: PathBuf,
+    /// The instructions in the file
+    pub content: String,
+}
+
+/// Instructions for the assistant written once per user or repository.
+///
+/// `CODEG.md` and `AGENTS.md` files are read from `~/.code-g`, then from every
+/// directory between the filesystem root and the project directory. Files
+/// closer to the project come last, so their instructions take precedence.
+/// Empty files are skipped.
+///
+/// # Examples
+///
+/// ```rust
+/// use code_g::config::instructions::{InstructionFile, ProjectInstructions};
+/// use std::path::PathBuf;
+///
+/// let instructions = ProjectInstructions {
+///     files: vec![InstructionFile {
+///         path: PathBuf::from("/repo/CODEG.md"),
+///         content: "Run `cargo test` before finishing.".to_string(),
+///     }],
+/// };
+///
+/// let prompt = instructions.to_prompt().unwrap();
+/// assert!(prompt.contains("## /repo/CODEG.md\nRun `cargo test` before finishing."));
+/// assert_eq!(ProjectInstructions::default().to_prompt(), None);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProjectInstructions {
+    /// The files found, from the most general to the most specific
+    pub files: Vec<InstructionFile>,
+}
+
+impl ProjectInstructions {
+    /// Loads the user's and the project's instruction files.
+    ///
+    /// # Arguments
+    ///
+    /// * `project_dir` - The directory the session runs in
+    ///
+    /// # Returns
+    ///
+    /// The instructions found, which may be none.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ConfigError`] if an instruction file exists but cannot be read.
+    pub fn load(project_dir: &Path) -> Result<Self, ConfigError> {
+        let mut dirs = Vec::new();
+        if let Some(home) = Settings::home_dir() {
+            dirs.push(home.join(USER_INSTRUCTIONS_DIR));
+        }
+        let mut ancestors: Vec<&Path> = project_dir.ancestors().collect();
+        ancestors.reverse();
+        dirs.extend(ancestors.into_iter().map(Path::to_path_buf));
+
+        Self::load_from(&dirs)
+    }
+
+    /// Loads the instruction files of the given directories, in order.
+    ///
+    /// # Arguments
+    ///
+    /// * `dirs` - The directories to search, from the most general to the most specific
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ConfigError`] if an instruction file exists but cannot be read.
+    pub fn load_from(dirs: &[PathBuf]) -> Result<Self, ConfigError> {
+        let mut files = Vec::new();
+        for dir in dirs {
+            for name in INSTRUCTION_FILES {
+                let path = dir.join(name);
+                let content = match fs::read_to_string(&path) {
+                    Ok(content) => content,
+                    Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                    Err(source) => return Err(ConfigError::ReadInstructions { path, source }),
+                };
+                let content = content.trim();
+                if !content.is_empty() {
+                    files.push(InstructionFile {
+                        path,
+                        content: content.to_string(),
+                    });
+                }
+            }
+        }
+
+        Ok(ProjectInstructions { files })
+    }
+
+    /// Formats the instructions as a section of the system prompt.
+    ///
+    /// # Returns
+    ///
+    /// The section, or `None` if no instructions were found.
+    pub fn to_prompt(&self) -> Option<String> {
+        if self.files.is_empty() {
+            return None;
+        }
+
+        let files: Vec<String> = self
+            .files
+            .iter()
+            .map(|file| format!("## {}\n{}", file.path.display(), file.content))
+            .collect();
+        Some(format!(
+            "# Project Instructions\nFollow these instructions from the user and the project. When they conflict, later instructions take precedence.\n\n{}",
+            files.join("\n\n")
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!(
+            "code_g_instructions_{}_{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("repo")).unwrap();
+        dir
+    }
+
+    #[test]
+    fn load_from_reads_files_from_general_to_specific() {
+        let dir = temp_dir("order");
+        let repo = dir.join("repo");
+        fs::write(dir.join("CODEG.md"), "Use British spelling.\n").unwrap();
+        fs::write(repo.join("AGENTS.md"), "Never touch vendor/.").unwrap();
+        fs::write(repo.join("CODEG.md"), "Build with `cargo build`.").unwrap();
+
+        let instructions = ProjectInstructions::load_from(&[dir.clone(), repo.clone()]).unwrap();
+
+        let contents: Vec<&str> = instructions
+            .files
+            .iter()
+            .map(|file| file.content.as_str())
+            .collect();
+        assert_eq!(
+            contents,
+            vec![
+                "Use British spelling.",
+                "Build with `cargo build`.",
+                "Never touch vendor/."
+            ]
+        );
+        assert_eq!(instructions.files[2].path, repo.join("AGENTS.md"));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn load_from_skips_missing_and_empty_files() {
+        let dir = temp_dir("empty");
+        fs::write(dir.join("CODEG.md"), "  \n").unwrap();
+
+        let instructions =
+            ProjectInstructions::load_from(&[dir.clone(), dir.join("repo")]).unwrap();
+
+        assert_eq!(instructions, ProjectInstructions::default());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
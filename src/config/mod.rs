@@ -0,0 +1,4 @@
+pub mod error;
+pub mod instructions;
+pub mod settings;
+pub mod trust;
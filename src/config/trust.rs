@@ -0,0 +1,164 @@
+use crate::config::error::ConfigError;
+use crate::config::settings::Settings;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Location of the list of trusted projects, relative to the home directory.
+pub const TRUSTED_PROJECTS_PATH: &str = ".code-g/trusted_projects.json";
+
+/// The projects the user trusts to run commands from their settings.
+///
+/// Project settings can run shell commands through hooks and let tools run
+/// without asking, so they only apply once the user trusted the project. The
+/// list is kept in the home directory, so a cloned repository cannot trust
+/// itself.
+///
+/// # Examples
+///
+/// ```rust
+/// use code_g::config::trust::ProjectTrust;
+/// use std::path::Path;
+///
+/// let trust = ProjectTrust::default();
+///
+/// assert!(!trust.is_trusted(Path::new("/tmp/cloned-repository")));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProjectTrust {
+    path: Option<PathBuf>,
+    projects: Vec<PathBuf>,
+}
+
+impl ProjectTrust {
+    /// Loads the trusted projects of the user.
+    ///
+    /// # Returns
+    ///
+    /// The trusted projects, or none if the list does not exist or there is no
+    /// home directory.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ConfigError`] if the list exists but cannot be read or parsed.
+    pub fn load() -> Result<Self, ConfigError> {
+        match Settings::home_dir() {
+            Some(home) => Self::load_file(&home.join(TRUSTED_PROJECTS_PATH)),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Loads the trusted projects from a file, which [`ProjectTrust::trust`] updates.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the list, a JSON array of project directories
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ConfigError`] if the file exists but cannot be read or parsed.
+    pub fn load_file(path: &Path) -> Result<Self, ConfigError> {
+        let projects = match fs::read_to_string(path) {
+            Ok(content) => serde_json::from_str(&content).map_err(|source| ConfigError::Parse {
+                path: path.to_path_buf(),
+                source,
+            })?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(source) => {
+                return Err(ConfigError::Read {
+                    path: path.to_path_buf(),
+                    source,
+                });
+            }
+        };
+
+        Ok(Self {
+            path: Some(path.to_path_buf()),
+            projects,
+        })
+    }
+
+    /// Returns `true` if the user trusted the project.
+    ///
+    /// # Arguments
+    ///
+    /// * `project_dir` - The directory of the project
+    pub fn is_trusted(&self, project_dir: &Path) -> bool {
+        self.projects.contains(&canonical(project_dir))
+    }
+
+    /// Trusts a project and saves the list.
+    ///
+    /// # Arguments
+    ///
+    /// * `project_dir` - The directory of the project
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ConfigError`] if the list cannot be written.
+    pub fn trust(&mut self, project_dir: &Path) -> Result<(), ConfigError> {
+        if !self.is_trusted(project_dir) {
+            self.projects.push(canonical(project_dir));
+        }
+
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let write_error = |source| ConfigError::Write {
+            path: path.clone(),
+            source,
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(write_error)?;
+        }
+        let content =
+            serde_json::to_string_pretty(&self.projects).map_err(|source| ConfigError::Parse {
+                path: path.clone(),
+                source,
+            })?;
+        fs::write(path, content + "\n").map_err(write_error)
+    }
+}
+
+fn canonical(project_dir: &Path) -> PathBuf {
+    project_dir
+        .canonicalize()
+        .unwrap_or_else(|_| project_dir.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("code_g_trust_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn load_file_trusts_nothing_when_missing() {
+        let dir = temp_dir("missing");
+
+        let trust = ProjectTrust::load_file(&dir.join(TRUSTED_PROJECTS_PATH)).unwrap();
+
+        assert!(!trust.is_trusted(&dir));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn trust_saves_the_project() {
+        let dir = temp_dir("save");
+        let path = dir.join(TRUSTED_PROJECTS_PATH);
+
+        ProjectTrust::load_file(&path).unwrap().trust(&dir).unwrap();
+        let trust = ProjectTrust::load_file(&path).unwrap();
+
+        assert!(trust.is_trusted(&dir));
+        assert!(trust.is_trusted(&dir.join(".")));
+        assert!(!trust.is_trusted(&dir.join(".code-g")));
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,32 @@
+use std::io;
+use std::path::PathBuf;
+use thiserror::Error;
+
+/// Represents errors that can occur while loading an eval suite.
+///
+/// # Examples
+///
+/// ```rust
+/// use code_g::eval::error::EvalError;
+/// use std::path::PathBuf;
+///
+/// let error = EvalError::Parse {
+///     path: PathBuf::from("evals.yaml"),
+///     message: "missing field `tasks`".to_string(),
+/// };
+/// assert_eq!(error.to_string(), "Failed to parse eval suite evals.yaml: missing field `tasks`");
+/// ```
+#[derive(Error, Debug)]
+pub enum EvalError {
+    /// The suite file could not be read
+    #[error("Failed to read eval suite {}: {source}", path.display())]
+    Read {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+
+    /// The suite file is not a valid eval suite
+    #[error("Failed to parse eval suite {}: {message}", path.display())]
+    Parse { path: PathBuf, message: String },
+}
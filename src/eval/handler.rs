@@ -0,0 +1,70 @@
+use crate::session::event::{Action, Event, EventHandler};
+use std::io;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Event handler for eval runs.
+///
+/// The `EvalEventHandler` renders nothing and counts the requests made to the
+/// assistant. Since each task works on a throwaway copy of its fixture, every
+/// approval request is approved.
+///
+/// # Examples
+///
+/// ```rust
+/// use code_g::eval::handler::EvalEventHandler;
+/// use code_g::session::event::{Event, EventHandler};
+/// use std::sync::Arc;
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+///
+/// let iterations = Arc::new(AtomicUsize::new(0));
+/// let mut handler = EvalEventHandler::new(iterations.clone());
+///
+/// handler.handle_event(Event::AwaitingAssistantResponse);
+/// assert_eq!(iterations.load(Ordering::SeqCst), 1);
+/// ```
+pub struct EvalEventHandler {
+    iterations: Arc<AtomicUsize>,
+}
+
+impl EvalEventHandler {
+    /// Creates a new eval event handler.
+    ///
+    /// # Arguments
+    ///
+    /// * `iterations` - Shared counter of the requests made to the assistant
+    ///
+    /// # Returns
+    ///
+    /// A new `EvalEventHandler` instance.
+    pub fn new(iterations: Arc<AtomicUsize>) -> Self {
+        Self { iterations }
+    }
+}
+
+impl EventHandler for EvalEventHandler {
+    /// Counts requests to the assistant and ignores every other event.
+    fn handle_event(&mut self, event: Event) {
+        if event == Event::AwaitingAssistantResponse {
+            self.iterations.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    /// Approves every approval request.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `io::Error` for user input requests, since an eval run has
+    /// no user to ask.
+    fn handle_action(&mut self, action: Action) -> Result<String, io::Error> {
+        match action {
+            Action::RequestUserInput => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "User input is not available during an eval run",
+            )),
+            Action::RequestUserApproval { .. } | Action::RequestPlanApproval { .. } => {
+                Ok("approved".to_string())
+            }
+        }
+    }
+}
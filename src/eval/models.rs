@@ -0,0 +1,282 @@
+use crate::client::models::Usage;
+use crate::eval::error::EvalError;
+use crate::headless::models::OutputFormat;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A set of eval tasks, read from a YAML or JSON file.
+///
+/// # Examples
+///
+/// ```rust
+/// use code_g::eval::models::EvalSuite;
+/// use std::path::PathBuf;
+///
+/// let suite = EvalSuite::parse_yaml(
+///     r#"
+/// tasks:
+///   - name: fix-off-by-one
+///     fixture: fixtures/off-by-one
+///     prompt: The tests in src/range.rs fail, fix the bug.
+///     verify: cargo test
+/// "#,
+/// )
+/// .unwrap();
+///
+/// assert_eq!(suite.tasks[0].fixture, PathBuf::from("fixtures/off-by-one"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EvalSuite {
+    /// The tasks of the suite, in order
+    pub tasks: Vec<EvalTask>,
+}
+
+/// A task the assistant is evaluated on.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EvalTask {
+    /// Name identifying the task in the report
+    pub name: String,
+    /// Directory of the repository the task starts from, copied for each run
+    pub fixture: PathBuf,
+    /// The prompt sent to the assistant
+    pub prompt: String,
+    /// Command run in the copy after the turn, the task passes if it succeeds
+    pub verify: String,
+}
+
+impl EvalSuite {
+    /// Loads a suite, reading files ending in `.json` as JSON and all others as YAML.
+    ///
+    /// Relative fixture paths are resolved against the directory of the suite file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the suite file
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`EvalError`] if the file cannot be read or is not a valid suite.
+    pub fn load(path: &Path) -> Result<Self, EvalError> {
+        let content = fs::read_to_string(path).map_err(|source| EvalError::Read {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let parsed = match path.extension().and_then(|extension| extension.to_str()) {
+            Some("json") => serde_json::from_str(&content).map_err(|e| e.to_string()),
+            _ => Self::parse_yaml(&content),
+        };
+        let mut suite: Self = parsed.map_err(|message| EvalError::Parse {
+            path: path.to_path_buf(),
+            message,
+        })?;
+
+        let base = path.parent().unwrap_or(Path::new(""));
+        for task in &mut suite.tasks {
+            task.fixture = base.join(&task.fixture);
+        }
+        Ok(suite)
+    }
+
+    /// Parses a suite written in YAML.
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - The YAML text of the suite
+    ///
+    /// # Errors
+    ///
+    /// Returns the parser's message if the text is not a valid suite.
+    pub fn parse_yaml(content: &str) -> Result<Self, String> {
+        serde_yaml::from_str(content).map_err(|e| e.to_string())
+    }
+}
+
+/// The outcome of a task run with a model.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EvalResult {
+    /// The name of the task
+    pub task: String,
+    /// The model the task was run with
+    pub model: String,
+    /// Whether the verification command succeeded
+    pub passed: bool,
+    /// The number of requests made to the assistant
+    pub iterations: usize,
+    /// The token usage of the run
+    pub usage: Usage,
+    /// The cost of the run in US dollars
+    pub cost: f64,
+    /// The assistant's final message, if the turn completed
+    pub response: Option<String>,
+    /// The error that ended the run, if any
+    pub error: Option<String>,
+    /// The output of the verification command, stdout and stderr
+    pub verify_output: String,
+}
+
+/// The outcomes of an eval run.
+///
+/// # Examples
+///
+/// ```rust
+/// use code_g::eval::models::EvalReport;
+///
+/// let report = EvalReport::new(vec![]);
+/// assert_eq!(report.exit_code, EvalReport::EXIT_SUCCESS);
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EvalReport {
+    /// The outcome of each task for each model
+    pub results: Vec<EvalResult>,
+    /// The exit status of the run
+    pub exit_code: i32,
+}
+
+impl EvalReport {
+    /// Exit status for a run in which every task passed.
+    pub const EXIT_SUCCESS: i32 = 0;
+    /// Exit status for a run with a failed task.
+    pub const EXIT_FAILED: i32 = 1;
+
+    /// Creates a report from the outcomes of a run.
+    ///
+    /// # Arguments
+    ///
+    /// * `results` - The outcome of each task for each model
+    pub fn new(results: Vec<EvalResult>) -> Self {
+        let exit_code = if results.iter().all(|result| result.passed) {
+            Self::EXIT_SUCCESS
+        } else {
+            Self::EXIT_FAILED
+        };
+        Self { results, exit_code }
+    }
+
+    /// Renders the report in the given output format.
+    ///
+    /// The text format lists one line per task and model, with the error or
+    /// verification output of failed tasks, followed by a summary per model.
+    /// The JSON format contains the whole report.
+    ///
+    /// # Arguments
+    ///
+    /// * `format` - The output format to render
+    pub fn render(&self, format: OutputFormat) -> String {
+        match format {
+            OutputFormat::Text => {
+                let mut lines = Vec::new();
+                for result in &self.results {
+                    lines.push(format!(
+                        "{} {} with {}: {} iterations, {} tokens, ${:.4}",
+                        if result.passed { "PASS" } else { "FAIL" },
+                        result.task,
+                        result.model,
+                        result.iterations,
+                        result.usage.total_tokens,
+                        result.cost
+                    ));
+                    if let Some(error) = &result.error {
+                        lines.push(format!("  Error: {}", error));
+                    } else if !result.passed {
+                        lines.push(format!("  Verify: {}", result.verify_output.trim_end()));
+                    }
+                }
+
+                let mut models: Vec<&str> = vec![];
+                for result in &self.results {
+                    if !models.contains(&result.model.as_str()) {
+                        models.push(&result.model);
+                    }
+                }
+                for model in models {
+                    let results: Vec<&EvalResult> = self
+                        .results
+                        .iter()
+                        .filter(|result| result.model == model)
+                        .collect();
+                    lines.push(format!(
+                        "{}: {} of {} tasks passed, {} tokens, ${:.4}",
+                        model,
+                        results.iter().filter(|result| result.passed).count(),
+                        results.len(),
+                        results
+                            .iter()
+                            .map(|result| result.usage.total_tokens)
+                            .sum::<u64>(),
+                        results.iter().map(|result| result.cost).sum::<f64>()
+                    ));
+                }
+                lines.join("\n")
+            }
+            OutputFormat::Json => serde_json::to_string_pretty(self)
+                .unwrap_or_else(|e| format!("{{\"error\": \"{}\"}}", e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(task: &str, model: &str, passed: bool, tokens: u64) -> EvalResult {
+        EvalResult {
+            task: task.to_string(),
+            model: model.to_string(),
+            passed,
+            iterations: 2,
+            usage: Usage {
+                prompt_tokens: tokens,
+                completion_tokens: 0,
+                total_tokens: tokens,
+            },
+            cost: 0.01,
+            response: Some("Fixed".to_string()),
+            error: None,
+            verify_output: "1 test failed\n".to_string(),
+        }
+    }
+
+    #[test]
+    fn render_text_lists_results_and_summarizes_each_model() {
+        let report = EvalReport::new(vec![
+            result("fix-bug", "gpt-4o", true, 100),
+            result("add-test", "gpt-4o", false, 200),
+            result("fix-bug", "gpt-4o-mini", true, 50),
+        ]);
+
+        assert_eq!(report.exit_code, EvalReport::EXIT_FAILED);
+        assert_eq!(
+            report.render(OutputFormat::Text),
+            "PASS fix-bug with gpt-4o: 2 iterations, 100 tokens, $0.0100\n\
+             FAIL add-test with gpt-4o: 2 iterations, 200 tokens, $0.0100\n  \
+             Verify: 1 test failed\n\
+             PASS fix-bug with gpt-4o-mini: 2 iterations, 50 tokens, $0.0100\n\
+             gpt-4o: 1 of 2 tasks passed, 300 tokens, $0.0200\n\
+             gpt-4o-mini: 1 of 1 tasks passed, 50 tokens, $0.0100"
+        );
+    }
+
+    #[test]
+    fn load_resolves_fixtures_against_the_suite_file() {
+        let dir = std::env::temp_dir().join(format!("code_g_eval_suite_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("evals.json");
+        fs::write(
+            &path,
+            r#"{"tasks": [{"name": "a", "fixture": "repo", "prompt": "p", "verify": "true"}]}"#,
+        )
+        .unwrap();
+
+        let suite = EvalSuite::load(&path).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(suite.tasks[0].fixture, dir.join("repo"));
+        assert!(matches!(
+            EvalSuite::load(&path),
+            Err(EvalError::Read { .. })
+        ));
+    }
+}
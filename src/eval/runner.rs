@@ -0,0 +1,215 @@
+use crate::client::models::{Model, Usage};
+use crate::client::traits::ChatClient;
+use crate::eval::handler::EvalEventHandler;
+use crate::eval::models::{EvalReport, EvalResult, EvalSuite, EvalTask};
+use crate::permissions::config::{PermissionDecision, PermissionRule, PermissionsConfig};
+use crate::session::budget::TurnBudget;
+use crate::session::builder::ChatSessionBuilder;
+use crate::session::environment::PromptEnvironment;
+use crate::session::system_prompt::SystemPromptConfig;
+use crate::session::verification::{CheckResult, DEFAULT_TIMEOUT_SECS, run_check};
+use crate::tools::traits::ToolRegistry;
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+// Counter giving each task run its own working copy
+static RUNS: AtomicUsize = AtomicUsize::new(0);
+
+/// Runs every task of a suite with every model.
+///
+/// A new client and tool registry is created for each run, so the runs do
+/// not share any state.
+///
+/// # Arguments
+///
+/// * `suite` - The [`EvalSuite`] to run
+/// * `models` - The models to run each task with
+/// * `budget` - The [`TurnBudget`] limiting each run
+/// * `client` - Creates the [`ChatClient`] for a run
+/// * `tools` - Creates the [`ToolRegistry`] for a run
+///
+/// # Returns
+///
+/// An [`EvalReport`] with the outcome of each task for each model, grouped by model.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use code_g::client::models::Model;
+/// use code_g::client::providers::openai::client::OpenAIClient;
+/// use code_g::client::providers::openai::schema::Model as OpenAiModel;
+/// use code_g::eval::models::EvalSuite;
+/// use code_g::eval::runner::run_eval;
+/// use code_g::headless::models::OutputFormat;
+/// use code_g::session::budget::TurnBudget;
+/// use code_g::tools::registry::Registry;
+/// use std::path::Path;
+/// use tokio::runtime::Runtime;
+///
+/// let suite = EvalSuite::load(Path::new("evals/suite.yaml")).unwrap();
+/// let rt = Runtime::new().unwrap();
+/// let report = rt.block_on(run_eval(
+///     &suite,
+///     &[Model::OpenAi(OpenAiModel::Gpt4o), Model::OpenAi(OpenAiModel::Gpt4oMini)],
+///     TurnBudget::default(),
+///     || Box::new(OpenAIClient::new("api_key".to_string())),
+///     || Box::new(Registry::all_tools()),
+/// ));
+///
+/// println!("{}", report.render(OutputFormat::Text));
+/// ```
+pub async fn run_eval(
+    suite: &EvalSuite,
+    models: &[Model],
+    budget: TurnBudget,
+    client: impl Fn() -> Box<dyn ChatClient>,
+    tools: impl Fn() -> Box<dyn ToolRegistry>,
+) -> EvalReport {
+    let mut results = Vec::new();
+    for model in models {
+        for task in &suite.tasks {
+            results
+                .push(run_eval_task(client(), model.clone(), tools(), task, budget.clone()).await);
+        }
+    }
+    EvalReport::new(results)
+}
+
+/// Runs a task on a fresh copy of its fixture and verifies the outcome.
+///
+/// The fixture is copied to a temporary directory and the prompt is sent as
+/// a single turn, with the tools working in the copy and every approval
+/// request approved. File tools are denied paths outside the copy, but shell
+/// commands are not confined, so suites should only be run with fixtures and
+/// prompts that are trusted. The verification command is then run in the
+/// copy, which is removed at the end.
+///
+/// # Arguments
+///
+/// * `client` - [`ChatClient`] implementation for API communication
+/// * `model` - The model to run the task with
+/// * `tools` - [`ToolRegistry`] containing tools available to the AI assistant
+/// * `task` - The [`EvalTask`] to run
+/// * `budget` - The [`TurnBudget`] limiting the turn
+///
+/// # Returns
+///
+/// The [`EvalResult`] of the task. Errors copying the fixture or running the
+/// turn fail the task and are recorded in the result.
+pub async fn run_eval_task(
+    client: Box<dyn ChatClient>,
+    model: Model,
+    tools: Box<dyn ToolRegistry>,
+    task: &EvalTask,
+    budget: TurnBudget,
+) -> EvalResult {
+    let mut result = EvalResult {
+        task: task.name.clone(),
+        model: model.to_string(),
+        passed: false,
+        iterations: 0,
+        usage: Usage::default(),
+        cost: 0.0,
+        response: None,
+        error: None,
+        verify_output: String::new(),
+    };
+
+    let dir = env::temp_dir().join(format!(
+        "code-g-eval-{}-{}",
+        std::process::id(),
+        RUNS.fetch_add(1, Ordering::Relaxed)
+    ));
+    if let Err(e) = copy_dir(&task.fixture, &dir) {
+        let _ = fs::remove_dir_all(&dir);
+        result.error = Some(format!(
+            "Failed to copy fixture {}: {}",
+            task.fixture.display(),
+            e
+        ));
+        return result;
+    }
+
+    let iterations = Arc::new(AtomicUsize::new(0));
+    let mut session = ChatSessionBuilder::new(client)
+        .tools(tools)
+        .event_handler(Box::new(EvalEventHandler::new(iterations.clone())))
+        .system_prompt(SystemPromptConfig::Default)
+        .model(model.clone())
+        .turn_budget(budget)
+        .project_dir(dir.clone())
+        .permissions(outside_copy_rules())
+        .environment(PromptEnvironment::detect(&dir))
+        .build();
+
+    let turn = session
+        .send_message(&task.prompt)
+        .await
+        .map_err(|e| e.to_string());
+
+    result.iterations = iterations.load(Ordering::SeqCst);
+    result.usage = session.usage();
+    result.cost = model.cost(&result.usage);
+    match turn {
+        Ok(turn) => {
+            result.response = Some(turn.message);
+            // The check can run for minutes, so it runs off the async runtime's threads
+            let (command, check_dir) = (task.verify.clone(), dir.clone());
+            let check = tokio::task::spawn_blocking(move || {
+                run_check(
+                    &command,
+                    &check_dir,
+                    Duration::from_secs(DEFAULT_TIMEOUT_SECS),
+                )
+            })
+            .await
+            .unwrap_or_else(|e| CheckResult {
+                passed: false,
+                output: format!("Failed to run the check command: {}", e),
+            });
+            result.passed = check.passed;
+            result.verify_output = check.output;
+        }
+        Err(e) => result.error = Some(e),
+    }
+
+    let _ = fs::remove_dir_all(&dir);
+    result
+}
+
+/// Returns rules denying file tool calls on paths outside the task's copy.
+///
+/// Paths are matched relative to the copy, so paths outside of it are
+/// absolute or start with `..`.
+fn outside_copy_rules() -> PermissionsConfig {
+    let rules = ["/*", "..", "../*"].map(|path| PermissionRule {
+        decision: PermissionDecision::Deny,
+        tool: "*".to_string(),
+        arguments: BTreeMap::from([("path".to_string(), path.to_string())]),
+        reason: Some("eval tasks only work in their copy of the fixture".to_string()),
+    });
+    PermissionsConfig {
+        rules: rules.to_vec(),
+    }
+}
+
+/// Copies a directory and everything in it.
+fn copy_dir(from: &Path, to: &Path) -> io::Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,144 @@
+use crate::headless::models::{ApprovalPolicy, ToolCallRecord};
+use crate::session::event::{Action, Event, EventHandler};
+use std::io;
+use std::sync::{Arc, Mutex};
+
+/// Event handler for non-interactive sessions.
+///
+/// The `HeadlessEventHandler` renders nothing. It records every tool call so it
+/// can be included in the headless report, and answers approval requests with
+/// the configured [`ApprovalPolicy`] since there is nobody to prompt.
+///
+/// # Examples
+///
+/// ```rust
+/// use code_g::headless::handler::HeadlessEventHandler;
+/// use code_g::headless::models::ApprovalPolicy;
+/// use code_g::session::event::{Action, EventHandler};
+/// use std::collections::HashMap;
+/// use std::sync::{Arc, Mutex};
+///
+/// let tool_calls = Arc::new(Mutex::new(vec![]));
+/// let mut handler = HeadlessEventHandler::new(ApprovalPolicy::Deny, tool_calls.clone());
+///
+/// let response = handler.handle_action(Action::RequestUserApproval {
+///     approval_message: "CodeG wants to execute command 'ls'".to_string(),
+///     tool_name: "execute_command".to_string(),
+///     parameters: HashMap::from([("command".to_string(), "ls".to_string())]),
+///     reason: "execute_command requires approval by default".to_string(),
+/// });
+/// assert_eq!(response.unwrap(), "declined");
+/// ```
+pub struct HeadlessEventHandler {
+    approval_policy: ApprovalPolicy,
+    tool_calls: Arc<Mutex<Vec<ToolCallRecord>>>,
+}
+
+impl HeadlessEventHandler {
+    /// Creates a new headless event handler.
+    ///
+    /// # Arguments
+    ///
+    /// * `approval_policy` - How approval requests are answered
+    /// * `tool_calls` - Shared list the handler records tool calls into
+    ///
+    /// # Returns
+    ///
+    /// A new `HeadlessEventHandler` instance.
+    pub fn new(
+        approval_policy: ApprovalPolicy,
+        tool_calls: Arc<Mutex<Vec<ToolCallRecord>>>,
+    ) -> Self {
+        Self {
+            approval_policy,
+            tool_calls,
+        }
+    }
+}
+
+impl EventHandler for HeadlessEventHandler {
+    /// Records tool responses and ignores every other event.
+    fn handle_event(&mut self, event: Event) {
+        if let Event::ReceivedToolResponse {
+            tool_name,
+            response,
+            parameters,
+            approved,
+        } = event
+        {
+            self.tool_calls.lock().unwrap().push(ToolCallRecord {
+                tool_name,
+                parameters,
+                response,
+                approved,
+            });
+        }
+    }
+
+    /// Answers approval requests using the approval policy.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `io::Error` for user input requests, since a headless session
+    /// has no user to ask.
+    fn handle_action(&mut self, action: Action) -> Result<String, io::Error> {
+        match action {
+            Action::RequestUserInput => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "User input is not available in headless mode",
+            )),
+            Action::RequestUserApproval { .. } | Action::RequestPlanApproval { .. } => {
+                match self.approval_policy {
+                    ApprovalPolicy::Approve => Ok("approved".to_string()),
+                    ApprovalPolicy::Deny => Ok("declined".to_string()),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn handle_event_records_tool_responses() {
+        let tool_calls = Arc::new(Mutex::new(vec![]));
+        let mut handler = HeadlessEventHandler::new(ApprovalPolicy::Approve, tool_calls.clone());
+
+        handler.handle_event(Event::AwaitingAssistantResponse);
+        handler.handle_event(Event::ReceivedToolResponse {
+            tool_name: "read_file".to_string(),
+            response: "content".to_string(),
+            parameters: HashMap::new(),
+            approved: true,
+        });
+
+        assert_eq!(
+            *tool_calls.lock().unwrap(),
+            vec![ToolCallRecord {
+                tool_name: "read_file".to_string(),
+                parameters: HashMap::new(),
+                response: "content".to_string(),
+                approved: true,
+            }]
+        );
+    }
+
+    #[test]
+    fn handle_action_answers_approvals_with_policy_and_rejects_input() {
+        let mut handler =
+            HeadlessEventHandler::new(ApprovalPolicy::Approve, Arc::new(Mutex::new(vec![])));
+
+        let approval = handler.handle_action(Action::RequestUserApproval {
+            approval_message: "CodeG wants to write to file a.txt".to_string(),
+            tool_name: "write_file".to_string(),
+            parameters: HashMap::new(),
+            reason: "write_file requires approval by default".to_string(),
+        });
+
+        assert_eq!(approval.unwrap(), "approved");
+        assert!(handler.handle_action(Action::RequestUserInput).is_err());
+    }
+}
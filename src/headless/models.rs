@@ -0,0 +1,278 @@
+use crate::client::models::Usage;
+use crate::session::budget::BudgetLimit;
+use crate::session::error::ChatSessionError;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// How tools that require approval are handled when nobody can be prompted.
+///
+/// # Examples
+///
+/// ```rust
+/// use code_g::headless::models::ApprovalPolicy;
+///
+/// let policy = ApprovalPolicy::Deny;
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ApprovalPolicy {
+    /// Decline every tool call that requires approval
+    Deny,
+    /// Approve every tool call that requires approval
+    Approve,
+}
+
+/// The format the headless mode writes its result in.
+///
+/// # Examples
+///
+/// ```rust
+/// use code_g::headless::models::OutputFormat;
+///
+/// let format = OutputFormat::Json;
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutputFormat {
+    /// Print only the final assistant message
+    Text,
+    /// Print a JSON object with the final message, tool calls and usage
+    Json,
+}
+
+/// A tool call made during a headless run, together with its outcome.
+///
+/// # Examples
+///
+/// ```rust
+/// use code_g::headless::models::ToolCallRecord;
+/// use std::collections::HashMap;
+///
+/// let record = ToolCallRecord {
+///     tool_name: "read_file".to_string(),
+///     parameters: HashMap::from([("path".to_string(), "main.rs".to_string())]),
+///     response: "fn main() {}".to_string(),
+///     approved: true,
+/// };
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ToolCallRecord {
+    pub tool_name: String,
+    pub parameters: HashMap<String, String>,
+    pub response: String,
+    pub approved: bool,
+}
+
+/// The outcome of a headless run.
+///
+/// The report is printed to stdout either as plain text or as JSON, and its
+/// exit code is used as the process exit status.
+///
+/// # Examples
+///
+/// ```rust
+/// use code_g::headless::models::HeadlessReport;
+/// use code_g::client::models::Usage;
+///
+/// let report = HeadlessReport::new(Ok("Done".to_string()), vec![], Usage::default());
+/// assert_eq!(report.exit_code, 0);
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HeadlessReport {
+    /// The final assistant message, if the turn completed
+    pub message: Option<String>,
+    /// The error that ended the turn, if any
+    pub error: Option<String>,
+    /// The exit status of the run
+    pub exit_code: i32,
+    /// Whether the turn exhausted its budget, so the message is a summary of
+    /// unfinished work
+    pub budget_exhausted: bool,
+    /// All tool calls made during the turn
+    pub tool_calls: Vec<ToolCallRecord>,
+    /// The token usage of the turn
+    pub usage: Usage,
+}
+
+impl HeadlessReport {
+    /// Exit status for a turn that completed successfully.
+    pub const EXIT_SUCCESS: i32 = 0;
+    /// Exit status for a fatal chat client error.
+    pub const EXIT_CLIENT_ERROR: i32 = 1;
+    /// Exit status for a turn that exceeded the maximum number of iterations.
+    pub const EXIT_MAX_ITERATIONS: i32 = 2;
+    /// Exit status for any other session error.
+    pub const EXIT_SESSION_ERROR: i32 = 3;
+    /// Exit status for a turn that exhausted its token, cost or time budget.
+    pub const EXIT_BUDGET_EXCEEDED: i32 = 4;
+
+    /// Creates a report from the result of a turn.
+    ///
+    /// # Arguments
+    ///
+    /// * `result` - The result of the turn
+    /// * `tool_calls` - The tool calls made during the turn
+    /// * `usage` - The token usage of the turn
+    ///
+    /// # Returns
+    ///
+    /// A new `HeadlessReport` with the exit code derived from the result.
+    pub fn new(
+        result: Result<String, ChatSessionError>,
+        tool_calls: Vec<ToolCallRecord>,
+        usage: Usage,
+    ) -> Self {
+        let (message, error, exit_code) = match result {
+            Ok(message) => (Some(message), None, Self::EXIT_SUCCESS),
+            Err(error) => {
+                let exit_code = match &error {
+                    ChatSessionError::ChatClient(_) => Self::EXIT_CLIENT_ERROR,
+                    ChatSessionError::MaxIterationsExceeded { .. } => Self::EXIT_MAX_ITERATIONS,
+                    ChatSessionError::BudgetExceeded { .. } => Self::EXIT_BUDGET_EXCEEDED,
+                    ChatSessionError::LoopDetected { .. } | ChatSessionError::ToolError(_) => {
+                        Self::EXIT_SESSION_ERROR
+                    }
+                };
+                (None, Some(error.to_string()), exit_code)
+            }
+        };
+
+        Self {
+            message,
+            error,
+            exit_code,
+            budget_exhausted: false,
+            tool_calls,
+            usage,
+        }
+    }
+
+    /// Marks the report of a turn that ended with a summary because it exhausted
+    /// its budget.
+    ///
+    /// The summary is kept as the message, but the exit code reports the limit
+    /// that was reached, so scripts do not mistake the summary for a finished task.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - The budget limit the turn exhausted
+    ///
+    /// # Returns
+    ///
+    /// The report with the exit code of the limit.
+    pub fn with_exhausted_budget(mut self, limit: &BudgetLimit) -> Self {
+        self.budget_exhausted = true;
+        self.exit_code = match limit {
+            BudgetLimit::Iterations { .. } => Self::EXIT_MAX_ITERATIONS,
+            _ => Self::EXIT_BUDGET_EXCEEDED,
+        };
+        self
+    }
+
+    /// Renders the report in the given output format.
+    ///
+    /// The text format contains only the final message, or the error if the
+    /// turn failed. The JSON format contains the whole report.
+    ///
+    /// # Arguments
+    ///
+    /// * `format` - The output format to render
+    ///
+    /// # Returns
+    ///
+    /// The rendered report.
+    pub fn render(&self, format: OutputFormat) -> String {
+        match format {
+            OutputFormat::Text => match (&self.message, &self.error) {
+                (Some(message), _) => message.clone(),
+                (None, Some(error)) => format!("Error: {}", error),
+                (None, None) => String::new(),
+            },
+            OutputFormat::Json => serde_json::to_string_pretty(self)
+                .unwrap_or_else(|e| format!("{{\"error\": \"{}\"}}", e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::error::ChatClientError;
+
+    #[test]
+    fn new_maps_session_errors_to_exit_codes() {
+        let report = HeadlessReport::new(
+            Err(ChatSessionError::ChatClient(ChatClientError::InvalidApiKey)),
+            vec![],
+            Usage::default(),
+        );
+        assert_eq!(report.exit_code, HeadlessReport::EXIT_CLIENT_ERROR);
+
+        let report = HeadlessReport::new(
+            Err(ChatSessionError::MaxIterationsExceeded { max_iterations: 50 }),
+            vec![],
+            Usage::default(),
+        );
+        assert_eq!(report.exit_code, HeadlessReport::EXIT_MAX_ITERATIONS);
+        assert!(report.message.is_none());
+        assert!(report.error.unwrap().contains("Maximum iterations"));
+    }
+
+    #[test]
+    fn with_exhausted_budget_keeps_the_summary_and_reports_the_limit() {
+        let report = HeadlessReport::new(Ok("Summary".to_string()), vec![], Usage::default())
+            .with_exhausted_budget(&BudgetLimit::Iterations { used: 50, max: 50 });
+
+        assert_eq!(report.exit_code, HeadlessReport::EXIT_MAX_ITERATIONS);
+        assert!(report.budget_exhausted);
+        assert_eq!(report.message, Some("Summary".to_string()));
+
+        let report = HeadlessReport::new(Ok("Summary".to_string()), vec![], Usage::default())
+            .with_exhausted_budget(&BudgetLimit::Tokens {
+                used: 300,
+                max: 250,
+            });
+
+        assert_eq!(report.exit_code, HeadlessReport::EXIT_BUDGET_EXCEEDED);
+    }
+
+    #[test]
+    fn render_json_includes_message_tool_calls_and_usage() {
+        let report = HeadlessReport::new(
+            Ok("Done".to_string()),
+            vec![ToolCallRecord {
+                tool_name: "read_file".to_string(),
+                parameters: HashMap::new(),
+                response: "content".to_string(),
+                approved: true,
+            }],
+            Usage {
+                prompt_tokens: 3,
+                completion_tokens: 2,
+                total_tokens: 5,
+            },
+        );
+
+        let json: serde_json::Value =
+            serde_json::from_str(&report.render(OutputFormat::Json)).unwrap();
+
+        assert_eq!(json["message"], "Done");
+        assert_eq!(json["exit_code"], 0);
+        assert_eq!(json["tool_calls"][0]["tool_name"], "read_file");
+        assert_eq!(json["usage"]["total_tokens"], 5);
+    }
+
+    #[test]
+    fn render_text_returns_message_or_error() {
+        let report = HeadlessReport::new(Ok("Done".to_string()), vec![], Usage::default());
+        assert_eq!(report.render(OutputFormat::Text), "Done");
+
+        let report = HeadlessReport::new(
+            Err(ChatSessionError::ToolError("boom".to_string())),
+            vec![],
+            Usage::default(),
+        );
+        assert_eq!(
+            report.render(OutputFormat::Text),
+            "Error: Tool execution error: boom"
+        );
+    }
+}
@@ -0,0 +1,108 @@
+use crate::client::models::Model;
+use crate::client::traits::ChatClient;
+use crate::config::settings::Settings;
+use crate::headless::handler::HeadlessEventHandler;
+use crate::headless::models::{ApprovalPolicy, HeadlessReport};
+use crate::session::budget::TurnBudget;
+use crate::session::builder::ChatSessionBuilder;
+use crate::session::environment::PromptEnvironment;
+use crate::session::system_prompt::SystemPromptConfig;
+use crate::tools::traits::ToolRegistry;
+use std::env;
+use std::sync::{Arc, Mutex};
+
+/// Runs a single prompt through a chat session without user interaction.
+///
+/// The turn runs to completion with the headless event handler, which answers
+/// approval requests using `approval_policy`. The current directory is the
+/// project directory, which permission rules and modes resolve paths against,
+/// and the system prompt describes its environment. The result, tool calls and token
+/// usage are collected into a [`HeadlessReport`].
+///
+/// # Arguments
+///
+/// * `client` - [`ChatClient`] implementation for API communication
+/// * `tools` - [`ToolRegistry`] containing tools available to the AI assistant
+/// * `system_prompt_config` - [`SystemPromptConfig`] for the initial system prompt
+/// * `model` - The [`Model`] to run the prompt with, `None` for the default model
+/// * `prompt` - The prompt to run
+/// * `approval_policy` - How tools that require approval are handled
+/// * `budget` - The [`TurnBudget`] limiting the turn
+/// * `settings` - The [`Settings`] with the hooks, permission rules and permission mode to apply
+///
+/// # Returns
+///
+/// A [`HeadlessReport`] describing the outcome of the turn.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use code_g::client::providers::openai::client::OpenAIClient;
+/// use code_g::headless::models::{ApprovalPolicy, OutputFormat};
+/// use code_g::headless::runner::run_headless;
+/// use code_g::config::settings::Settings;
+/// use code_g::session::budget::TurnBudget;
+/// use code_g::session::system_prompt::SystemPromptConfig;
+/// use code_g::tools::registry::Registry;
+/// use tokio::runtime::Runtime;
+///
+/// let rt = Runtime::new().unwrap();
+/// let report = rt.block_on(run_headless(
+///     Box::new(OpenAIClient::new("api_key".to_string())),
+///     Box::new(Registry::read_only_tools()),
+///     SystemPromptConfig::Default,
+///     None,
+///     "Which files define tools?",
+///     ApprovalPolicy::Deny,
+///     TurnBudget::default(),
+///     Settings::default(),
+/// ));
+///
+/// println!("{}", report.render(OutputFormat::Text));
+/// std::process::exit(report.exit_code);
+/// ```
+#[allow(clippy::too_many_arguments)]
+pub async fn run_headless(
+    client: Box<dyn ChatClient>,
+    tools: Box<dyn ToolRegistry>,
+    system_prompt_config: SystemPromptConfig,
+    model: Option<Model>,
+    prompt: &str,
+    approval_policy: ApprovalPolicy,
+    budget: TurnBudget,
+    settings: Settings,
+) -> HeadlessReport {
+    let tool_calls = Arc::new(Mutex::new(vec![]));
+    let event_handler = HeadlessEventHandler::new(approval_policy, tool_calls.clone());
+
+    let mut builder = ChatSessionBuilder::new(client)
+        .tools(tools)
+        .event_handler(Box::new(event_handler))
+        .system_prompt(system_prompt_config)
+        .turn_budget(budget)
+        .hooks(settings.hooks)
+        .permissions(settings.permissions)
+        .verification(settings.verification)
+        .permission_mode(settings.permission_mode.unwrap_or_default());
+    if let Some(model) = model {
+        builder = builder.model(model);
+    }
+    if let Ok(cwd) = env::current_dir() {
+        builder = builder
+            .environment(PromptEnvironment::detect(&cwd))
+            .project_dir(cwd);
+    }
+    let mut session = builder.build();
+    let result = session.run_once(prompt).await;
+    let budget_exhausted = result
+        .as_ref()
+        .ok()
+        .and_then(|turn| turn.budget_exhausted.clone());
+
+    let tool_calls = tool_calls.lock().unwrap().clone();
+    let report = HeadlessReport::new(result.map(|turn| turn.message), tool_calls, session.usage());
+    match budget_exhausted {
+        Some(limit) => report.with_exhausted_budget(&limit),
+        None => report,
+    }
+}
@@ -0,0 +1,92 @@
+use serde::Deserialize;
+
+/// A shell command run on a lifecycle event.
+///
+/// # Examples
+///
+/// ```rust
+/// use code_g::hooks::config::Hook;
+///
+/// let hook = Hook {
+///     command: "cargo fmt".to_string(),
+///     tools: vec!["edit_file".to_string()],
+/// };
+///
+/// assert!(hook.matches("edit_file"));
+/// assert!(!hook.matches("read_file"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Hook {
+    /// The shell command to run
+    pub command: String,
+    /// Names of the tools the hook applies to, empty for all tools
+    #[serde(default)]
+    pub tools: Vec<String>,
+}
+
+impl Hook {
+    /// Returns `true` if the hook applies to calls of the given tool.
+    ///
+    /// # Arguments
+    ///
+    /// * `tool_name` - The name of the tool being called
+    pub fn matches(&self, tool_name: &str) -> bool {
+        self.tools.is_empty() || self.tools.iter().any(|tool| tool == tool_name)
+    }
+}
+
+/// Hooks configured for each lifecycle event.
+///
+/// Every hook receives a JSON description of the event on stdin. A hook that
+/// exits with status 2 gives feedback to the assistant: a `pre_tool_use` hook
+/// blocks the tool call with its output as the reason, and the output of a
+/// `post_tool_use` hook is added to the tool result. Status 2 has no special
+/// meaning for the other events. Other non-zero exit statuses are reported as
+/// hook failures.
+///
+/// # Examples
+///
+/// ```rust
+/// use code_g::hooks::config::HooksConfig;
+///
+/// let config: HooksConfig = serde_json::from_str(r#"{
+///     "pre_tool_use": [{ "command": "./scripts/check-command.sh", "tools": ["execute_command"] }]
+/// }"#).unwrap();
+///
+/// assert!(!config.is_empty());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HooksConfig {
+    /// Run when the user submits a prompt, before it is sent to the assistant
+    pub user_prompt_submit: Vec<Hook>,
+    /// Run before a tool is called, can block the call
+    pub pre_tool_use: Vec<Hook>,
+    /// Run after a tool was called, for example to run formatters or linters
+    pub post_tool_use: Vec<Hook>,
+    /// Run when a turn ends, successfully or not
+    pub turn_end: Vec<Hook>,
+}
+
+impl HooksConfig {
+    /// Returns `true` if no hooks are configured.
+    pub fn is_empty(&self) -> bool {
+        self.user_prompt_submit.is_empty()
+            && self.pre_tool_use.is_empty()
+            && self.post_tool_use.is_empty()
+            && self.turn_end.is_empty()
+    }
+
+    /// Appends the hooks of another configuration to this one.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The hooks to append
+    pub fn merge(&mut self, other: HooksConfig) {
+        self.user_prompt_submit.extend(other.user_prompt_submit);
+        self.pre_tool_use.extend(other.pre_tool_use);
+        self.post_tool_use.extend(other.post_tool_use);
+        self.turn_end.extend(other.turn_end);
+    }
+}
@@ -0,0 +1,46 @@
+use std::io;
+use std::time::Duration;
+use thiserror::Error;
+
+/// Represents errors that can occur while running a hook.
+///
+/// Hook errors never stop a turn; they are reported to the user and the
+/// session continues as if the hook had succeeded.
+///
+/// # Examples
+///
+/// ```rust
+/// use code_g::hooks::error::HookError;
+///
+/// let error = HookError::Failed {
+///     command: "cargo fmt".to_string(),
+///     status: Some(1),
+///     output: "error: expected item".to_string(),
+/// };
+/// assert_eq!(
+///     error.to_string(),
+///     "Hook 'cargo fmt' failed with status 1: error: expected item"
+/// );
+/// ```
+#[derive(Error, Debug)]
+pub enum HookError {
+    /// The hook command could not be started
+    #[error("Failed to run hook '{command}': {source}")]
+    Spawn {
+        command: String,
+        #[source]
+        source: io::Error,
+    },
+
+    /// The hook ran longer than [`HOOK_TIMEOUT`](crate::hooks::runner::HOOK_TIMEOUT) and was killed
+    #[error("Hook '{command}' was stopped after {} seconds", timeout.as_secs_f64())]
+    TimedOut { command: String, timeout: Duration },
+
+    /// The hook exited with a status other than 0 or 2
+    #[error("Hook '{command}' failed with status {}: {output}", status.map_or("unknown".to_string(), |s| s.to_string()))]
+    Failed {
+        command: String,
+        status: Option<i32>,
+        output: String,
+    },
+}
@@ -0,0 +1,310 @@
+use crate::client::models::ToolCall;
+use crate::hooks::config::{Hook, HooksConfig};
+use crate::hooks::error::HookError;
+use crate::shell::error::ShellError;
+use crate::shell::runner::run_shell;
+use serde::Serialize;
+use std::time::Duration;
+
+// Exit status a hook uses to give feedback to the assistant
+const FEEDBACK_EXIT_STATUS: i32 = 2;
+
+/// How long a hook may run before it is killed and reported as failed.
+pub const HOOK_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// A lifecycle event that hooks can run on.
+///
+/// The event is serialized as JSON and written to the hook's stdin, with the
+/// event name in the `hook_event` field.
+///
+/// # Examples
+///
+/// ```rust
+/// use code_g::hooks::runner::HookEvent;
+///
+/// let event = HookEvent::UserPromptSubmit { prompt: "Fix the tests".to_string() };
+///
+/// assert_eq!(
+///     serde_json::to_string(&event).unwrap(),
+///     r#"{"hook_event":"user_prompt_submit","prompt":"Fix the tests"}"#
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "hook_event", rename_all = "snake_case")]
+pub enum HookEvent {
+    /// The user submitted a prompt
+    UserPromptSubmit { prompt: String },
+    /// The assistant is about to call a tool
+    PreToolUse { tool_call: ToolCall },
+    /// A tool was called
+    PostToolUse {
+        tool_call: ToolCall,
+        tool_response: String,
+    },
+    /// A turn ended with the assistant's message or an error
+    TurnEnd {
+        message: Option<String>,
+        error: Option<String>,
+    },
+}
+
+/// The result of running the hooks for an event.
+#[derive(Debug, Default)]
+pub struct HookOutcome {
+    /// Output of the first hook that exited with status 2, fed back to the assistant
+    pub feedback: Option<String>,
+    /// Hooks that could not be run or failed
+    pub errors: Vec<HookError>,
+}
+
+/// Runs the configured hooks for lifecycle events.
+///
+/// # Examples
+///
+/// ```rust
+/// use code_g::hooks::config::{Hook, HooksConfig};
+/// use code_g::hooks::runner::{HookEvent, HookRunner};
+///
+/// let runner = HookRunner::new(HooksConfig {
+///     user_prompt_submit: vec![Hook { command: "cat > /dev/null".to_string(), tools: vec![] }],
+///     ..HooksConfig::default()
+/// });
+///
+/// let outcome = runner.run(&HookEvent::UserPromptSubmit { prompt: "Hi".to_string() });
+/// assert!(outcome.feedback.is_none());
+/// ```
+#[derive(Debug, Clone)]
+pub struct HookRunner {
+    config: HooksConfig,
+    timeout: Duration,
+}
+
+impl Default for HookRunner {
+    fn default() -> Self {
+        Self::new(HooksConfig::default())
+    }
+}
+
+impl HookRunner {
+    /// Creates a runner for the given hooks.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The [`HooksConfig`] with the hooks to run
+    pub fn new(config: HooksConfig) -> Self {
+        Self {
+            config,
+            timeout: HOOK_TIMEOUT,
+        }
+    }
+
+    /// Sets how long a hook may run before it is killed, [`HOOK_TIMEOUT`] by default.
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - The time limit of each hook
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Returns `true` if no hooks are configured, so running them does nothing.
+    pub fn is_empty(&self) -> bool {
+        self.config.is_empty()
+    }
+
+    /// Returns a runner with only the tool hooks of this runner.
+    ///
+    /// Used for nested sessions such as the editor, whose tool calls should
+    /// run the same hooks while its prompts and turns are not the user's.
+    pub fn tool_hooks(&self) -> Self {
+        Self::new(HooksConfig {
+            pre_tool_use: self.config.pre_tool_use.clone(),
+            post_tool_use: self.config.post_tool_use.clone(),
+            ..HooksConfig::default()
+        })
+        .with_timeout(self.timeout)
+    }
+
+    /// Runs the hooks that apply to an event, in configuration order.
+    ///
+    /// Running stops at the first hook that exits with status 2, so a
+    /// `pre_tool_use` hook that blocks a call prevents later hooks from running.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The lifecycle event, written to each hook's stdin as JSON
+    ///
+    /// # Returns
+    ///
+    /// A [`HookOutcome`] with the feedback for the assistant and any hook errors.
+    pub fn run(&self, event: &HookEvent) -> HookOutcome {
+        let mut outcome = HookOutcome::default();
+        if self.config.is_empty() {
+            return outcome;
+        }
+
+        let payload = serde_json::to_string(event).unwrap_or_default();
+
+        for hook in self.hooks_for(event) {
+            match self.run_hook(hook, &payload) {
+                Ok(None) => {}
+                Ok(Some(feedback)) => {
+                    outcome.feedback = Some(feedback);
+                    break;
+                }
+                Err(e) => outcome.errors.push(e),
+            }
+        }
+
+        outcome
+    }
+
+    fn hooks_for<'a>(&'a self, event: &HookEvent) -> impl Iterator<Item = &'a Hook> {
+        let (hooks, tool_name) = match event {
+            HookEvent::UserPromptSubmit { .. } => (&self.config.user_prompt_submit, None),
+            HookEvent::PreToolUse { tool_call } => {
+                (&self.config.pre_tool_use, Some(tool_call.name.clone()))
+            }
+            HookEvent::PostToolUse { tool_call, .. } => {
+                (&self.config.post_tool_use, Some(tool_call.name.clone()))
+            }
+            HookEvent::TurnEnd { .. } => (&self.config.turn_end, None),
+        };
+
+        hooks.iter().filter(move |hook| {
+            tool_name
+                .as_deref()
+                .is_none_or(|tool_name| hook.matches(tool_name))
+        })
+    }
+
+    /// Runs a single hook with the payload on stdin.
+    ///
+    /// # Returns
+    ///
+    /// `Some(feedback)` if the hook exited with status 2, `None` if it succeeded.
+    fn run_hook(&self, hook: &Hook, payload: &str) -> Result<Option<String>, HookError> {
+        let output = run_shell(&hook.command, None, Some(payload), Some(self.timeout)).map_err(
+            |e| match e {
+                ShellError::Spawn { source, .. } => HookError::Spawn {
+                    command: hook.command.clone(),
+                    source,
+                },
+                ShellError::TimedOut { timeout, .. } => HookError::TimedOut {
+                    command: hook.command.clone(),
+                    timeout,
+                },
+            },
+        )?;
+
+        let stdout = output.stdout.trim().to_string();
+        let stderr = output.stderr.trim().to_string();
+        let message = if stderr.is_empty() { stdout } else { stderr };
+
+        match output.status {
+            Some(0) => Ok(None),
+            Some(FEEDBACK_EXIT_STATUS) => Ok(Some(message)),
+            status => Err(HookError::Failed {
+                command: hook.command.clone(),
+                status,
+                output: message,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn hook(command: &str, tools: &[&str]) -> Hook {
+        Hook {
+            command: command.to_string(),
+            tools: tools.iter().map(|tool| tool.to_string()).collect(),
+        }
+    }
+
+    fn pre_tool_use(name: &str) -> HookEvent {
+        HookEvent::PreToolUse {
+            tool_call: ToolCall {
+                id: "1".to_string(),
+                name: name.to_string(),
+                arguments: HashMap::from([("command".to_string(), "rm -rf /".to_string())]),
+            },
+        }
+    }
+
+    #[test]
+    fn run_returns_feedback_of_hook_exiting_with_status_2() {
+        let runner = HookRunner::new(HooksConfig {
+            pre_tool_use: vec![
+                hook(
+                    "grep -q 'rm -rf' && echo 'Deleting is not allowed' >&2 && exit 2",
+                    &[],
+                ),
+                hook("exit 1", &[]),
+            ],
+            ..HooksConfig::default()
+        });
+
+        let outcome = runner.run(&pre_tool_use("execute_command"));
+
+        assert_eq!(
+            outcome.feedback,
+            Some("Deleting is not allowed".to_string())
+        );
+        assert!(outcome.errors.is_empty());
+    }
+
+    #[test]
+    fn run_skips_hooks_for_other_tools() {
+        let runner = HookRunner::new(HooksConfig {
+            pre_tool_use: vec![hook("exit 2", &["write_file"])],
+            ..HooksConfig::default()
+        });
+
+        let outcome = runner.run(&pre_tool_use("execute_command"));
+
+        assert_eq!(outcome.feedback, None);
+    }
+
+    #[test]
+    fn run_reports_failing_hooks() {
+        let runner = HookRunner::new(HooksConfig {
+            turn_end: vec![hook("echo broken && exit 3", &[])],
+            ..HooksConfig::default()
+        });
+
+        let outcome = runner.run(&HookEvent::TurnEnd {
+            message: Some("Done".to_string()),
+            error: None,
+        });
+
+        assert_eq!(outcome.feedback, None);
+        assert_eq!(
+            outcome.errors[0].to_string(),
+            "Hook 'echo broken && exit 3' failed with status 3: broken"
+        );
+    }
+
+    #[test]
+    fn run_reports_hooks_that_exceed_the_timeout() {
+        let runner = HookRunner::new(HooksConfig {
+            turn_end: vec![hook("sleep 10", &[])],
+            ..HooksConfig::default()
+        })
+        .with_timeout(Duration::from_millis(100));
+
+        let outcome = runner.run(&HookEvent::TurnEnd {
+            message: None,
+            error: None,
+        });
+
+        assert_eq!(
+            outcome.errors[0].to_string(),
+            "Hook 'sleep 10' was stopped after 0.1 seconds"
+        );
+    }
+}
@@ -1,4 +1,12 @@
+pub mod cli;
 pub mod client;
+pub mod config;
+pub mod eval;
+pub mod headless;
+pub mod hooks;
+pub mod permissions;
+pub mod script;
 pub mod session;
+pub mod shell;
 pub mod tools;
 pub mod tui;
@@ -1,9 +1,30 @@
-use code_g::session::session::ChatSession;
-use code_g::session::system_prompt::SystemPromptConfig;
+use code_g::cli::args::{Args, USAGE};
+use code_g::cli::error::CliError;
+use code_g::cli::trust::confirm_trust;
+use code_g::client::models::Model;
 use code_g::client::providers::openai::client::OpenAIClient;
+use code_g::client::providers::openai::schema::Model as OpenAiModel;
+use code_g::config::instructions::ProjectInstructions;
+use code_g::config::settings::Settings;
+use code_g::config::trust::ProjectTrust;
+use code_g::eval::models::EvalSuite;
+use code_g::eval::runner::run_eval;
+use code_g::headless::models::{HeadlessReport, OutputFormat};
+use code_g::headless::runner::run_headless;
+use code_g::script::models::{Script, ScriptReport};
+use code_g::script::runner::run_script;
+use code_g::session::builder::ChatSessionBuilder;
+use code_g::session::environment::PromptEnvironment;
+use code_g::session::input_queue::InputQueue;
+use code_g::session::system_prompt::SystemPromptConfig;
 use code_g::tools::registry::Registry;
 use code_g::tui::tui::Tui;
 use std::env;
+use std::io;
+use std::process;
+
+// Exit status used when the command line arguments are invalid
+const EXIT_USAGE: i32 = 64;
 
 // Entry point for the CodeG terminal chat application.
 //
@@ -11,22 +32,181 @@ use std::env;
 // Responsible for starting the async runtime and wiring
 // together the OpenAI client, tools, and TUI renderer.
 //
+// When started with `-p <prompt>`, runs the prompt headlessly instead,
+// prints the result and exits with a status reflecting the outcome.
+// `run-script <file>` does the same for a scripted conversation, and
+// `eval <suite>` for the tasks of an eval suite.
+//
 // Panics if required environment variables (e.g. OPENAI_API_KEY) are missing.
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = match Args::parse(env::args().skip(1)) {
+        Ok(args) => args,
+        Err(CliError::HelpRequested) => {
+            print!("{}", USAGE);
+            return Ok(());
+        }
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            process::exit(EXIT_USAGE);
+        }
+    };
+
     let api_key = env::var("OPENAI_API_KEY")?;
-    let openai_client = OpenAIClient::new(api_key);
+
+    if let Some(path) = args.eval {
+        let suite = match EvalSuite::load(&path) {
+            Ok(suite) => suite,
+            Err(e) => {
+                eprintln!("{}", e);
+                process::exit(EXIT_USAGE);
+            }
+        };
+        let models = if args.models.is_empty() {
+            vec![Model::OpenAi(OpenAiModel::Gpt4oMini)]
+        } else {
+            args.models.clone()
+        };
+
+        let report = run_eval(
+            &suite,
+            &models,
+            args.budget,
+            || Box::new(OpenAIClient::new(api_key.clone())),
+            || Box::new(Registry::all_tools()),
+        )
+        .await;
+
+        println!("{}", report.render(args.output_format));
+        process::exit(report.exit_code);
+    }
+    let openai_client = OpenAIClient::new(api_key.clone());
 
     let tools = Registry::all_tools();
+    let project_dir = env::current_dir()?;
+    let mut trust = ProjectTrust::load()?;
+    let project_settings = Settings::load_project_file(&project_dir)?;
+    if let Some(user_only) = project_settings
+        .as_ref()
+        .map(Settings::user_only)
+        .filter(|user_only| !user_only.is_empty())
+    {
+        eprintln!(
+            "Ignoring project settings that only the user settings or --permission-mode can set: {}.",
+            user_only.join(", ")
+        );
+    }
+    if !trust.is_trusted(&project_dir)
+        && let Some(requiring_trust) = project_settings
+            .map(|project_settings| project_settings.requiring_trust())
+            .filter(|requiring_trust| !requiring_trust.is_empty())
+    {
+        let interactive = args.script.is_none() && args.prompt.is_none();
+        if interactive
+            && confirm_trust(
+                &mut io::stdin().lock(),
+                &mut io::stdout(),
+                &project_dir,
+                &requiring_trust,
+            )?
+        {
+            trust.trust(&project_dir)?;
+        } else {
+            eprintln!(
+                "Ignoring project settings that need trust: {}. Start an interactive session in the project to trust it.",
+                requiring_trust.join(", ")
+            );
+        }
+    }
+    let mut settings = Settings::load(&project_dir, trust.is_trusted(&project_dir))?;
+    if args.permission_mode.is_some() {
+        settings.permission_mode = args.permission_mode;
+    }
+    let system_prompt_config =
+        SystemPromptConfig::Default.with_instructions(&ProjectInstructions::load(&project_dir)?);
+
+    if let Some(path) = args.script {
+        let mut script = match Script::load(&path) {
+            Ok(script) => script,
+            Err(e) => {
+                eprintln!("{}", e);
+                process::exit(EXIT_USAGE);
+            }
+        };
+        if args.permission_mode.is_some() {
+            script.permission_mode = args.permission_mode;
+        }
+
+        let report = run_script(
+            Box::new(openai_client),
+            Box::new(tools),
+            system_prompt_config,
+            &script,
+            args.budget,
+            settings,
+            &project_dir,
+        )
+        .await;
+
+        let output = report.render(args.output_format);
+        if report.exit_code == ScriptReport::EXIT_SUCCESS
+            || args.output_format == OutputFormat::Json
+        {
+            println!("{}", output);
+        } else {
+            eprintln!("{}", output);
+        }
+        process::exit(report.exit_code);
+    }
+
+    if let Some(prompt) = args.prompt {
+        let report = run_headless(
+            Box::new(openai_client),
+            Box::new(tools),
+            system_prompt_config,
+            args.models.first().cloned(),
+            &prompt,
+            args.approval_policy,
+            args.budget,
+            settings,
+        )
+        .await;
+
+        let output = report.render(args.output_format);
+        if report.exit_code == HeadlessReport::EXIT_SUCCESS
+            || args.output_format == OutputFormat::Json
+        {
+            println!("{}", output);
+        } else {
+            eprintln!("{}", output);
+        }
+        process::exit(report.exit_code);
+    }
 
-    let tui = Tui::new();
+    let input_queue = InputQueue::new();
+    let tui = Tui::new().with_input_queue(input_queue.clone());
 
-    let mut chat_session = ChatSession::new(
-        Box::new(openai_client),
-        Box::new(tools),
-        Box::new(tui),
-        SystemPromptConfig::Default,
-    );
+    let mut builder = ChatSessionBuilder::new(Box::new(openai_client))
+        .tools(Box::new(tools))
+        .event_handler(Box::new(tui))
+        .system_prompt(system_prompt_config)
+        .turn_budget(args.budget)
+        .hooks(settings.hooks)
+        .permissions(settings.permissions)
+        .verification(settings.verification)
+        .permission_mode(settings.permission_mode.unwrap_or_default())
+        .project_dir(project_dir.clone())
+        .sub_agents(true)
+        .todos(true)
+        .input_queue(input_queue)
+        .environment(PromptEnvironment::detect(&project_dir));
+    if let Some(model) = args.models.first() {
+        builder = builder.model(model.clone());
+    }
+    if let Some(editor_model) = args.editor_model {
+        builder = builder.editor(Box::new(OpenAIClient::new(api_key)), editor_model);
+    }
+    let mut chat_session = builder.build();
 
     chat_session.run().await?;
 
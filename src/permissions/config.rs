@@ -0,0 +1,110 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// What happens to a tool call matched by a permission rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PermissionDecision {
+    /// Run the tool without asking
+    Allow,
+    /// Ask the user for approval before running the tool
+    Ask,
+    /// Refuse to run the tool
+    Deny,
+}
+
+impl fmt::Display for PermissionDecision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PermissionDecision::Allow => write!(f, "allow"),
+            PermissionDecision::Ask => write!(f, "ask"),
+            PermissionDecision::Deny => write!(f, "deny"),
+        }
+    }
+}
+
+/// A rule matching tool calls by tool name and argument patterns.
+///
+/// Patterns support the wildcards `*` (any number of characters, including `/`)
+/// and `?` (exactly one character), and `\*`, `\?` and `\\` for the literal
+/// characters. A pattern ending in ` *` also matches the text before it, so
+/// `ls *` matches `ls` and `ls -la` but not `lsblk`. A rule matches a call when
+/// the tool name matches `tool` and every pattern in `arguments` matches the
+/// argument of the same name. `path` arguments are matched relative to the
+/// project directory.
+///
+/// # Examples
+///
+/// ```rust
+/// use code_g::permissions::config::{PermissionDecision, PermissionRule};
+///
+/// let rule: PermissionRule = serde_json::from_str(r#"{
+///     "decision": "allow",
+///     "tool": "execute_command",
+///     "arguments": { "command": "cargo test*" }
+/// }"#).unwrap();
+///
+/// assert_eq!(rule.decision, PermissionDecision::Allow);
+/// assert_eq!(rule.to_string(), r#"allow execute_command command="cargo test*""#);
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct PermissionRule {
+    /// The decision for matching calls
+    pub decision: PermissionDecision,
+    /// Pattern for the tool name
+    pub tool: String,
+    /// Patterns for the tool arguments, by argument name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub arguments: BTreeMap<String, String>,
+    /// Optional explanation shown when the rule applies
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+impl fmt::Display for PermissionRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.decision, self.tool)?;
+        for (name, pattern) in &self.arguments {
+            write!(f, " {}=\"{}\"", name, pattern)?;
+        }
+        if let Some(reason) = &self.reason {
+            write!(f, " ({})", reason)?;
+        }
+        Ok(())
+    }
+}
+
+/// Permission rules loaded from the settings files.
+///
+/// # Examples
+///
+/// ```rust
+/// use code_g::permissions::config::PermissionsConfig;
+///
+/// let config: PermissionsConfig = serde_json::from_str(r#"{
+///     "rules": [
+///         { "decision": "deny", "tool": "write_file", "arguments": { "path": ".git/*" } }
+///     ]
+/// }"#).unwrap();
+///
+/// assert_eq!(config.rules.len(), 1);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PermissionsConfig {
+    /// The rules, evaluated together regardless of their order
+    pub rules: Vec<PermissionRule>,
+}
+
+impl PermissionsConfig {
+    /// Appends the rules of another configuration to this one.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The rules to append
+    pub fn merge(&mut self, other: PermissionsConfig) {
+        self.rules.extend(other.rules);
+    }
+}
@@ -0,0 +1,407 @@
+use crate::client::models::ToolCall;
+use crate::permissions::config::{PermissionDecision, PermissionRule};
+use crate::permissions::policy::{escape_pattern, is_compound_command, normalize_path};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::path::Path;
+
+// Commands whose prefix grants are never widened to other arguments
+const DESTRUCTIVE_COMMANDS: [&str; 14] = [
+    "rm",
+    "rmdir",
+    "mv",
+    "dd",
+    "shred",
+    "truncate",
+    "chmod",
+    "chown",
+    "mkfs",
+    "kill",
+    "sudo",
+    "git push",
+    "git reset",
+    "git clean",
+];
+
+// Commands that only read, so running them leaves the files unchanged
+const READ_ONLY_COMMANDS: [&str; 17] = [
+    "ls",
+    "cat",
+    "head",
+    "tail",
+    "wc",
+    "grep",
+    "rg",
+    "pwd",
+    "tree",
+    "stat",
+    "du",
+    "which",
+    "git status",
+    "git diff",
+    "git log",
+    "git show",
+    "git blame",
+];
+
+/// What an "always allow" approval applies to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GrantKind {
+    /// Every call of the tool
+    Tool,
+    /// Calls of the tool whose command starts with the same words
+    CommandPrefix,
+    /// Calls of the tool on the same path
+    Path,
+}
+
+impl GrantKind {
+    /// Returns the kinds of grant that apply to a tool call with the given arguments.
+    ///
+    /// Commands that chain, substitute or redirect other commands get no
+    /// command prefix grant, since the prefix says nothing about the rest.
+    ///
+    /// # Arguments
+    ///
+    /// * `arguments` - The arguments of the tool call
+    pub fn available(arguments: &HashMap<String, String>) -> Vec<GrantKind> {
+        let mut kinds = vec![GrantKind::Tool];
+        if arguments
+            .get("command")
+            .is_some_and(|command| !is_compound_command(command))
+        {
+            kinds.push(GrantKind::CommandPrefix);
+        }
+        if arguments.contains_key("path") {
+            kinds.push(GrantKind::Path);
+        }
+        kinds
+    }
+}
+
+/// Where an "always allow" approval is remembered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GrantScope {
+    /// Until the session ends
+    Session,
+    /// In the project's settings file, for all future sessions
+    Project,
+}
+
+impl fmt::Display for GrantScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GrantScope::Session => write!(f, "session"),
+            GrantScope::Project => write!(f, "project"),
+        }
+    }
+}
+
+/// The user's answer to an approval request.
+///
+/// Event handlers answer `Action::RequestUserApproval` with the string form of
+/// this type, so frontends that only know `approved` and `declined` keep working.
+/// A declined call can carry the user's reason after a colon, as in
+/// `declined:Use the existing helper instead`.
+///
+/// # Examples
+///
+/// ```rust
+/// use code_g::permissions::grant::{ApprovalResponse, GrantKind, GrantScope};
+///
+/// let response = ApprovalResponse::Always {
+///     kind: GrantKind::CommandPrefix,
+///     scope: GrantScope::Project,
+/// };
+///
+/// assert_eq!(response.to_string(), "always:command_prefix:project");
+/// assert_eq!(ApprovalResponse::parse("always:command_prefix:project"), response);
+/// assert_eq!(ApprovalResponse::parse("approved"), ApprovalResponse::Approved);
+/// assert_eq!(
+///     ApprovalResponse::parse("declined:Keep the public API unchanged"),
+///     ApprovalResponse::Declined { reason: Some("Keep the public API unchanged".to_string()) }
+/// );
+/// assert_eq!(ApprovalResponse::parse("anything else"), ApprovalResponse::Declined { reason: None });
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ApprovalResponse {
+    /// Run this call
+    Approved,
+    /// Do not run this call, optionally telling the assistant why
+    Declined { reason: Option<String> },
+    /// Run this call and allow similar calls from now on
+    Always { kind: GrantKind, scope: GrantScope },
+}
+
+impl ApprovalResponse {
+    /// Parses the response of an event handler, treating unknown responses as declined.
+    ///
+    /// # Arguments
+    ///
+    /// * `response` - The response returned by the event handler
+    pub fn parse(response: &str) -> Self {
+        if let Some(reason) = response.strip_prefix("declined:") {
+            let reason = reason.trim();
+            return ApprovalResponse::Declined {
+                reason: (!reason.is_empty()).then(|| reason.to_string()),
+            };
+        }
+
+        let mut parts = response.split(':');
+        match (parts.next(), parts.next(), parts.next(), parts.next()) {
+            (Some("approved"), None, None, None) => ApprovalResponse::Approved,
+            (Some("always"), Some(kind), Some(scope), None) => {
+                let kind = match kind {
+                    "tool" => GrantKind::Tool,
+                    "command_prefix" => GrantKind::CommandPrefix,
+                    "path" => GrantKind::Path,
+                    _ => return ApprovalResponse::Declined { reason: None },
+                };
+                let scope = match scope {
+                    "session" => GrantScope::Session,
+                    "project" => GrantScope::Project,
+                    _ => return ApprovalResponse::Declined { reason: None },
+                };
+                ApprovalResponse::Always { kind, scope }
+            }
+            _ => ApprovalResponse::Declined { reason: None },
+        }
+    }
+}
+
+impl fmt::Display for ApprovalResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApprovalResponse::Approved => write!(f, "approved"),
+            ApprovalResponse::Declined { reason: None } => write!(f, "declined"),
+            ApprovalResponse::Declined {
+                reason: Some(reason),
+            } => write!(f, "declined:{}", reason),
+            ApprovalResponse::Always { kind, scope } => {
+                let kind = match kind {
+                    GrantKind::Tool => "tool",
+                    GrantKind::CommandPrefix => "command_prefix",
+                    GrantKind::Path => "path",
+                };
+                write!(f, "always:{}:{}", kind, scope)
+            }
+        }
+    }
+}
+
+/// Builds the allow rule for an "always allow" approval of a tool call.
+///
+/// A command prefix covers the first two words of the command, so approving
+/// `cargo test --all` allows `cargo test` followed by any arguments, but not
+/// `cargo testx`. Destructive commands such as `rm -rf build` are only allowed
+/// exactly as approved. A path grant allows exactly the approved path, made
+/// relative to the project directory.
+///
+/// # Arguments
+///
+/// * `kind` - What the approval applies to
+/// * `tool_call` - The approved tool call
+/// * `project_dir` - The project directory paths are made relative to, if known
+///
+/// # Returns
+///
+/// The [`PermissionRule`] allowing matching calls, or `None` if the call has no
+/// argument to grant or its command chains, substitutes or redirects others.
+///
+/// # Examples
+///
+/// ```rust
+/// use code_g::client::models::ToolCall;
+/// use code_g::permissions::grant::{GrantKind, grant_rule};
+/// use std::collections::HashMap;
+///
+/// let call = ToolCall {
+///     id: "1".to_string(),
+///     name: "execute_command".to_string(),
+///     arguments: HashMap::from([("command".to_string(), "cargo test --all".to_string())]),
+/// };
+///
+/// let rule = grant_rule(GrantKind::CommandPrefix, &call, None).unwrap();
+/// assert_eq!(rule.to_string(), r#"allow execute_command command="cargo test *""#);
+/// ```
+pub fn grant_rule(
+    kind: GrantKind,
+    tool_call: &ToolCall,
+    project_dir: Option<&Path>,
+) -> Option<PermissionRule> {
+    let mut arguments = BTreeMap::new();
+    match kind {
+        GrantKind::Tool => {}
+        GrantKind::CommandPrefix => {
+            let command = tool_call.arguments.get("command")?;
+            if is_compound_command(command) {
+                return None;
+            }
+            let words: Vec<&str> = command.split_whitespace().collect();
+            let pattern = if is_destructive(&words) {
+                escape_pattern(&words.join(" "))
+            } else {
+                let prefix = words.iter().take(2).copied().collect::<Vec<_>>();
+                if prefix.is_empty() {
+                    return None;
+                }
+                format!("{} *", escape_pattern(&prefix.join(" ")))
+            };
+            arguments.insert("command".to_string(), pattern);
+        }
+        GrantKind::Path => {
+            let path = tool_call.arguments.get("path")?;
+            arguments.insert(
+                "path".to_string(),
+                escape_pattern(&normalize_path(path, project_dir)),
+            );
+        }
+    }
+
+    Some(PermissionRule {
+        decision: PermissionDecision::Allow,
+        tool: tool_call.name.clone(),
+        arguments,
+        reason: None,
+    })
+}
+
+/// Returns `true` if a shell command only reads files.
+///
+/// Commands that chain, substitute or redirect other commands are never
+/// read-only, since the rest of the command can do anything.
+pub(crate) fn is_read_only_command(command: &str) -> bool {
+    let words: Vec<&str> = command.split_whitespace().collect();
+    !is_compound_command(command) && starts_with_any(&words, &READ_ONLY_COMMANDS)
+}
+
+/// Returns `true` if the command's words start with a destructive command.
+fn is_destructive(words: &[&str]) -> bool {
+    starts_with_any(words, &DESTRUCTIVE_COMMANDS)
+}
+
+/// Returns `true` if the command's words start with one of the commands.
+fn starts_with_any(words: &[&str], commands: &[&str]) -> bool {
+    commands.iter().any(|command| {
+        let command: Vec<&str> = command.split(' ').collect();
+        words.starts_with(&command)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(arguments: &[(&str, &str)]) -> ToolCall {
+        ToolCall {
+            id: "1".to_string(),
+            name: "write_file".to_string(),
+            arguments: arguments
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn parse_round_trips_all_responses() {
+        for kind in [GrantKind::Tool, GrantKind::CommandPrefix, GrantKind::Path] {
+            for scope in [GrantScope::Session, GrantScope::Project] {
+                let response = ApprovalResponse::Always { kind, scope };
+                assert_eq!(ApprovalResponse::parse(&response.to_string()), response);
+            }
+        }
+        let declined = ApprovalResponse::Declined {
+            reason: Some("Use a match: it reads better".to_string()),
+        };
+        assert_eq!(ApprovalResponse::parse(&declined.to_string()), declined);
+        assert_eq!(
+            ApprovalResponse::parse("declined"),
+            ApprovalResponse::Declined { reason: None }
+        );
+        assert_eq!(
+            ApprovalResponse::parse("always:tool:forever"),
+            ApprovalResponse::Declined { reason: None }
+        );
+    }
+
+    #[test]
+    fn grant_rule_matches_tool_or_path() {
+        let call = call(&[("path", "src/main.rs"), ("content", "fn main() {}")]);
+
+        assert_eq!(
+            grant_rule(GrantKind::Tool, &call, None)
+                .unwrap()
+                .to_string(),
+            "allow write_file"
+        );
+        assert_eq!(
+            grant_rule(GrantKind::Path, &call, None)
+                .unwrap()
+                .to_string(),
+            r#"allow write_file path="src/main.rs""#
+        );
+    }
+
+    #[test]
+    fn grant_rule_stores_paths_as_literal_project_paths() {
+        let project_dir = Some(Path::new("/home/user/project"));
+        let call = call(&[("path", "/home/user/project/./notes/*?.md")]);
+
+        let rule = grant_rule(GrantKind::Path, &call, project_dir).unwrap();
+
+        assert_eq!(rule.arguments["path"], r"notes/\*\?.md");
+    }
+
+    #[test]
+    fn grant_rule_anchors_command_prefixes_on_words() {
+        let prefix = |command: &str| {
+            grant_rule(
+                GrantKind::CommandPrefix,
+                &call(&[("command", command)]),
+                None,
+            )
+            .map(|rule| rule.arguments["command"].clone())
+        };
+
+        assert_eq!(prefix("ls"), Some("ls *".to_string()));
+        assert_eq!(prefix("cargo test --all"), Some("cargo test *".to_string()));
+        assert_eq!(prefix("rm -rf build"), Some("rm -rf build".to_string()));
+        assert_eq!(
+            prefix("git push --force origin main"),
+            Some("git push --force origin main".to_string())
+        );
+        assert_eq!(prefix("ls && rm -rf ~"), None);
+        assert_eq!(prefix("  "), None);
+    }
+
+    #[test]
+    fn available_offers_grants_for_present_arguments() {
+        assert_eq!(
+            GrantKind::available(&HashMap::from([("command".to_string(), "ls".to_string())])),
+            vec![GrantKind::Tool, GrantKind::CommandPrefix]
+        );
+        assert_eq!(
+            GrantKind::available(&call(&[("path", "a.txt")]).arguments),
+            vec![GrantKind::Tool, GrantKind::Path]
+        );
+        assert_eq!(
+            GrantKind::available(&HashMap::from([(
+                "command".to_string(),
+                "ls | sh".to_string()
+            )])),
+            vec![GrantKind::Tool]
+        );
+    }
+
+    #[test]
+    fn is_read_only_command_only_accepts_simple_reading_commands() {
+        assert!(is_read_only_command("ls -la src"));
+        assert!(is_read_only_command("git diff HEAD~1"));
+        assert!(!is_read_only_command("lsof -i"));
+        assert!(!is_read_only_command("git checkout main"));
+        assert!(!is_read_only_command("cat notes.txt > copy.txt"));
+        assert!(!is_read_only_command("cat notes.txt; rm notes.txt"));
+        assert!(!is_read_only_command(""));
+    }
+}
@@ -0,0 +1,4 @@
+pub mod config;
+pub mod grant;
+pub mod mode;
+pub mod policy;
@@ -0,0 +1,350 @@
+use crate::client::models::ToolCall;
+use crate::permissions::config::PermissionDecision;
+use crate::permissions::policy::PermissionVerdict;
+use crate::tools::registry::{EDIT_TOOLS, READ_ONLY_TOOLS};
+use serde::Deserialize;
+use std::fmt;
+use std::path::{Component, Path};
+use std::str::FromStr;
+
+/// How much the assistant may do without asking, switchable during a session.
+///
+/// Deny rules of the permission policy apply in every mode.
+///
+/// # Examples
+///
+/// ```rust
+/// use code_g::permissions::mode::PermissionMode;
+///
+/// let mode: PermissionMode = "accept-edits".parse().unwrap();
+///
+/// assert_eq!(mode, PermissionMode::AcceptEdits);
+/// assert_eq!(mode.to_string(), "accept-edits");
+/// assert!(PermissionMode::Plan.allows_tool("read_file"));
+/// assert!(!PermissionMode::Plan.allows_tool("write_file"));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PermissionMode {
+    /// Ask for tools that require approval, unless a rule allows them
+    #[default]
+    Default,
+    /// Only read-only tools are available and the assistant is asked for a plan
+    Plan,
+    /// File edits inside the workspace are approved automatically, commands
+    /// still need approval
+    AcceptEdits,
+    /// Tool calls on paths inside the workspace are approved automatically,
+    /// commands still need approval unless a rule allows them
+    FullAuto,
+}
+
+impl PermissionMode {
+    /// All modes, in the order they are listed to the user.
+    pub const ALL: [PermissionMode; 4] = [
+        PermissionMode::Default,
+        PermissionMode::Plan,
+        PermissionMode::AcceptEdits,
+        PermissionMode::FullAuto,
+    ];
+
+    /// Returns `true` if the tool can be offered to the assistant in this mode.
+    ///
+    /// # Arguments
+    ///
+    /// * `tool_name` - The name of the tool
+    pub fn allows_tool(&self, tool_name: &str) -> bool {
+        match self {
+            PermissionMode::Plan => READ_ONLY_TOOLS.contains(&tool_name),
+            _ => true,
+        }
+    }
+
+    /// Returns the mode after this one, used to cycle through the modes.
+    pub fn next(&self) -> PermissionMode {
+        let index = PermissionMode::ALL
+            .iter()
+            .position(|mode| mode == self)
+            .unwrap_or(0);
+        PermissionMode::ALL[(index + 1) % PermissionMode::ALL.len()]
+    }
+
+    /// Returns a note for the assistant describing what the mode allows.
+    pub fn system_note(&self) -> &'static str {
+        match self {
+            PermissionMode::Default => {
+                "The permission mode is now default: tools that change files or run commands need the user's approval."
+            }
+            PermissionMode::Plan => {
+                "Plan mode is active: only read-only tools are available. Investigate the code as needed, then reply with a numbered, step-by-step plan for the user's request. Do not try to modify files or run commands."
+            }
+            PermissionMode::AcceptEdits => {
+                "The permission mode is now accept-edits: file edits inside the workspace are applied without asking, other edits and commands still need the user's approval."
+            }
+            PermissionMode::FullAuto => {
+                "The permission mode is now full-auto: tool calls on files inside the workspace run without asking the user, commands still need the user's approval."
+            }
+        }
+    }
+
+    /// Adjusts the policy's verdict for a tool call to this mode.
+    ///
+    /// Denied calls stay denied. Plan mode denies every tool that is not
+    /// read-only, accept-edits allows file edits inside the workspace, and
+    /// full-auto allows every call whose `path` argument is inside the workspace. Calls without a
+    /// `path` argument, such as commands, can reach anything, so full-auto
+    /// leaves them to the policy.
+    ///
+    /// # Arguments
+    ///
+    /// * `verdict` - The verdict of the permission policy
+    /// * `tool_call` - The tool call requested by the assistant
+    /// * `workspace` - The workspace directory, if known
+    ///
+    /// # Returns
+    ///
+    /// The [`PermissionVerdict`] to apply.
+    pub fn apply(
+        &self,
+        verdict: PermissionVerdict,
+        tool_call: &ToolCall,
+        workspace: Option<&Path>,
+    ) -> PermissionVerdict {
+        if verdict.decision == PermissionDecision::Deny {
+            return verdict;
+        }
+
+        match self {
+            PermissionMode::Plan if !self.allows_tool(&tool_call.name) => PermissionVerdict {
+                decision: PermissionDecision::Deny,
+                reason: format!("{} is not available in plan mode", tool_call.name),
+            },
+            PermissionMode::AcceptEdits
+                if verdict.decision == PermissionDecision::Ask
+                    && EDIT_TOOLS.contains(&tool_call.name.as_str()) =>
+            {
+                match tool_call.arguments.get("path") {
+                    Some(path) if !is_inside_workspace(Path::new(path), workspace) => {
+                        PermissionVerdict {
+                            decision: PermissionDecision::Ask,
+                            reason: format!("{} is outside the workspace", path),
+                        }
+                    }
+                    _ => PermissionVerdict {
+                        decision: PermissionDecision::Allow,
+                        reason: "file edits are accepted in accept-edits mode".to_string(),
+                    },
+                }
+            }
+            PermissionMode::FullAuto if verdict.decision == PermissionDecision::Ask => {
+                match tool_call.arguments.get("path") {
+                    None => PermissionVerdict {
+                        decision: PermissionDecision::Ask,
+                        reason: format!(
+                            "{} is not limited to the workspace, so it needs approval unless a rule allows it",
+                            tool_call.name
+                        ),
+                    },
+                    Some(path) if !is_inside_workspace(Path::new(path), workspace) => {
+                        PermissionVerdict {
+                            decision: PermissionDecision::Ask,
+                            reason: format!("{} is outside the workspace", path),
+                        }
+                    }
+                    Some(_) => PermissionVerdict {
+                        decision: PermissionDecision::Allow,
+                        reason: "everything inside the workspace is approved in full-auto mode"
+                            .to_string(),
+                    },
+                }
+            }
+            _ => verdict,
+        }
+    }
+}
+
+impl fmt::Display for PermissionMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PermissionMode::Default => write!(f, "default"),
+            PermissionMode::Plan => write!(f, "plan"),
+            PermissionMode::AcceptEdits => write!(f, "accept-edits"),
+            PermissionMode::FullAuto => write!(f, "full-auto"),
+        }
+    }
+}
+
+impl FromStr for PermissionMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        PermissionMode::ALL
+            .into_iter()
+            .find(|mode| mode.to_string() == s)
+            .ok_or_else(|| format!("Unknown permission mode '{}'", s))
+    }
+}
+
+/// Returns `true` if a path stays inside the workspace.
+///
+/// Relative paths are resolved against the workspace and may not climb out of
+/// it with `..`. Absolute paths must start with the workspace directory.
+fn is_inside_workspace(path: &Path, workspace: Option<&Path>) -> bool {
+    if path.is_absolute() {
+        return workspace.is_some_and(|workspace| path.starts_with(workspace))
+            && !path.components().any(|c| c == Component::ParentDir);
+    }
+
+    let mut depth = 0usize;
+    for component in path.components() {
+        match component {
+            Component::ParentDir if depth == 0 => return false,
+            Component::ParentDir => depth -= 1,
+            Component::Normal(_) => depth += 1,
+            _ => {}
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn call(name: &str, path: &str) -> ToolCall {
+        ToolCall {
+            id: "1".to_string(),
+            name: name.to_string(),
+            arguments: HashMap::from([("path".to_string(), path.to_string())]),
+        }
+    }
+
+    fn verdict(decision: PermissionDecision) -> PermissionVerdict {
+        PermissionVerdict {
+            decision,
+            reason: "policy".to_string(),
+        }
+    }
+
+    #[test]
+    fn plan_mode_denies_tools_that_are_not_read_only() {
+        let mode = PermissionMode::Plan;
+
+        let read = mode.apply(
+            verdict(PermissionDecision::Allow),
+            &call("read_file", "a"),
+            None,
+        );
+        let write = mode.apply(
+            verdict(PermissionDecision::Ask),
+            &call("write_file", "a"),
+            None,
+        );
+
+        assert_eq!(read.decision, PermissionDecision::Allow);
+        assert_eq!(write.decision, PermissionDecision::Deny);
+        assert_eq!(write.reason, "write_file is not available in plan mode");
+    }
+
+    #[test]
+    fn accept_edits_mode_only_allows_file_edits() {
+        let mode = PermissionMode::AcceptEdits;
+
+        let edit = mode.apply(
+            verdict(PermissionDecision::Ask),
+            &call("edit_file", "a"),
+            None,
+        );
+        let command = mode.apply(
+            verdict(PermissionDecision::Ask),
+            &call("execute_command", "a"),
+            None,
+        );
+        let denied = mode.apply(
+            verdict(PermissionDecision::Deny),
+            &call("edit_file", "a"),
+            None,
+        );
+
+        assert_eq!(edit.decision, PermissionDecision::Allow);
+        assert_eq!(command.decision, PermissionDecision::Ask);
+        assert_eq!(denied.decision, PermissionDecision::Deny);
+    }
+
+    #[test]
+    fn accept_edits_mode_asks_for_edits_outside_the_workspace() {
+        let mode = PermissionMode::AcceptEdits;
+        let workspace = Some(Path::new("/home/user/project"));
+
+        let inside = mode.apply(
+            verdict(PermissionDecision::Ask),
+            &call("write_file", "/home/user/project/src/main.rs"),
+            workspace,
+        );
+        let parent = mode.apply(
+            verdict(PermissionDecision::Ask),
+            &call("edit_file", "../other-repo/src/main.rs"),
+            workspace,
+        );
+        let outside = mode.apply(
+            verdict(PermissionDecision::Ask),
+            &call("write_file", "/etc/hosts"),
+            workspace,
+        );
+
+        assert_eq!(inside.decision, PermissionDecision::Allow);
+        assert_eq!(parent.decision, PermissionDecision::Ask);
+        assert_eq!(outside.decision, PermissionDecision::Ask);
+        assert_eq!(outside.reason, "/etc/hosts is outside the workspace");
+    }
+
+    #[test]
+    fn full_auto_mode_asks_for_paths_outside_the_workspace() {
+        let mode = PermissionMode::FullAuto;
+        let workspace = Some(Path::new("/home/user/project"));
+
+        let inside = mode.apply(
+            verdict(PermissionDecision::Ask),
+            &call("write_file", "src/../README.md"),
+            workspace,
+        );
+        let absolute = mode.apply(
+            verdict(PermissionDecision::Ask),
+            &call("write_file", "/home/user/project/src/main.rs"),
+            workspace,
+        );
+        let parent = mode.apply(
+            verdict(PermissionDecision::Ask),
+            &call("write_file", "../other/main.rs"),
+            workspace,
+        );
+        let outside = mode.apply(
+            verdict(PermissionDecision::Ask),
+            &call("write_file", "/etc/hosts"),
+            workspace,
+        );
+
+        assert_eq!(inside.decision, PermissionDecision::Allow);
+        assert_eq!(absolute.decision, PermissionDecision::Allow);
+        assert_eq!(parent.decision, PermissionDecision::Ask);
+        assert_eq!(outside.decision, PermissionDecision::Ask);
+        assert_eq!(outside.reason, "/etc/hosts is outside the workspace");
+    }
+
+    #[test]
+    fn full_auto_mode_asks_for_commands() {
+        let mode = PermissionMode::FullAuto;
+        let command = ToolCall {
+            id: "1".to_string(),
+            name: "execute_command".to_string(),
+            arguments: HashMap::from([("command".to_string(), "rm -rf ~".to_string())]),
+        };
+
+        let asked = mode.apply(verdict(PermissionDecision::Ask), &command, None);
+        let allowed = mode.apply(verdict(PermissionDecision::Allow), &command, None);
+
+        assert_eq!(asked.decision, PermissionDecision::Ask);
+        assert_eq!(allowed.decision, PermissionDecision::Allow);
+    }
+}
@@ -0,0 +1,32 @@
+use std::io;
+use std::path::PathBuf;
+use thiserror::Error;
+
+/// Represents errors that can occur while loading a conversation script.
+///
+/// # Examples
+///
+/// ```rust
+/// use code_g::script::error::ScriptError;
+/// use std::path::PathBuf;
+///
+/// let error = ScriptError::Parse {
+///     path: PathBuf::from("demo.yaml"),
+///     message: "missing field `steps`".to_string(),
+/// };
+/// assert_eq!(error.to_string(), "Failed to parse script demo.yaml: missing field `steps`");
+/// ```
+#[derive(Error, Debug)]
+pub enum ScriptError {
+    /// The script file could not be read
+    #[error("Failed to read script {}: {source}", path.display())]
+    Read {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+
+    /// The script file is not a valid script
+    #[error("Failed to parse script {}: {message}", path.display())]
+    Parse { path: PathBuf, message: String },
+}
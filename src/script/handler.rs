@@ -0,0 +1,198 @@
+use crate::script::models::{ApprovalAnswer, ExpectedApproval};
+use crate::session::event::{Action, Event, EventHandler};
+use std::collections::VecDeque;
+use std::io;
+use std::sync::{Arc, Mutex};
+
+/// Tool name scripts use for plan approval requests.
+pub const PLAN_APPROVAL_TOOL: &str = "plan";
+
+/// The approval requests a script step expects, and those that went differently.
+#[derive(Debug, Default)]
+pub struct ApprovalScript {
+    expected: VecDeque<ExpectedApproval>,
+    failures: Vec<String>,
+}
+
+impl ApprovalScript {
+    /// Replaces the expected approval requests with those of the next step.
+    ///
+    /// # Arguments
+    ///
+    /// * `approvals` - The approval requests expected during the step, in order
+    pub fn expect(&mut self, approvals: Vec<ExpectedApproval>) {
+        self.expected = approvals.into();
+        self.failures.clear();
+    }
+
+    /// Answers an approval request for the given tool with the next expected answer.
+    ///
+    /// A request that was not expected, or expected for another tool, is
+    /// recorded as a failure and declined.
+    ///
+    /// # Arguments
+    ///
+    /// * `tool_name` - The tool asking for approval
+    ///
+    /// # Returns
+    ///
+    /// `approved` or `declined`.
+    pub fn answer(&mut self, tool_name: &str) -> String {
+        let answer = match self.expected.pop_front() {
+            Some(ExpectedApproval {
+                tool: Some(tool), ..
+            }) if tool != tool_name => {
+                self.failures.push(format!(
+                    "expected an approval request for {}, got one for {}",
+                    tool, tool_name
+                ));
+                ApprovalAnswer::Decline
+            }
+            Some(expected) => expected.answer,
+            None => {
+                self.failures
+                    .push(format!("unexpected approval request for {}", tool_name));
+                ApprovalAnswer::Decline
+            }
+        };
+        match answer {
+            ApprovalAnswer::Approve => "approved".to_string(),
+            ApprovalAnswer::Decline => "declined".to_string(),
+        }
+    }
+
+    /// Ends the step, reporting expected approval requests that never came.
+    ///
+    /// # Returns
+    ///
+    /// The approval requests of the step that did not go as scripted.
+    pub fn finish(&mut self) -> Vec<String> {
+        let mut failures = std::mem::take(&mut self.failures);
+        failures.extend(self.expected.drain(..).map(|expected| match expected.tool {
+            Some(tool) => format!("expected an approval request for {} that never came", tool),
+            None => "expected an approval request that never came".to_string(),
+        }));
+        failures
+    }
+}
+
+/// Event handler for sessions driven by a script.
+///
+/// The `ScriptEventHandler` renders nothing and answers approval requests as
+/// the current script step expects, through an [`ApprovalScript`] shared with
+/// the runner.
+///
+/// # Examples
+///
+/// ```rust
+/// use code_g::script::handler::{ApprovalScript, ScriptEventHandler};
+/// use code_g::script::models::{ApprovalAnswer, ExpectedApproval};
+/// use code_g::session::event::{Action, EventHandler};
+/// use std::collections::HashMap;
+/// use std::sync::{Arc, Mutex};
+///
+/// let approvals = Arc::new(Mutex::new(ApprovalScript::default()));
+/// approvals.lock().unwrap().expect(vec![ExpectedApproval {
+///     tool: Some("execute_command".to_string()),
+///     answer: ApprovalAnswer::Approve,
+/// }]);
+/// let mut handler = ScriptEventHandler::new(approvals.clone());
+///
+/// let response = handler.handle_action(Action::RequestUserApproval {
+///     approval_message: "CodeG wants to execute command 'ls'".to_string(),
+///     tool_name: "execute_command".to_string(),
+///     parameters: HashMap::from([("command".to_string(), "ls".to_string())]),
+///     reason: "execute_command requires approval by default".to_string(),
+/// });
+/// assert_eq!(response.unwrap(), "approved");
+/// assert!(approvals.lock().unwrap().finish().is_empty());
+/// ```
+pub struct ScriptEventHandler {
+    approvals: Arc<Mutex<ApprovalScript>>,
+}
+
+impl ScriptEventHandler {
+    /// Creates a new script event handler.
+    ///
+    /// # Arguments
+    ///
+    /// * `approvals` - The expected approval requests, shared with the runner
+    ///
+    /// # Returns
+    ///
+    /// A new `ScriptEventHandler` instance.
+    pub fn new(approvals: Arc<Mutex<ApprovalScript>>) -> Self {
+        Self { approvals }
+    }
+}
+
+impl EventHandler for ScriptEventHandler {
+    /// Ignores events, the runner reports the outcome of each turn.
+    fn handle_event(&mut self, _event: Event) {}
+
+    /// Answers approval requests as the script expects.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `io::Error` for user input requests, since the script
+    /// sends the user messages itself.
+    fn handle_action(&mut self, action: Action) -> Result<String, io::Error> {
+        match action {
+            Action::RequestUserInput => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "User input is not available when running a script",
+            )),
+            Action::RequestUserApproval { tool_name, .. } => {
+                Ok(self.approvals.lock().unwrap().answer(&tool_name))
+            }
+            Action::RequestPlanApproval { .. } => {
+                Ok(self.approvals.lock().unwrap().answer(PLAN_APPROVAL_TOOL))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expected(tool: Option<&str>, answer: ApprovalAnswer) -> ExpectedApproval {
+        ExpectedApproval {
+            tool: tool.map(str::to_string),
+            answer,
+        }
+    }
+
+    #[test]
+    fn answer_declines_and_records_unexpected_requests() {
+        let mut approvals = ApprovalScript::default();
+        approvals.expect(vec![
+            expected(Some("write_file"), ApprovalAnswer::Approve),
+            expected(None, ApprovalAnswer::Approve),
+        ]);
+
+        assert_eq!(approvals.answer("execute_command"), "declined");
+        assert_eq!(approvals.answer("execute_command"), "approved");
+        assert_eq!(approvals.answer("write_file"), "declined");
+        assert_eq!(
+            approvals.finish(),
+            vec![
+                "expected an approval request for write_file, got one for execute_command"
+                    .to_string(),
+                "unexpected approval request for write_file".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn finish_reports_approvals_that_never_came() {
+        let mut approvals = ApprovalScript::default();
+        approvals.expect(vec![expected(Some("plan"), ApprovalAnswer::Decline)]);
+
+        assert_eq!(
+            approvals.finish(),
+            vec!["expected an approval request for plan that never came".to_string()]
+        );
+        assert!(approvals.finish().is_empty());
+    }
+}
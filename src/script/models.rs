@@ -0,0 +1,361 @@
+use crate::client::models::Usage;
+use crate::headless::models::OutputFormat;
+use crate::permissions::mode::PermissionMode;
+use crate::script::error::ScriptError;
+use crate::session::turn::ToolCallOutcome;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// A conversation to run without user interaction, read from a YAML or JSON file.
+///
+/// Each step sends a user message, answers the approval requests of its turn
+/// as scripted and then checks its assertions.
+///
+/// # Examples
+///
+/// ```rust
+/// use code_g::script::models::{ApprovalAnswer, Assertion, Script};
+///
+/// let script = Script::parse_yaml(
+///     r#"
+/// steps:
+///   - message: Create hello.txt containing "hi"
+///     approvals:
+///       - tool: write_file
+///         answer: approve
+///     assert:
+///       - file_exists: hello.txt
+///       - command_output_contains:
+///           command: cat hello.txt
+///           text: hi
+/// "#,
+/// )
+/// .unwrap();
+///
+/// assert_eq!(script.steps[0].approvals[0].answer, ApprovalAnswer::Approve);
+/// assert_eq!(script.steps[0].assert[0], Assertion::FileExists("hello.txt".to_string()));
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Script {
+    /// Permission mode to run the script in, overriding the settings files
+    #[serde(default)]
+    pub permission_mode: Option<PermissionMode>,
+    /// The steps of the conversation, in order
+    pub steps: Vec<ScriptStep>,
+}
+
+/// A user message of a script with the approvals and checks of its turn.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScriptStep {
+    /// The message sent to the assistant
+    pub message: String,
+    /// The approval requests expected during the turn, in order
+    #[serde(default)]
+    pub approvals: Vec<ExpectedApproval>,
+    /// Checks run after the turn
+    #[serde(default)]
+    pub assert: Vec<Assertion>,
+}
+
+/// An approval request expected during a turn and the answer to give.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExpectedApproval {
+    /// The tool expected to ask for approval, any tool if `None`.
+    /// Plan approvals are requested for the tool `plan`.
+    #[serde(default)]
+    pub tool: Option<String>,
+    /// The answer to give
+    pub answer: ApprovalAnswer,
+}
+
+/// The answer to a scripted approval request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalAnswer {
+    Approve,
+    Decline,
+}
+
+/// A check run after the turn of a step.
+///
+/// Paths are relative to the directory the script runs in, and commands are
+/// run there with the system shell.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum Assertion {
+    /// The file or directory exists
+    FileExists(String),
+    /// The file exists and contains the text
+    FileContains { path: String, text: String },
+    /// The command's output, stdout and stderr, contains the text
+    CommandOutputContains { command: String, text: String },
+    /// The assistant's final message contains the text
+    ResponseContains(String),
+}
+
+impl fmt::Display for Assertion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::FileExists(path) => write!(f, "{} exists", path),
+            Self::FileContains { path, text } => write!(f, "{} contains {:?}", path, text),
+            Self::CommandOutputContains { command, text } => {
+                write!(f, "output of '{}' contains {:?}", command, text)
+            }
+            Self::ResponseContains(text) => write!(f, "response contains {:?}", text),
+        }
+    }
+}
+
+impl Script {
+    /// Loads a script, reading files ending in `.json` as JSON and all others as YAML.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the script file
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ScriptError`] if the file cannot be read or is not a valid script.
+    pub fn load(path: &Path) -> Result<Self, ScriptError> {
+        let content = fs::read_to_string(path).map_err(|source| ScriptError::Read {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let parsed = match path.extension().and_then(|extension| extension.to_str()) {
+            Some("json") => serde_json::from_str(&content).map_err(|e| e.to_string()),
+            _ => Self::parse_yaml(&content),
+        };
+        parsed.map_err(|message| ScriptError::Parse {
+            path: path.to_path_buf(),
+            message,
+        })
+    }
+
+    /// Parses a script written in YAML.
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - The YAML text of the script
+    ///
+    /// # Errors
+    ///
+    /// Returns the parser's message if the text is not a valid script.
+    pub fn parse_yaml(content: &str) -> Result<Self, String> {
+        // serde_yaml reads enums from YAML tags, so assertions written as
+        // single-key maps are read through a JSON value
+        let value: serde_json::Value = serde_yaml::from_str(content).map_err(|e| e.to_string())?;
+        serde_json::from_value(value).map_err(|e| e.to_string())
+    }
+}
+
+/// The outcome of an assertion.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AssertionResult {
+    /// What was checked
+    pub assertion: String,
+    /// Whether the check passed
+    pub passed: bool,
+    /// Why the check failed, if it did
+    pub details: Option<String>,
+}
+
+/// The outcome of a script step.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StepReport {
+    /// The message sent to the assistant
+    pub message: String,
+    /// The assistant's final message, if the turn completed
+    pub response: Option<String>,
+    /// The error that ended the turn, if any
+    pub error: Option<String>,
+    /// The tool calls of the turn
+    pub tool_calls: Vec<ToolCallOutcome>,
+    /// Approval requests that did not go as scripted
+    pub approval_failures: Vec<String>,
+    /// The outcomes of the step's assertions
+    pub assertions: Vec<AssertionResult>,
+}
+
+impl StepReport {
+    /// Returns `true` if the turn completed as scripted and every assertion passed.
+    pub fn passed(&self) -> bool {
+        self.error.is_none()
+            && self.approval_failures.is_empty()
+            && self.assertions.iter().all(|assertion| assertion.passed)
+    }
+}
+
+/// The outcome of a script run.
+///
+/// The run stops at the first step that fails, so later steps are missing
+/// from the report.
+///
+/// # Examples
+///
+/// ```rust
+/// use code_g::client::models::Usage;
+/// use code_g::script::models::ScriptReport;
+///
+/// let report = ScriptReport::new(vec![], 2, Usage::default());
+/// assert_eq!(report.exit_code, ScriptReport::EXIT_FAILED);
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ScriptReport {
+    /// The reports of the steps that ran
+    pub steps: Vec<StepReport>,
+    /// The number of steps in the script
+    pub total_steps: usize,
+    /// The exit status of the run
+    pub exit_code: i32,
+    /// The token usage of the run
+    pub usage: Usage,
+}
+
+impl ScriptReport {
+    /// Exit status for a script whose steps all passed.
+    pub const EXIT_SUCCESS: i32 = 0;
+    /// Exit status for a script with a failed step.
+    pub const EXIT_FAILED: i32 = 1;
+
+    /// Creates a report from the steps that ran.
+    ///
+    /// # Arguments
+    ///
+    /// * `steps` - The reports of the steps that ran
+    /// * `total_steps` - The number of steps in the script
+    /// * `usage` - The token usage of the run
+    pub fn new(steps: Vec<StepReport>, total_steps: usize, usage: Usage) -> Self {
+        let passed = steps.len() == total_steps && steps.iter().all(StepReport::passed);
+        Self {
+            steps,
+            total_steps,
+            exit_code: if passed {
+                Self::EXIT_SUCCESS
+            } else {
+                Self::EXIT_FAILED
+            },
+            usage,
+        }
+    }
+
+    /// Renders the report in the given output format.
+    ///
+    /// The text format lists each step with its response, approval failures
+    /// and assertions, followed by a summary. The JSON format contains the
+    /// whole report.
+    ///
+    /// # Arguments
+    ///
+    /// * `format` - The output format to render
+    pub fn render(&self, format: OutputFormat) -> String {
+        match format {
+            OutputFormat::Text => {
+                let mut lines = Vec::new();
+                for (i, step) in self.steps.iter().enumerate() {
+                    let status = if step.passed() { "passed" } else { "FAILED" };
+                    lines.push(format!("Step {} {}: {}", i + 1, status, step.message));
+                    if let Some(response) = &step.response {
+                        lines.push(format!("  Response: {}", response));
+                    }
+                    if let Some(error) = &step.error {
+                        lines.push(format!("  Error: {}", error));
+                    }
+                    for failure in &step.approval_failures {
+                        lines.push(format!("  Approval: {}", failure));
+                    }
+                    for assertion in &step.assertions {
+                        let mark = if assertion.passed { "ok" } else { "failed" };
+                        let mut line = format!("  [{}] {}", mark, assertion.assertion);
+                        if let Some(details) = &assertion.details {
+                            line.push_str(&format!(": {}", details));
+                        }
+                        lines.push(line);
+                    }
+                }
+                let passed = self.steps.iter().filter(|step| step.passed()).count();
+                lines.push(format!("{} of {} steps passed", passed, self.total_steps));
+                lines.join("\n")
+            }
+            OutputFormat::Json => serde_json::to_string_pretty(self)
+                .unwrap_or_else(|e| format!("{{\"error\": \"{}\"}}", e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(assertions: Vec<AssertionResult>) -> StepReport {
+        StepReport {
+            message: "Create hello.txt".to_string(),
+            response: Some("Done".to_string()),
+            error: None,
+            tool_calls: vec![],
+            approval_failures: vec![],
+            assertions,
+        }
+    }
+
+    #[test]
+    fn parse_yaml_reads_all_assertion_kinds_and_rejects_unknown_fields() {
+        let script = Script::parse_yaml(
+            r#"
+permission_mode: accept-edits
+steps:
+  - message: Hi
+    assert:
+      - file_contains: { path: a.txt, text: a }
+      - response_contains: done
+  - message: Bye
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(script.permission_mode, Some(PermissionMode::AcceptEdits));
+        assert_eq!(
+            script.steps[0].assert,
+            vec![
+                Assertion::FileContains {
+                    path: "a.txt".to_string(),
+                    text: "a".to_string(),
+                },
+                Assertion::ResponseContains("done".to_string()),
+            ]
+        );
+        assert!(script.steps[1].approvals.is_empty());
+        assert!(Script::parse_yaml("steps:\n  - mesage: Hi\n").is_err());
+    }
+
+    #[test]
+    fn render_text_lists_steps_and_failed_assertions() {
+        let report = ScriptReport::new(
+            vec![step(vec![
+                AssertionResult {
+                    assertion: "hello.txt exists".to_string(),
+                    passed: true,
+                    details: None,
+                },
+                AssertionResult {
+                    assertion: "hello.txt contains \"hi\"".to_string(),
+                    passed: false,
+                    details: Some("the file contains \"ho\"".to_string()),
+                },
+            ])],
+            1,
+            Usage::default(),
+        );
+
+        assert_eq!(report.exit_code, ScriptReport::EXIT_FAILED);
+        assert_eq!(
+            report.render(OutputFormat::Text),
+            "Step 1 FAILED: Create hello.txt\n  Response: Done\n  [ok] hello.txt exists\n  \
+             [failed] hello.txt contains \"hi\": the file contains \"ho\"\n0 of 1 steps passed"
+        );
+    }
+}
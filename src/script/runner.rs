@@ -0,0 +1,171 @@
+use crate::client::traits::ChatClient;
+use crate::config::settings::Settings;
+use crate::script::handler::{ApprovalScript, ScriptEventHandler};
+use crate::script::models::{Assertion, AssertionResult, Script, ScriptReport, StepReport};
+use crate::session::budget::TurnBudget;
+use crate::session::builder::ChatSessionBuilder;
+use crate::session::environment::PromptEnvironment;
+use crate::session::system_prompt::SystemPromptConfig;
+use crate::session::verification::DEFAULT_TIMEOUT_SECS;
+use crate::shell::runner::run_shell;
+use crate::tools::traits::ToolRegistry;
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Runs a script through a chat session without user interaction.
+///
+/// Each step sends its message as a turn, answering approval requests as the
+/// step expects, and then checks its assertions in `dir`. The run stops at the
+/// first step whose turn fails, whose approvals do not go as scripted or whose
+/// assertions fail. The script's permission mode takes precedence over the one
+/// of the settings.
+///
+/// # Arguments
+///
+/// * `client` - [`ChatClient`] implementation for API communication
+/// * `tools` - [`ToolRegistry`] containing tools available to the AI assistant
+/// * `system_prompt_config` - [`SystemPromptConfig`] for the initial system prompt
+/// * `script` - The [`Script`] to run
+/// * `budget` - The [`TurnBudget`] limiting each turn
+/// * `settings` - The [`Settings`] with the hooks, permission rules and permission mode to apply
+/// * `dir` - The project directory the session works in and assertions are checked in
+///
+/// # Returns
+///
+/// A [`ScriptReport`] describing the steps that ran.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use code_g::client::providers::openai::client::OpenAIClient;
+/// use code_g::config::settings::Settings;
+/// use code_g::headless::models::OutputFormat;
+/// use code_g::script::models::Script;
+/// use code_g::script::runner::run_script;
+/// use code_g::session::budget::TurnBudget;
+/// use code_g::session::system_prompt::SystemPromptConfig;
+/// use code_g::tools::registry::Registry;
+/// use std::path::Path;
+/// use tokio::runtime::Runtime;
+///
+/// let script = Script::load(Path::new("scripts/hello.yaml")).unwrap();
+/// let rt = Runtime::new().unwrap();
+/// let report = rt.block_on(run_script(
+///     Box::new(OpenAIClient::new("api_key".to_string())),
+///     Box::new(Registry::all_tools()),
+///     SystemPromptConfig::Default,
+///     &script,
+///     TurnBudget::default(),
+///     Settings::default(),
+///     Path::new("."),
+/// ));
+///
+/// println!("{}", report.render(OutputFormat::Text));
+/// std::process::exit(report.exit_code);
+/// ```
+pub async fn run_script(
+    client: Box<dyn ChatClient>,
+    tools: Box<dyn ToolRegistry>,
+    system_prompt_config: SystemPromptConfig,
+    script: &Script,
+    budget: TurnBudget,
+    settings: Settings,
+    dir: &Path,
+) -> ScriptReport {
+    let approvals = Arc::new(Mutex::new(ApprovalScript::default()));
+    let permission_mode = script
+        .permission_mode
+        .or(settings.permission_mode)
+        .unwrap_or_default();
+
+    let mut session = ChatSessionBuilder::new(client)
+        .tools(tools)
+        .event_handler(Box::new(ScriptEventHandler::new(approvals.clone())))
+        .system_prompt(system_prompt_config)
+        .turn_budget(budget)
+        .hooks(settings.hooks)
+        .permissions(settings.permissions)
+        .verification(settings.verification)
+        .permission_mode(permission_mode)
+        .project_dir(dir.to_path_buf())
+        .environment(PromptEnvironment::detect(dir))
+        .build();
+
+    let mut steps = Vec::new();
+    for step in &script.steps {
+        approvals.lock().unwrap().expect(step.approvals.clone());
+        let result = session.send_message(&step.message).await;
+        let approval_failures = approvals.lock().unwrap().finish();
+
+        let report = match result {
+            Ok(turn) => StepReport {
+                assertions: step
+                    .assert
+                    .iter()
+                    .map(|assertion| check(assertion, &turn.message, dir))
+                    .collect(),
+                message: step.message.clone(),
+                response: Some(turn.message),
+                error: None,
+                tool_calls: turn.tool_calls,
+                approval_failures,
+            },
+            Err(e) => StepReport {
+                message: step.message.clone(),
+                response: None,
+                error: Some(e.to_string()),
+                tool_calls: vec![],
+                approval_failures,
+                assertions: vec![],
+            },
+        };
+
+        let passed = report.passed();
+        steps.push(report);
+        if !passed {
+            break;
+        }
+    }
+
+    ScriptReport::new(steps, script.steps.len(), session.usage())
+}
+
+/// Checks an assertion against the directory and the assistant's final message.
+fn check(assertion: &Assertion, response: &str, dir: &Path) -> AssertionResult {
+    let failure = match assertion {
+        Assertion::FileExists(path) => (!dir.join(path).exists()).then(|| "not found".to_string()),
+        Assertion::FileContains { path, text } => match fs::read_to_string(dir.join(path)) {
+            Ok(content) if content.contains(text.as_str()) => None,
+            Ok(content) => Some(format!("the file contains {:?}", content)),
+            Err(e) => Some(format!("could not read the file: {}", e)),
+        },
+        Assertion::CommandOutputContains { command, text } => match command_output(command, dir) {
+            Ok(output) if output.contains(text.as_str()) => None,
+            Ok(output) => Some(format!("the output was {:?}", output)),
+            Err(e) => Some(e),
+        },
+        Assertion::ResponseContains(text) => {
+            (!response.contains(text.as_str())).then(|| format!("the response was {:?}", response))
+        }
+    };
+
+    AssertionResult {
+        assertion: assertion.to_string(),
+        passed: failure.is_none(),
+        details: failure,
+    }
+}
+
+/// Runs a command in the directory with the system shell, returning stdout and stderr.
+fn command_output(command: &str, dir: &Path) -> Result<String, String> {
+    run_shell(
+        command,
+        Some(dir),
+        None,
+        Some(Duration::from_secs(DEFAULT_TIMEOUT_SECS)),
+    )
+    .map(|output| output.combined())
+    .map_err(|e| e.to_string())
+}
@@ -0,0 +1,457 @@
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+
+/// Maximum number of bytes inlined for a single attachment.
+pub const MAX_ATTACHMENT_BYTES: usize = 20_000;
+
+/// Maximum number of bytes inlined for all attachments of a message together.
+pub const MAX_MESSAGE_ATTACHMENT_BYTES: usize = 100_000;
+
+/// Maximum number of entries listed for an attached directory.
+pub const MAX_DIRECTORY_ENTRIES: usize = 200;
+
+// Characters ending a sentence that are not part of a mentioned path
+const TRAILING_PUNCTUATION: [char; 7] = ['.', ',', ';', '!', '?', ')', '"'];
+
+/// What part of the file system an attachment contains.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttachmentKind {
+    /// The whole file
+    File,
+    /// A range of lines of the file, numbered from 1 and inclusive
+    Lines { start: usize, end: usize },
+    /// The entries of a directory
+    Directory,
+}
+
+/// A file, line range or directory mentioned with `@` in a user message.
+///
+/// # Examples
+///
+/// ```rust
+/// use code_g::session::attachment::{Attachment, AttachmentKind};
+///
+/// let attachment = Attachment {
+///     path: "src/main.rs".to_string(),
+///     kind: AttachmentKind::Lines { start: 10, end: 40 },
+///     truncated: false,
+/// };
+///
+/// assert_eq!(attachment.to_string(), "@src/main.rs:10-40");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Attachment {
+    /// The path as written by the user
+    pub path: String,
+    /// What was attached
+    pub kind: AttachmentKind,
+    /// Whether the content was cut short to stay within the size limits
+    pub truncated: bool,
+}
+
+impl fmt::Display for Attachment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            AttachmentKind::Lines { start, end } => write!(f, "@{}:{}-{}", self.path, start, end),
+            _ => write!(f, "@{}", self.path),
+        }
+    }
+}
+
+/// A user message with the content of its `@` mentions attached.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AttachedMessage {
+    /// The message followed by one context block per attachment
+    pub content: String,
+    /// The attachments, in the order they were mentioned
+    pub attachments: Vec<Attachment>,
+}
+
+/// Attaches the files and directories mentioned in a user message.
+///
+/// Mentions start with `@` at the beginning of a word: `@src/main.rs` attaches a
+/// file, `@src/` a directory listing and `@src/main.rs:10-40` or `@src/main.rs:10`
+/// a range of lines. Paths are resolved against the working directory, and
+/// mentions of paths that do not exist, are not regular files or directories,
+/// or cannot be read are left as plain text. A mention repeated with the same
+/// range is attached once. Files are read no further than the size limits, and
+/// once the attachments of the message reach [`MAX_MESSAGE_ATTACHMENT_BYTES`],
+/// further mentions are left as plain text.
+///
+/// # Arguments
+///
+/// * `message` - The message entered by the user
+/// * `cwd` - The directory paths are resolved against
+///
+/// # Returns
+///
+/// The [`AttachedMessage`] to send to the assistant.
+///
+/// # Examples
+///
+/// ```rust
+/// use code_g::session::attachment::attach;
+/// use std::path::Path;
+///
+/// let attached = attach("Explain @Cargo.toml:1-2 please", Path::new(env!("CARGO_MANIFEST_DIR")));
+///
+/// assert_eq!(attached.attachments[0].to_string(), "@Cargo.toml:1-2");
+/// assert!(attached.content.starts_with("Explain @Cargo.toml:1-2 please\n\n<attachment path=\"Cargo.toml\" lines=\"1-2\">\n[package]\n"));
+/// ```
+pub fn attach(message: &str, cwd: &Path) -> AttachedMessage {
+    let mut attachments = Vec::new();
+    let mut blocks = Vec::new();
+    let mut remaining = MAX_MESSAGE_ATTACHMENT_BYTES;
+
+    for word in message.split_whitespace() {
+        let Some(mention) = word.strip_prefix('@') else {
+            continue;
+        };
+        if remaining == 0 {
+            break;
+        }
+        let limit = remaining.min(MAX_ATTACHMENT_BYTES);
+        let Some((attachment, block)) = read_mention(mention, cwd, limit) else {
+            continue;
+        };
+        if !attachments.contains(&attachment) {
+            remaining = remaining.saturating_sub(block.len());
+            attachments.push(attachment);
+            blocks.push(block);
+        }
+    }
+
+    let content = if blocks.is_empty() {
+        message.to_string()
+    } else {
+        format!("{}\n\n{}", message, blocks.join("\n\n"))
+    };
+    AttachedMessage {
+        content,
+        attachments,
+    }
+}
+
+/// Reads a single mention, returning the attachment and its context block.
+///
+/// At most `limit` bytes of a file are read.
+fn read_mention(mention: &str, cwd: &Path, limit: usize) -> Option<(Attachment, String)> {
+    let mention = mention.trim_end_matches(TRAILING_PUNCTUATION);
+    let (path, range) = match mention.rsplit_once(':') {
+        Some((path, range)) => match parse_range(range) {
+            Some(range) => (path, Some(range)),
+            None => (mention, None),
+        },
+        None => (mention, None),
+    };
+    if path.is_empty() {
+        return None;
+    }
+
+    let full_path = cwd.join(path);
+    if full_path.is_dir() && range.is_none() {
+        let (listing, truncated) = list_directory(&full_path)?;
+        let attachment = Attachment {
+            path: path.to_string(),
+            kind: AttachmentKind::Directory,
+            truncated,
+        };
+        let block = format!(
+            "<attachment path=\"{}\" type=\"directory\">\n{}\n</attachment>",
+            path, listing
+        );
+        return Some((attachment, block));
+    }
+
+    // Opening a FIFO or device could block or never end, so only regular files are read
+    if !fs::metadata(&full_path).ok()?.is_file() {
+        return None;
+    }
+    let mut reader = BufReader::new(File::open(&full_path).ok()?);
+    let (kind, bytes, truncated, range_attribute) = match range {
+        Some((start, end)) => {
+            let (bytes, lines, truncated) = read_lines(&mut reader, start, end, limit)?;
+            let end = start + lines.max(1) - 1;
+            (
+                AttachmentKind::Lines { start, end },
+                bytes,
+                truncated,
+                format!(" lines=\"{}-{}\"", start, end),
+            )
+        }
+        None => {
+            let mut bytes = Vec::new();
+            reader
+                .by_ref()
+                .take(limit as u64)
+                .read_to_end(&mut bytes)
+                .ok()?;
+            let truncated = !reader.fill_buf().ok()?.is_empty();
+            (AttachmentKind::File, bytes, truncated, String::new())
+        }
+    };
+
+    let mut content = decode(bytes, truncated)?;
+    if truncated {
+        content = format!(
+            "{}\n... truncated at {} bytes",
+            content.trim_end_matches('\n'),
+            limit
+        );
+    }
+    let attachment = Attachment {
+        path: path.to_string(),
+        kind,
+        truncated,
+    };
+    let block = format!(
+        "<attachment path=\"{}\"{}>\n{}\n</attachment>",
+        path,
+        range_attribute,
+        content.trim_end_matches('\n')
+    );
+    Some((attachment, block))
+}
+
+/// Reads the lines `start` to `end` of a file, numbered from 1 and inclusive.
+///
+/// Lines before the range are skipped without being kept, and at most `limit`
+/// bytes of the range are read.
+///
+/// # Returns
+///
+/// The bytes of the lines, the number of lines read and whether the range was
+/// cut short by the limit, or `None` if the file cannot be read.
+fn read_lines(
+    reader: &mut impl BufRead,
+    start: usize,
+    end: usize,
+    limit: usize,
+) -> Option<(Vec<u8>, usize, bool)> {
+    for _ in 1..start {
+        if reader.skip_until(b'\n').ok()? == 0 {
+            break;
+        }
+    }
+
+    let mut bytes = Vec::new();
+    let mut lines = 0;
+    while lines < end + 1 - start && !reader.fill_buf().ok()?.is_empty() {
+        if bytes.len() >= limit {
+            return Some((bytes, lines, true));
+        }
+        reader
+            .take((limit - bytes.len()) as u64)
+            .read_until(b'\n', &mut bytes)
+            .ok()?;
+        if !bytes.ends_with(b"\n") && !reader.fill_buf().ok()?.is_empty() {
+            return Some((bytes, lines + 1, true));
+        }
+        lines += 1;
+    }
+    Some((bytes, lines, false))
+}
+
+/// Decodes the bytes read from a file, which must be UTF-8 text.
+///
+/// A character cut in half at the end of truncated content is dropped.
+fn decode(bytes: Vec<u8>, truncated: bool) -> Option<String> {
+    match String::from_utf8(bytes) {
+        Ok(content) => Some(content),
+        Err(e) if truncated && e.utf8_error().error_len().is_none() => {
+            let valid = e.utf8_error().valid_up_to();
+            let mut bytes = e.into_bytes();
+            bytes.truncate(valid);
+            String::from_utf8(bytes).ok()
+        }
+        Err(_) => None,
+    }
+}
+
+/// Parses a line range such as `10-40` or a single line such as `10`.
+fn parse_range(range: &str) -> Option<(usize, usize)> {
+    let (start, end) = match range.split_once('-') {
+        Some((start, end)) => (start.parse().ok()?, end.parse().ok()?),
+        None => {
+            let line = range.parse().ok()?;
+            (line, line)
+        }
+    };
+    (start >= 1 && start <= end).then_some((start, end))
+}
+
+/// Lists the entries of a directory, marking subdirectories with a trailing `/`.
+fn list_directory(dir: &Path) -> Option<(String, bool)> {
+    let mut entries: Vec<String> = fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if entry.path().is_dir() {
+                format!("{}/", name)
+            } else {
+                name
+            }
+        })
+        .collect();
+    entries.sort();
+
+    let truncated = entries.len() > MAX_DIRECTORY_ENTRIES;
+    if truncated {
+        let omitted = entries.len() - MAX_DIRECTORY_ENTRIES;
+        entries.truncate(MAX_DIRECTORY_ENTRIES);
+        entries.push(format!("... {} more entries", omitted));
+    }
+    Some((entries.join("\n"), truncated))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::path::PathBuf;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            env::temp_dir().join(format!("code_g_attachment_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("src/tools")).unwrap();
+        fs::write(dir.join("src/main.rs"), "line 1\nline 2\nline 3\nline 4\n").unwrap();
+        dir
+    }
+
+    #[test]
+    fn attach_inlines_files_ranges_and_directories() {
+        let dir = temp_dir("kinds");
+
+        let attached = attach(
+            "Compare @src/main.rs:2-3 with @src/ and @src/main.rs.",
+            &dir,
+        );
+
+        assert_eq!(
+            attached.attachments,
+            vec![
+                Attachment {
+                    path: "src/main.rs".to_string(),
+                    kind: AttachmentKind::Lines { start: 2, end: 3 },
+                    truncated: false,
+                },
+                Attachment {
+                    path: "src/".to_string(),
+                    kind: AttachmentKind::Directory,
+                    truncated: false,
+                },
+                Attachment {
+                    path: "src/main.rs".to_string(),
+                    kind: AttachmentKind::File,
+                    truncated: false,
+                },
+            ]
+        );
+        assert_eq!(
+            attached.content,
+            "Compare @src/main.rs:2-3 with @src/ and @src/main.rs.\n\n\
+             <attachment path=\"src/main.rs\" lines=\"2-3\">\nline 2\nline 3\n</attachment>\n\n\
+             <attachment path=\"src/\" type=\"directory\">\nmain.rs\ntools/\n</attachment>\n\n\
+             <attachment path=\"src/main.rs\">\nline 1\nline 2\nline 3\nline 4\n</attachment>"
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn attach_leaves_unknown_mentions_and_emails_as_text() {
+        let dir = temp_dir("unknown");
+
+        let message = "Ask @alice or mail bob@src/main.rs about @missing.rs";
+        let attached = attach(message, &dir);
+
+        assert_eq!(attached.content, message);
+        assert!(attached.attachments.is_empty());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn attach_clamps_ranges_and_truncates_large_files() {
+        let dir = temp_dir("limits");
+        fs::write(dir.join("big.txt"), "x".repeat(MAX_ATTACHMENT_BYTES + 5)).unwrap();
+
+        let attached = attach("@src/main.rs:3-99 @big.txt", &dir);
+
+        assert_eq!(
+            attached.attachments[0].kind,
+            AttachmentKind::Lines { start: 3, end: 4 }
+        );
+        assert!(attached.attachments[1].truncated);
+        assert!(
+            attached
+                .content
+                .contains(&format!("... truncated at {} bytes", MAX_ATTACHMENT_BYTES))
+        );
+        assert!(
+            !attached
+                .content
+                .contains(&"x".repeat(MAX_ATTACHMENT_BYTES + 1))
+        );
+        assert_eq!(parse_range("0-4"), None);
+        assert_eq!(parse_range("7"), Some((7, 7)));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn attach_limits_the_size_of_all_attachments_of_a_message() {
+        let dir = temp_dir("total");
+        let mut message = String::from("Compare");
+        for n in 1..=6 {
+            fs::write(
+                dir.join(format!("big{}.txt", n)),
+                "x".repeat(MAX_ATTACHMENT_BYTES),
+            )
+            .unwrap();
+            message.push_str(&format!(" @big{}.txt", n));
+        }
+
+        let attached = attach(&message, &dir);
+
+        assert_eq!(attached.attachments.len(), 5);
+        assert!(!attached.attachments[3].truncated);
+        assert!(attached.attachments[4].truncated);
+        // Only the tags and truncation notes of the blocks come on top of the limit
+        assert!(attached.content.len() < message.len() + MAX_MESSAGE_ATTACHMENT_BYTES + 500);
+        assert!(!attached.content.contains("big6.txt\""));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn attach_reads_line_ranges_within_the_limit() {
+        let dir = temp_dir("range_limit");
+        let line = "y".repeat(MAX_ATTACHMENT_BYTES / 2);
+        fs::write(dir.join("wide.txt"), format!("{0}\n{0}\n{0}\n", line)).unwrap();
+
+        let attached = attach("@wide.txt:2-3", &dir);
+
+        assert_eq!(
+            attached.attachments[0],
+            Attachment {
+                path: "wide.txt".to_string(),
+                kind: AttachmentKind::Lines { start: 2, end: 3 },
+                truncated: true,
+            }
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn attach_leaves_mentions_of_special_files_as_text() {
+        let dir = temp_dir("special");
+
+        let attached = attach("Read @/dev/zero", &dir);
+
+        assert_eq!(attached.content, "Read @/dev/zero");
+        assert!(attached.attachments.is_empty());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
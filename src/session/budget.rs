@@ -0,0 +1,369 @@
+use crate::client::models::Usage;
+use serde::Serialize;
+use std::fmt;
+use std::mem::{Discriminant, discriminant};
+use std::time::{Duration, Instant};
+
+/// Fraction of a budget after which a warning is emitted.
+const WARNING_THRESHOLD: f64 = 0.8;
+
+/// Limits that bound the work done in a single turn.
+///
+/// A turn ends gracefully when any of the configured limits is reached: the
+/// assistant is asked for a final summary instead of continuing to call tools.
+/// Limits set to `None` are not enforced.
+///
+/// # Examples
+///
+/// ```rust
+/// use code_g::session::budget::TurnBudget;
+/// use std::time::Duration;
+///
+/// let budget = TurnBudget {
+///     max_iterations: 20,
+///     max_tokens: Some(100_000),
+///     max_cost: Some(0.50),
+///     max_duration: Some(Duration::from_secs(300)),
+/// };
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct TurnBudget {
+    /// Maximum number of requests to the assistant per turn
+    pub max_iterations: usize,
+    /// Maximum number of tokens used per turn
+    pub max_tokens: Option<u64>,
+    /// Maximum cost in US dollars per turn
+    pub max_cost: Option<f64>,
+    /// Maximum wall-clock time per turn
+    pub max_duration: Option<Duration>,
+}
+
+impl Default for TurnBudget {
+    /// Returns a budget of 50 iterations with no token, cost or time limits.
+    fn default() -> Self {
+        Self {
+            max_iterations: 50,
+            max_tokens: None,
+            max_cost: None,
+            max_duration: None,
+        }
+    }
+}
+
+/// A budget limit together with the amount used when it was checked.
+///
+/// # Examples
+///
+/// ```rust
+/// use code_g::session::budget::BudgetLimit;
+///
+/// let limit = BudgetLimit::Iterations { used: 40, max: 50 };
+/// assert_eq!(limit.to_string(), "iterations (40/50)");
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetLimit {
+    /// The iteration limit
+    Iterations { used: usize, max: usize },
+    /// The token limit
+    Tokens { used: u64, max: u64 },
+    /// The cost limit in US dollars
+    Cost { used: f64, max: f64 },
+    /// The wall-clock time limit
+    Duration { used: Duration, max: Duration },
+}
+
+impl BudgetLimit {
+    /// Returns the fraction of the limit that has been used.
+    ///
+    /// A limit of zero counts as used up, even when nothing was used.
+    fn fraction_used(&self) -> f64 {
+        let fraction = match self {
+            BudgetLimit::Iterations { used, max } => *used as f64 / *max as f64,
+            BudgetLimit::Tokens { used, max } => *used as f64 / *max as f64,
+            BudgetLimit::Cost { used, max } => used / max,
+            BudgetLimit::Duration { used, max } => used.as_secs_f64() / max.as_secs_f64(),
+        };
+        if fraction.is_nan() { 1.0 } else { fraction }
+    }
+}
+
+impl fmt::Display for BudgetLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BudgetLimit::Iterations { used, max } => write!(f, "iterations ({}/{})", used, max),
+            BudgetLimit::Tokens { used, max } => write!(f, "tokens ({}/{})", used, max),
+            BudgetLimit::Cost { used, max } => write!(f, "cost (${:.2}/${:.2})", used, max),
+            BudgetLimit::Duration { used, max } => {
+                write!(f, "time ({}s/{}s)", used.as_secs(), max.as_secs())
+            }
+        }
+    }
+}
+
+/// Tracks the resources used by a turn against a [`TurnBudget`].
+///
+/// # Examples
+///
+/// ```rust
+/// use code_g::session::budget::{BudgetLimit, TurnBudget, TurnBudgetTracker};
+///
+/// let mut tracker = TurnBudgetTracker::start(TurnBudget {
+///     max_iterations: 2,
+///     ..TurnBudget::default()
+/// });
+///
+/// tracker.record_iteration();
+/// tracker.record_iteration();
+/// assert_eq!(
+///     tracker.exceeded(),
+///     Some(BudgetLimit::Iterations { used: 2, max: 2 })
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct TurnBudgetTracker {
+    budget: TurnBudget,
+    started: Instant,
+    iterations: usize,
+    tokens: u64,
+    cost: f64,
+    warned: Vec<Discriminant<BudgetLimit>>,
+}
+
+impl TurnBudgetTracker {
+    /// Starts tracking a new turn against the given budget.
+    pub fn start(budget: TurnBudget) -> Self {
+        Self {
+            budget,
+            started: Instant::now(),
+            iterations: 0,
+            tokens: 0,
+            cost: 0.0,
+            warned: vec![],
+        }
+    }
+
+    /// Records that a request to the assistant is being made.
+    pub fn record_iteration(&mut self) {
+        self.iterations += 1;
+    }
+
+    /// Records the usage and cost of a completed request.
+    ///
+    /// # Arguments
+    ///
+    /// * `usage` - The token usage of the request
+    /// * `cost` - The cost of the request in US dollars
+    pub fn record_usage(&mut self, usage: &Usage, cost: f64) {
+        self.tokens += usage.total_tokens;
+        self.cost += cost;
+    }
+
+    /// Returns the part of the budget that is not used yet.
+    ///
+    /// Nested sessions, such as sub-agents, run with the remaining budget so
+    /// they cannot take the turn that started them over its limits.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use code_g::session::budget::{TurnBudget, TurnBudgetTracker};
+    ///
+    /// let mut tracker = TurnBudgetTracker::start(TurnBudget {
+    ///     max_iterations: 10,
+    ///     max_cost: Some(1.0),
+    ///     ..TurnBudget::default()
+    /// });
+    /// tracker.record_iteration();
+    ///
+    /// let remaining = tracker.remaining();
+    /// assert_eq!(remaining.max_iterations, 9);
+    /// assert_eq!(remaining.max_cost, Some(1.0));
+    /// ```
+    pub fn remaining(&self) -> TurnBudget {
+        TurnBudget {
+            max_iterations: self.budget.max_iterations.saturating_sub(self.iterations),
+            max_tokens: self
+                .budget
+                .max_tokens
+                .map(|max| max.saturating_sub(self.tokens)),
+            max_cost: self.budget.max_cost.map(|max| (max - self.cost).max(0.0)),
+            max_duration: self
+                .budget
+                .max_duration
+                .map(|max| max.saturating_sub(self.started.elapsed())),
+        }
+    }
+
+    /// Returns the number of requests made in the turn so far.
+    pub fn iterations(&self) -> usize {
+        self.iterations
+    }
+
+    /// Returns the first limit that has been reached, if any.
+    pub fn exceeded(&self) -> Option<BudgetLimit> {
+        self.limits()
+            .into_iter()
+            .find(|limit| limit.fraction_used() >= 1.0)
+    }
+
+    /// Returns the limits that crossed the warning threshold since the last call.
+    ///
+    /// Each kind of limit is reported at most once per turn.
+    pub fn take_warnings(&mut self) -> Vec<BudgetLimit> {
+        let warnings: Vec<BudgetLimit> = self
+            .limits()
+            .into_iter()
+            .filter(|limit| limit.fraction_used() >= WARNING_THRESHOLD)
+            .filter(|limit| !self.warned.contains(&discriminant(limit)))
+            .collect();
+
+        self.warned.extend(warnings.iter().map(discriminant));
+        warnings
+    }
+
+    /// Returns the configured limits together with their current usage.
+    fn limits(&self) -> Vec<BudgetLimit> {
+        let mut limits = vec![BudgetLimit::Iterations {
+            used: self.iterations,
+            max: self.budget.max_iterations,
+        }];
+
+        if let Some(max) = self.budget.max_tokens {
+            limits.push(BudgetLimit::Tokens {
+                used: self.tokens,
+                max,
+            });
+        }
+        if let Some(max) = self.budget.max_cost {
+            limits.push(BudgetLimit::Cost {
+                used: self.cost,
+                max,
+            });
+        }
+        if let Some(max) = self.budget.max_duration {
+            limits.push(BudgetLimit::Duration {
+                used: self.started.elapsed(),
+                max,
+            });
+        }
+
+        limits
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(total_tokens: u64) -> Usage {
+        Usage {
+            prompt_tokens: total_tokens,
+            completion_tokens: 0,
+            total_tokens,
+        }
+    }
+
+    #[test]
+    fn exceeded_returns_none_within_budget() {
+        let mut tracker = TurnBudgetTracker::start(TurnBudget::default());
+        tracker.record_iteration();
+
+        assert_eq!(tracker.exceeded(), None);
+    }
+
+    #[test]
+    fn exceeded_returns_token_limit_when_reached() {
+        let mut tracker = TurnBudgetTracker::start(TurnBudget {
+            max_tokens: Some(100),
+            ..TurnBudget::default()
+        });
+        tracker.record_usage(&usage(60), 0.0);
+        tracker.record_usage(&usage(40), 0.0);
+
+        assert_eq!(
+            tracker.exceeded(),
+            Some(BudgetLimit::Tokens {
+                used: 100,
+                max: 100
+            })
+        );
+    }
+
+    #[test]
+    fn exceeded_returns_duration_limit_when_elapsed() {
+        let tracker = TurnBudgetTracker::start(TurnBudget {
+            max_duration: Some(Duration::ZERO),
+            ..TurnBudget::default()
+        });
+
+        assert!(matches!(
+            tracker.exceeded(),
+            Some(BudgetLimit::Duration { .. })
+        ));
+    }
+
+    #[test]
+    fn exceeded_returns_limits_of_zero() {
+        let tracker = TurnBudgetTracker::start(TurnBudget {
+            max_iterations: 0,
+            ..TurnBudget::default()
+        });
+
+        assert_eq!(
+            tracker.exceeded(),
+            Some(BudgetLimit::Iterations { used: 0, max: 0 })
+        );
+    }
+
+    #[test]
+    fn take_warnings_reports_each_limit_once_past_threshold() {
+        let mut tracker = TurnBudgetTracker::start(TurnBudget {
+            max_iterations: 10,
+            max_cost: Some(1.0),
+            ..TurnBudget::default()
+        });
+
+        for _ in 0..7 {
+            tracker.record_iteration();
+        }
+        tracker.record_usage(&usage(10), 0.5);
+        assert!(tracker.take_warnings().is_empty());
+
+        tracker.record_iteration();
+        tracker.record_usage(&usage(10), 0.3);
+        assert_eq!(
+            tracker.take_warnings(),
+            vec![
+                BudgetLimit::Iterations { used: 8, max: 10 },
+                BudgetLimit::Cost {
+                    used: 0.8,
+                    max: 1.0
+                },
+            ]
+        );
+
+        tracker.record_iteration();
+        assert!(tracker.take_warnings().is_empty());
+    }
+
+    #[test]
+    fn budget_limit_display_describes_usage() {
+        assert_eq!(
+            BudgetLimit::Cost {
+                used: 0.4,
+                max: 0.5
+            }
+            .to_string(),
+            "cost ($0.40/$0.50)"
+        );
+        assert_eq!(
+            BudgetLimit::Duration {
+                used: Duration::from_secs(240),
+                max: Duration::from_secs(300)
+            }
+            .to_string(),
+            "time (240s/300s)"
+        );
+    }
+}
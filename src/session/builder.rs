@@ -0,0 +1,233 @@
+use crate::client::models::Model;
+use crate::client::traits::ChatClient;
+use crate::headless::handler::HeadlessEventHandler;
+use crate::headless::models::ApprovalPolicy;
+use crate::hooks::config::HooksConfig;
+use crate::permissions::config::PermissionsConfig;
+use crate::permissions::mode::PermissionMode;
+use crate::session::budget::TurnBudget;
+use crate::session::environment::PromptEnvironment;
+use crate::session::event::EventHandler;
+use crate::session::input_queue::InputQueue;
+use crate::session::output_limit::DEFAULT_MAX_OUTPUT_TOKENS;
+use crate::session::session::ChatSession;
+use crate::session::system_prompt::SystemPromptConfig;
+use crate::session::verification::VerificationConfig;
+use crate::tools::registry::Registry;
+use crate::tools::traits::ToolRegistry;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+/// Builder for a [`ChatSession`] with named, optional settings.
+///
+/// Only the chat client is required. Without further settings the session has
+/// no tools, uses the default system prompt, model, turn budget and permission
+/// rules, and runs without a frontend: events are dropped and tool calls that
+/// need approval are declined. Pass an [`EventHandler`] to ask a user instead.
+///
+/// # Examples
+///
+/// ```rust
+/// use code_g::client::models::Model;
+/// use code_g::client::providers::openai::client::OpenAIClient;
+/// use code_g::client::providers::openai::schema::Model as OpenAiModel;
+/// use code_g::permissions::mode::PermissionMode;
+/// use code_g::session::budget::TurnBudget;
+/// use code_g::session::builder::ChatSessionBuilder;
+/// use code_g::session::system_prompt::SystemPromptConfig;
+/// use code_g::tools::registry::Registry;
+///
+/// let session = ChatSessionBuilder::new(Box::new(OpenAIClient::new("api_key".to_string())))
+///     .model(Model::OpenAi(OpenAiModel::Gpt4o))
+///     .tools(Box::new(Registry::all_tools()))
+///     .system_prompt(SystemPromptConfig::Custom("You review Rust code.".to_string()))
+///     .turn_budget(TurnBudget::default())
+///     .permission_mode(PermissionMode::AcceptEdits)
+///     .build();
+/// ```
+pub struct ChatSessionBuilder {
+    client: Box<dyn ChatClient>,
+    tools: Box<dyn ToolRegistry>,
+    event_handler: Option<Box<dyn EventHandler>>,
+    system_prompt: SystemPromptConfig,
+    model: Option<Model>,
+    turn_budget: TurnBudget,
+    hooks: HooksConfig,
+    permissions: PermissionsConfig,
+    permission_mode: PermissionMode,
+    project_dir: Option<PathBuf>,
+    environment: Option<PromptEnvironment>,
+    sub_agents: bool,
+    todos: bool,
+    output_limit: usize,
+    input_queue: Option<InputQueue>,
+    verification: VerificationConfig,
+    editor: Option<(Box<dyn ChatClient>, Model)>,
+}
+
+impl ChatSessionBuilder {
+    /// Creates a builder for a session using the given chat client.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - [`ChatClient`] implementation for API communication
+    pub fn new(client: Box<dyn ChatClient>) -> Self {
+        Self {
+            client,
+            tools: Box::new(Registry::new()),
+            event_handler: None,
+            system_prompt: SystemPromptConfig::Default,
+            model: None,
+            turn_budget: TurnBudget::default(),
+            hooks: HooksConfig::default(),
+            permissions: PermissionsConfig::default(),
+            permission_mode: PermissionMode::default(),
+            project_dir: None,
+            environment: None,
+            sub_agents: false,
+            todos: false,
+            output_limit: DEFAULT_MAX_OUTPUT_TOKENS,
+            input_queue: None,
+            verification: VerificationConfig::default(),
+            editor: None,
+        }
+    }
+
+    /// Sets the tools available to the assistant.
+    pub fn tools(mut self, tools: Box<dyn ToolRegistry>) -> Self {
+        self.tools = tools;
+        self
+    }
+
+    /// Sets the frontend that receives events and answers approval and input requests.
+    pub fn event_handler(mut self, event_handler: Box<dyn EventHandler>) -> Self {
+        self.event_handler = Some(event_handler);
+        self
+    }
+
+    /// Sets the initial system prompt.
+    pub fn system_prompt(mut self, system_prompt: SystemPromptConfig) -> Self {
+        self.system_prompt = system_prompt;
+        self
+    }
+
+    /// Sets the model used for chat completions.
+    pub fn model(mut self, model: Model) -> Self {
+        self.model = Some(model);
+        self
+    }
+
+    /// Sets the limits on the work done per turn.
+    pub fn turn_budget(mut self, turn_budget: TurnBudget) -> Self {
+        self.turn_budget = turn_budget;
+        self
+    }
+
+    /// Sets the hooks run on lifecycle events such as tool calls.
+    pub fn hooks(mut self, hooks: HooksConfig) -> Self {
+        self.hooks = hooks;
+        self
+    }
+
+    /// Sets the rules deciding which tool calls run, need approval or are denied.
+    pub fn permissions(mut self, permissions: PermissionsConfig) -> Self {
+        self.permissions = permissions;
+        self
+    }
+
+    /// Sets the permission mode the session starts in.
+    pub fn permission_mode(mut self, permission_mode: PermissionMode) -> Self {
+        self.permission_mode = permission_mode;
+        self
+    }
+
+    /// Sets the project directory that grants are saved to and `@` mentions are resolved against.
+    pub fn project_dir(mut self, project_dir: PathBuf) -> Self {
+        self.project_dir = Some(project_dir);
+        self
+    }
+
+    /// Describes the environment the assistant operates in in the system prompt.
+    pub fn environment(mut self, environment: PromptEnvironment) -> Self {
+        self.environment = Some(environment);
+        self
+    }
+
+    /// Sets whether the assistant can delegate read-only tasks to sub-agents.
+    pub fn sub_agents(mut self, enabled: bool) -> Self {
+        self.sub_agents = enabled;
+        self
+    }
+
+    /// Sets whether the assistant can keep a todo list.
+    pub fn todos(mut self, enabled: bool) -> Self {
+        self.todos = enabled;
+        self
+    }
+
+    /// Sets the limit on the size of a tool output added to the conversation, in tokens.
+    pub fn output_limit(mut self, max_tokens: usize) -> Self {
+        self.output_limit = max_tokens;
+        self
+    }
+
+    /// Sets the queue of messages the user types while the assistant is working.
+    pub fn input_queue(mut self, input_queue: InputQueue) -> Self {
+        self.input_queue = Some(input_queue);
+        self
+    }
+
+    /// Sets the check command run after turns that modified files.
+    pub fn verification(mut self, verification: VerificationConfig) -> Self {
+        self.verification = verification;
+        self
+    }
+
+    /// Pairs the assistant with an editor model that applies its file changes.
+    pub fn editor(mut self, client: Box<dyn ChatClient>, model: Model) -> Self {
+        self.editor = Some((client, model));
+        self
+    }
+
+    /// Builds the session.
+    ///
+    /// # Returns
+    ///
+    /// A new [`ChatSession`] with the configured settings.
+    pub fn build(self) -> ChatSession {
+        let event_handler = self.event_handler.unwrap_or_else(|| {
+            Box::new(HeadlessEventHandler::new(
+                ApprovalPolicy::Deny,
+                Arc::new(Mutex::new(vec![])),
+            ))
+        });
+
+        let mut session =
+            ChatSession::new(self.client, self.tools, event_handler, self.system_prompt)
+                .with_turn_budget(self.turn_budget)
+                .with_hooks(self.hooks)
+                .with_permissions(self.permissions)
+                .with_permission_mode(self.permission_mode)
+                .with_sub_agents(self.sub_agents)
+                .with_todos(self.todos)
+                .with_output_limit(self.output_limit)
+                .with_verification(self.verification);
+        if let Some(model) = self.model {
+            session = session.with_model(model);
+        }
+        if let Some(project_dir) = self.project_dir {
+            session = session.with_project_dir(project_dir);
+        }
+        if let Some(input_queue) = self.input_queue {
+            session = session.with_input_queue(input_queue);
+        }
+        if let Some((client, model)) = self.editor {
+            session = session.with_editor(client, model);
+        }
+        // The environment lists the tools, so it is described once they are all set
+        if let Some(environment) = self.environment {
+            session = session.with_environment(environment);
+        }
+        session
+    }
+}
@@ -0,0 +1,501 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Represents errors that can occur when restoring file checkpoints.
+///
+/// # Examples
+///
+/// ```rust
+/// use code_g::session::checkpoint::CheckpointError;
+///
+/// let error = CheckpointError::NotFound { id: 3 };
+/// assert_eq!(error.to_string(), "Checkpoint 3 not found");
+/// ```
+#[derive(Error, Debug)]
+pub enum CheckpointError {
+    /// There are no checkpoints to restore
+    #[error("No file changes to undo")]
+    NoCheckpoints,
+
+    /// The requested checkpoint does not exist
+    #[error("Checkpoint {id} not found")]
+    NotFound { id: usize },
+
+    /// A file could not be restored to its snapshot
+    #[error("Failed to restore '{path}': {source}")]
+    Io {
+        path: String,
+        #[source]
+        source: io::Error,
+    },
+}
+
+/// The content of a single file captured before a tool modified it.
+///
+/// A `content` of `None` means the file did not exist when the snapshot was
+/// taken, so restoring the snapshot removes the file again.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileSnapshot {
+    pub path: PathBuf,
+    pub content: Option<Vec<u8>>,
+}
+
+impl FileSnapshot {
+    /// Captures the current content of the file at `path`.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file to snapshot
+    ///
+    /// # Returns
+    ///
+    /// A snapshot of the file, with `content` set to `None` if the file does not exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `io::Error` if the file exists but cannot be read.
+    pub fn capture(path: &Path) -> Result<Self, io::Error> {
+        let content = match fs::read(path) {
+            Ok(content) => Some(content),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e),
+        };
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            content,
+        })
+    }
+
+    /// Writes the snapshot back to disk, removing the file if it did not exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`CheckpointError::Io`] if the file cannot be written or removed.
+    pub fn restore(&self) -> Result<(), CheckpointError> {
+        let result = match &self.content {
+            Some(content) => fs::write(&self.path, content),
+            None => match fs::remove_file(&self.path) {
+                Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+                other => other,
+            },
+        };
+
+        result.map_err(|source| CheckpointError::Io {
+            path: self.path.display().to_string(),
+            source,
+        })
+    }
+}
+
+/// The pre-modification snapshots of every file touched by tools during one turn.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Checkpoint {
+    /// Sequential identifier of the checkpoint, starting at 1
+    pub id: usize,
+    /// The turn the checkpoint belongs to
+    pub turn: usize,
+    /// The user message that started the turn
+    pub prompt: String,
+    /// Snapshots of the files as they were before the turn modified them
+    pub snapshots: Vec<FileSnapshot>,
+}
+
+impl Checkpoint {
+    /// Returns a lightweight summary of the checkpoint suitable for display.
+    pub fn summary(&self) -> CheckpointSummary {
+        CheckpointSummary {
+            id: self.id,
+            prompt: self.prompt.clone(),
+            paths: self
+                .snapshots
+                .iter()
+                .map(|snapshot| snapshot.path.display().to_string())
+                .collect(),
+        }
+    }
+}
+
+/// A display-friendly description of a checkpoint.
+///
+/// # Examples
+///
+/// ```rust
+/// use code_g::session::checkpoint::CheckpointSummary;
+///
+/// let summary = CheckpointSummary {
+///     id: 1,
+///     prompt: "Rename the config module".to_string(),
+///     paths: vec!["src/config.rs".to_string()],
+/// };
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct CheckpointSummary {
+    pub id: usize,
+    pub prompt: String,
+    pub paths: Vec<String>,
+}
+
+/// Stores file checkpoints for a chat session.
+///
+/// The store captures the original content of every file a tool is about to
+/// modify, grouped by the turn in which the modification happened. Only the
+/// first snapshot of a file per turn is kept, so restoring a checkpoint returns
+/// the files to the state they were in before the turn started. Checkpoints are
+/// kept in memory and do not depend on git, so they work in any directory.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use code_g::session::checkpoint::CheckpointStore;
+/// use std::path::Path;
+///
+/// let mut store = CheckpointStore::new();
+/// store.begin_turn("Update the readme");
+/// store.snapshot(Path::new("README.md")).unwrap();
+///
+/// // ... a tool modifies README.md ...
+///
+/// let restored = store.undo().unwrap();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CheckpointStore {
+    checkpoints: Vec<Checkpoint>,
+    turn: usize,
+    prompt: String,
+    // Id of the latest checkpoint, never handed out again after a restore
+    last_id: usize,
+}
+
+impl CheckpointStore {
+    /// Creates a new empty checkpoint store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Marks the start of a new turn.
+    ///
+    /// Snapshots taken after this call are grouped into a new checkpoint.
+    ///
+    /// # Arguments
+    ///
+    /// * `prompt` - The user message that started the turn
+    pub fn begin_turn(&mut self, prompt: &str) {
+        self.turn += 1;
+        self.prompt = prompt.to_string();
+    }
+
+    /// Captures the content of a file before it is modified in the current turn.
+    ///
+    /// Files already captured in the current turn are ignored so the checkpoint
+    /// always holds the content from before the turn started.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file that is about to be modified
+    ///
+    /// # Errors
+    ///
+    /// Returns an `io::Error` if the file exists but cannot be read.
+    pub fn snapshot(&mut self, path: &Path) -> Result<(), io::Error> {
+        if self.is_captured(path) {
+            return Ok(());
+        }
+
+        let snapshot = FileSnapshot::capture(path)?;
+        self.add(snapshot);
+
+        Ok(())
+    }
+
+    /// Moves the snapshots of another store into the current turn.
+    ///
+    /// Used for changes made by a nested session, such as the editor, so they
+    /// are undone together with the turn that started it. Files already
+    /// captured in the current turn keep their earlier snapshot.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The store whose snapshots to take over
+    pub fn absorb(&mut self, other: CheckpointStore) {
+        for snapshot in other
+            .checkpoints
+            .into_iter()
+            .flat_map(|checkpoint| checkpoint.snapshots)
+        {
+            if !self.is_captured(&snapshot.path) {
+                self.add(snapshot);
+            }
+        }
+    }
+
+    /// Returns whether the file was already captured in the current turn.
+    fn is_captured(&self, path: &Path) -> bool {
+        self.checkpoints.last().is_some_and(|checkpoint| {
+            checkpoint.turn == self.turn && checkpoint.snapshots.iter().any(|s| s.path == path)
+        })
+    }
+
+    /// Adds a snapshot to the checkpoint of the current turn, creating it if needed.
+    fn add(&mut self, snapshot: FileSnapshot) {
+        let turn = self.turn;
+        match self.checkpoints.last_mut() {
+            Some(checkpoint) if checkpoint.turn == turn => checkpoint.snapshots.push(snapshot),
+            _ => {
+                self.last_id += 1;
+                self.checkpoints.push(Checkpoint {
+                    id: self.last_id,
+                    turn,
+                    prompt: self.prompt.clone(),
+                    snapshots: vec![snapshot],
+                });
+            }
+        }
+    }
+
+    /// Returns summaries of all stored checkpoints, oldest first.
+    pub fn list(&self) -> Vec<CheckpointSummary> {
+        self.checkpoints.iter().map(Checkpoint::summary).collect()
+    }
+
+    /// Reverts the file changes made in the most recent turn that modified files.
+    ///
+    /// # Returns
+    ///
+    /// The summary of the checkpoint that was restored.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CheckpointError::NoCheckpoints`] if there is nothing to undo, or
+    /// [`CheckpointError::Io`] if a file could not be restored.
+    pub fn undo(&mut self) -> Result<CheckpointSummary, CheckpointError> {
+        let id = self
+            .checkpoints
+            .last()
+            .map(|checkpoint| checkpoint.id)
+            .ok_or(CheckpointError::NoCheckpoints)?;
+        self.restore(id)
+    }
+
+    /// Restores the files to the state they were in before checkpoint `id`.
+    ///
+    /// All checkpoints taken after `id` are reverted as well, newest first, and
+    /// removed from the store together with checkpoint `id`.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The identifier of the checkpoint to restore
+    ///
+    /// # Returns
+    ///
+    /// The summary of the checkpoint that was restored.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CheckpointError::NotFound`] if no checkpoint has the given id, or
+    /// [`CheckpointError::Io`] if a file could not be restored.
+    pub fn restore(&mut self, id: usize) -> Result<CheckpointSummary, CheckpointError> {
+        let index = self
+            .checkpoints
+            .iter()
+            .position(|checkpoint| checkpoint.id == id)
+            .ok_or(CheckpointError::NotFound { id })?;
+
+        while self.checkpoints.len() > index {
+            let checkpoint = self.checkpoints.last().expect("checkpoint exists");
+            for snapshot in checkpoint.snapshots.iter().rev() {
+                snapshot.restore()?;
+            }
+            let checkpoint = self.checkpoints.pop().expect("checkpoint exists");
+            if checkpoint.id == id {
+                return Ok(checkpoint.summary());
+            }
+        }
+
+        Err(CheckpointError::NotFound { id })
+    }
+
+    /// Returns the number of stored checkpoints.
+    pub fn len(&self) -> usize {
+        self.checkpoints.len()
+    }
+
+    /// Returns whether the store holds no checkpoints.
+    pub fn is_empty(&self) -> bool {
+        self.checkpoints.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn create_temp_dir() -> PathBuf {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let temp_dir = std::env::temp_dir().join(format!(
+            "code_g_checkpoint_test_{}_{}",
+            std::process::id(),
+            nanos
+        ));
+        fs::create_dir_all(&temp_dir).unwrap();
+        temp_dir
+    }
+
+    #[test]
+    fn snapshot_ignores_files_already_captured_in_the_same_turn() {
+        let temp_dir = create_temp_dir();
+        let path = temp_dir.join("file.txt");
+        fs::write(&path, "original").unwrap();
+
+        let mut store = CheckpointStore::new();
+        store.begin_turn("edit twice");
+        store.snapshot(&path).unwrap();
+        fs::write(&path, "first edit").unwrap();
+        store.snapshot(&path).unwrap();
+
+        assert_eq!(store.len(), 1);
+        assert_eq!(
+            store.checkpoints[0].snapshots,
+            vec![FileSnapshot {
+                path: path.clone(),
+                content: Some(b"original".to_vec()),
+            }]
+        );
+
+        fs::remove_dir_all(temp_dir).ok();
+    }
+
+    #[test]
+    fn absorb_adds_snapshots_of_files_not_yet_captured_to_the_current_turn() {
+        let temp_dir = create_temp_dir();
+        let shared = temp_dir.join("shared.txt");
+        let other = temp_dir.join("other.txt");
+        fs::write(&shared, "original").unwrap();
+
+        let mut store = CheckpointStore::new();
+        store.begin_turn("refactor");
+        store.snapshot(&shared).unwrap();
+        fs::write(&shared, "architect edit").unwrap();
+
+        let mut nested = CheckpointStore::new();
+        nested.begin_turn("apply changes");
+        nested.snapshot(&shared).unwrap();
+        nested.snapshot(&other).unwrap();
+        store.absorb(nested);
+
+        assert_eq!(store.len(), 1);
+        assert_eq!(
+            store.checkpoints[0].snapshots,
+            vec![
+                FileSnapshot {
+                    path: shared.clone(),
+                    content: Some(b"original".to_vec()),
+                },
+                FileSnapshot {
+                    path: other.clone(),
+                    content: None,
+                },
+            ]
+        );
+
+        fs::remove_dir_all(temp_dir).ok();
+    }
+
+    #[test]
+    fn undo_restores_modified_files_and_removes_created_files() {
+        let temp_dir = create_temp_dir();
+        let existing = temp_dir.join("existing.txt");
+        let created = temp_dir.join("created.txt");
+        fs::write(&existing, "original").unwrap();
+
+        let mut store = CheckpointStore::new();
+        store.begin_turn("change files");
+        store.snapshot(&existing).unwrap();
+        store.snapshot(&created).unwrap();
+        fs::write(&existing, "changed").unwrap();
+        fs::write(&created, "new").unwrap();
+
+        let summary = store.undo().unwrap();
+
+        assert_eq!(summary.id, 1);
+        assert_eq!(summary.prompt, "change files");
+        assert_eq!(fs::read_to_string(&existing).unwrap(), "original");
+        assert!(!created.exists());
+        assert!(store.is_empty());
+
+        fs::remove_dir_all(temp_dir).ok();
+    }
+
+    #[test]
+    fn restore_reverts_later_checkpoints_as_well() {
+        let temp_dir = create_temp_dir();
+        let path = temp_dir.join("file.txt");
+        fs::write(&path, "v1").unwrap();
+
+        let mut store = CheckpointStore::new();
+        store.begin_turn("first");
+        store.snapshot(&path).unwrap();
+        fs::write(&path, "v2").unwrap();
+        store.begin_turn("second");
+        store.snapshot(&path).unwrap();
+        fs::write(&path, "v3").unwrap();
+
+        store.restore(1).unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "v1");
+        assert!(store.is_empty());
+
+        fs::remove_dir_all(temp_dir).ok();
+    }
+
+    #[test]
+    fn checkpoints_after_a_restore_get_new_ids() {
+        let temp_dir = create_temp_dir();
+        let path = temp_dir.join("file.txt");
+        fs::write(&path, "v1").unwrap();
+
+        let mut store = CheckpointStore::new();
+        store.begin_turn("first");
+        store.snapshot(&path).unwrap();
+        store.begin_turn("second");
+        store.snapshot(&path).unwrap();
+        store.restore(2).unwrap();
+        store.begin_turn("third");
+        store.snapshot(&path).unwrap();
+
+        let ids: Vec<usize> = store.list().iter().map(|summary| summary.id).collect();
+        assert_eq!(ids, vec![1, 3]);
+        assert!(matches!(
+            store.restore(2),
+            Err(CheckpointError::NotFound { id: 2 })
+        ));
+
+        fs::remove_dir_all(temp_dir).ok();
+    }
+
+    #[test]
+    fn turns_without_snapshots_do_not_create_checkpoints() {
+        let mut store = CheckpointStore::new();
+        store.begin_turn("just chatting");
+        store.begin_turn("still chatting");
+
+        assert!(store.is_empty());
+        assert!(matches!(store.undo(), Err(CheckpointError::NoCheckpoints)));
+    }
+
+    #[test]
+    fn restore_returns_error_for_unknown_checkpoint() {
+        let mut store = CheckpointStore::new();
+
+        assert!(matches!(
+            store.restore(7),
+            Err(CheckpointError::NotFound { id: 7 })
+        ));
+    }
+}
@@ -0,0 +1,211 @@
+use crate::permissions::mode::PermissionMode;
+use crate::session::transcript::TranscriptFormat;
+use std::path::PathBuf;
+use thiserror::Error;
+
+/// Represents errors that can occur when parsing a slash command.
+///
+/// # Examples
+///
+/// ```rust
+/// use code_g::session::command::CommandError;
+///
+/// let error = CommandError::InvalidArguments { command: "undo".to_string(), usage: "/undo" };
+/// assert_eq!(error.to_string(), "Invalid arguments for /undo, usage: /undo");
+/// ```
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum CommandError {
+    /// A known command was given missing, extra or invalid arguments
+    #[error("Invalid arguments for /{command}, usage: {usage}")]
+    InvalidArguments {
+        command: String,
+        usage: &'static str,
+    },
+}
+
+/// Slash commands that can be entered instead of a chat message.
+///
+/// Commands are handled by the [`ChatSession`](crate::session::session::ChatSession)
+/// itself and are never sent to the assistant.
+///
+/// # Examples
+///
+/// ```rust
+/// use code_g::session::command::Command;
+///
+/// assert_eq!(Command::parse("/undo"), Ok(Some(Command::Undo)));
+/// assert_eq!(
+///     Command::parse("/checkpoints 2"),
+///     Ok(Some(Command::Checkpoints { restore: Some(2) }))
+/// );
+/// assert_eq!(Command::parse("Hello"), Ok(None));
+/// assert!(Command::parse("/mode yolo").is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    /// Revert the file changes made in the last turn that modified files
+    Undo,
+    /// List the stored checkpoints, or restore one when an id is given
+    Checkpoints { restore: Option<usize> },
+    /// Send the message of the last failed turn again
+    Retry,
+    /// Switch to the given permission mode, or to the next one when none is given
+    Mode { mode: Option<PermissionMode> },
+    /// Write the conversation to a transcript file, at the given path or a generated one
+    Export {
+        format: TranscriptFormat,
+        path: Option<PathBuf>,
+    },
+}
+
+impl Command {
+    /// Parses user input into a command.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The raw user input
+    ///
+    /// # Returns
+    ///
+    /// `Some(Command)` if the input is a known slash command, `None` otherwise.
+    /// Input that is not a known command should be treated as a regular message.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CommandError::InvalidArguments`] if the input names a known
+    /// command but its arguments are missing, extra or invalid.
+    pub fn parse(input: &str) -> Result<Option<Self>, CommandError> {
+        let mut parts = input.split_whitespace();
+        let Some(name) = parts.next().and_then(|name| name.strip_prefix('/')) else {
+            return Ok(None);
+        };
+        let Some(usage) = Self::usage(name) else {
+            return Ok(None);
+        };
+        let args: Vec<&str> = parts.collect();
+
+        let command = match (name, args.as_slice()) {
+            ("undo", []) => Some(Command::Undo),
+            ("retry", []) => Some(Command::Retry),
+            ("mode", []) => Some(Command::Mode { mode: None }),
+            ("mode", [mode]) => mode
+                .parse()
+                .ok()
+                .map(|mode| Command::Mode { mode: Some(mode) }),
+            ("export", [format]) => format
+                .parse()
+                .ok()
+                .map(|format| Command::Export { format, path: None }),
+            ("export", [format, path]) => format.parse().ok().map(|format| Command::Export {
+                format,
+                path: Some(PathBuf::from(path)),
+            }),
+            ("checkpoints", []) => Some(Command::Checkpoints { restore: None }),
+            ("checkpoints", [id]) => id
+                .parse()
+                .ok()
+                .map(|id| Command::Checkpoints { restore: Some(id) }),
+            _ => None,
+        };
+
+        command
+            .map(Some)
+            .ok_or_else(|| CommandError::InvalidArguments {
+                command: name.to_string(),
+                usage,
+            })
+    }
+
+    fn usage(name: &str) -> Option<&'static str> {
+        match name {
+            "undo" => Some("/undo"),
+            "retry" => Some("/retry"),
+            "mode" => Some("/mode [default|plan|accept-edits|full-auto]"),
+            "export" => Some("/export <md|html|json> [PATH]"),
+            "checkpoints" => Some("/checkpoints [ID]"),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn invalid(command: &str) -> Result<Option<Command>, CommandError> {
+        Err(CommandError::InvalidArguments {
+            command: command.to_string(),
+            usage: Command::usage(command).unwrap(),
+        })
+    }
+
+    #[test]
+    fn parse_returns_undo_command() {
+        assert_eq!(Command::parse("/undo"), Ok(Some(Command::Undo)));
+        assert_eq!(Command::parse("  /undo  "), Ok(Some(Command::Undo)));
+    }
+
+    #[test]
+    fn parse_returns_retry_command() {
+        assert_eq!(Command::parse("/retry"), Ok(Some(Command::Retry)));
+        assert_eq!(Command::parse("/retry now"), invalid("retry"));
+    }
+
+    #[test]
+    fn parse_returns_mode_command_with_optional_mode() {
+        assert_eq!(
+            Command::parse("/mode"),
+            Ok(Some(Command::Mode { mode: None }))
+        );
+        assert_eq!(
+            Command::parse("/mode plan"),
+            Ok(Some(Command::Mode {
+                mode: Some(PermissionMode::Plan)
+            }))
+        );
+        assert_eq!(Command::parse("/mode yolo"), invalid("mode"));
+    }
+
+    #[test]
+    fn parse_returns_export_command_with_optional_path() {
+        assert_eq!(
+            Command::parse("/export md"),
+            Ok(Some(Command::Export {
+                format: TranscriptFormat::Markdown,
+                path: None,
+            }))
+        );
+        assert_eq!(
+            Command::parse("/export json review/session.json"),
+            Ok(Some(Command::Export {
+                format: TranscriptFormat::Json,
+                path: Some(PathBuf::from("review/session.json")),
+            }))
+        );
+        assert_eq!(Command::parse("/export"), invalid("export"));
+        assert_eq!(Command::parse("/export pdf"), invalid("export"));
+    }
+
+    #[test]
+    fn parse_returns_checkpoints_command_with_optional_id() {
+        assert_eq!(
+            Command::parse("/checkpoints"),
+            Ok(Some(Command::Checkpoints { restore: None }))
+        );
+        assert_eq!(
+            Command::parse("/checkpoints 3"),
+            Ok(Some(Command::Checkpoints { restore: Some(3) }))
+        );
+        assert_eq!(
+            Command::parse("/checkpoints latest"),
+            invalid("checkpoints")
+        );
+    }
+
+    #[test]
+    fn parse_returns_none_for_regular_messages_and_unknown_commands() {
+        assert_eq!(Command::parse("undo the last change"), Ok(None));
+        assert_eq!(Command::parse("/usr/bin is missing"), Ok(None));
+        assert_eq!(Command::parse(""), Ok(None));
+    }
+}
@@ -0,0 +1,269 @@
+use crate::client::models::{Function, Model, Parameters, Property, Tool, ToolType};
+use crate::client::traits::ChatClient;
+use crate::session::event::{Action, Event, EventHandler};
+use crate::session::turn::TurnResult;
+use crate::tools::registry::EDIT_TOOLS;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::io;
+use std::rc::Rc;
+use std::sync::Arc;
+
+/// Name of the tool the architect calls to hand its changes to the editor.
+pub const HAND_OFF_EDITS_TOOL: &str = "hand_off_edits";
+
+/// Tools the editor can use: the edit tools and reading files to locate edits.
+pub const EDITOR_TOOLS: [&str; 3] = ["read_file", "edit_file", "write_file"];
+
+/// System prompt of the editor, followed by the changes handed off to it.
+pub const EDITOR_PROMPT: &str = "You are the editor of a coding assistant. The architect has decided which changes to make and describes them to you. Apply exactly these changes with the edit_file and write_file tools, reading files first where you need their exact content. Do not redesign or extend the changes. When done, reply with one short sentence per file you changed, and describe anything you could not apply.";
+
+/// A chat client and model that apply the changes decided by the main model.
+#[derive(Clone)]
+pub(crate) struct Editor {
+    pub(crate) client: Arc<dyn ChatClient>,
+    pub(crate) model: Model,
+}
+
+/// The changes the architect hands off to the editor.
+///
+/// # Examples
+///
+/// ```rust
+/// use code_g::session::editor::Handoff;
+/// use std::collections::HashMap;
+///
+/// let handoff = Handoff::from_arguments(&HashMap::from([
+///     ("files".to_string(), "src/lib.rs, src/parser.rs".to_string()),
+///     ("instructions".to_string(), "Rename parse_args to parse".to_string()),
+/// ]))
+/// .unwrap();
+///
+/// assert_eq!(handoff.files, vec!["src/lib.rs", "src/parser.rs"]);
+/// assert_eq!(
+///     handoff.to_string(),
+///     "Apply these changes.\n\nFiles:\n- src/lib.rs\n- src/parser.rs\n\nInstructions:\nRename parse_args to parse"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Handoff {
+    /// The files to change
+    pub files: Vec<String>,
+    /// What to change in them
+    pub instructions: String,
+}
+
+impl Handoff {
+    /// Reads a handoff from the arguments of a call of the handoff tool.
+    ///
+    /// # Arguments
+    ///
+    /// * `arguments` - The arguments of the call
+    ///
+    /// # Errors
+    ///
+    /// Returns a message for the architect if the instructions are missing or empty.
+    pub fn from_arguments(arguments: &HashMap<String, String>) -> Result<Self, String> {
+        let instructions = arguments
+            .get("instructions")
+            .map(|instructions| instructions.trim())
+            .filter(|instructions| !instructions.is_empty())
+            .ok_or("The instructions argument is required")?;
+        let files = arguments
+            .get("files")
+            .map(|files| {
+                files
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|file| !file.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(Self {
+            files,
+            instructions: instructions.to_string(),
+        })
+    }
+
+    /// Returns the definition of the tool the architect calls to hand off changes.
+    pub fn tool() -> Tool {
+        Tool {
+            tool_type: ToolType::Function,
+            function: Function {
+                name: HAND_OFF_EDITS_TOOL.to_string(),
+                description: "Hand file changes to the editor, which applies them with its edit tools. You cannot edit files yourself: decide what to change, then describe the changes precisely enough to apply them without further reasoning, such as the functions to replace and their new code. The editor reports back which files it changed.".to_string(),
+                parameters: Parameters {
+                    param_type: "object".to_string(),
+                    properties: HashMap::from([
+                        (
+                            "files".to_string(),
+                            Property {
+                                prop_type: "string".to_string(),
+                                description: "Comma-separated paths of the files to change or create".to_string(),
+                            },
+                        ),
+                        (
+                            "instructions".to_string(),
+                            Property {
+                                prop_type: "string".to_string(),
+                                description: "The changes to make in each file".to_string(),
+                            },
+                        ),
+                    ]),
+                    required: vec!["files".to_string(), "instructions".to_string()],
+                    additional_properties: false,
+                },
+                strict: true,
+            },
+        }
+    }
+
+    /// Describes the outcome of the editor's turn for the architect.
+    ///
+    /// # Arguments
+    ///
+    /// * `turn` - The editor's turn
+    ///
+    /// # Returns
+    ///
+    /// The edit calls the editor made with their outcome, followed by its reply.
+    pub fn report(turn: &TurnResult) -> String {
+        let edits: Vec<String> = turn
+            .tool_calls
+            .iter()
+            .filter(|tool_call| EDIT_TOOLS.contains(&tool_call.tool_name.as_str()))
+            .map(|tool_call| {
+                let path = tool_call
+                    .arguments
+                    .get("path")
+                    .map(String::as_str)
+                    .unwrap_or("?");
+                let outcome = if tool_call.decision.is_executed() {
+                    tool_call.response.lines().next().unwrap_or_default()
+                } else {
+                    "not applied"
+                };
+                format!("- {} {}: {}", tool_call.tool_name, path, outcome)
+            })
+            .collect();
+
+        if edits.is_empty() {
+            format!(
+                "The editor made no edits.\nEditor's reply: {}",
+                turn.message
+            )
+        } else {
+            format!(
+                "The editor made {} edit(s):\n{}\nEditor's reply: {}",
+                edits.len(),
+                edits.join("\n"),
+                turn.message
+            )
+        }
+    }
+}
+
+impl fmt::Display for Handoff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Apply these changes.")?;
+        if !self.files.is_empty() {
+            let files: Vec<String> = self
+                .files
+                .iter()
+                .map(|file| format!("- {}", file))
+                .collect();
+            write!(f, "\n\nFiles:\n{}", files.join("\n"))?;
+        }
+        write!(f, "\n\nInstructions:\n{}", self.instructions)
+    }
+}
+
+/// Event handler of the editor's session.
+///
+/// The editor borrows the frontend of the architect's session for the length
+/// of its turn, so the user sees and approves its tool calls as usual. The
+/// handoff and the editor's reply are not shown, since the architect receives
+/// a report of the edits instead.
+pub(crate) struct EditorEventHandler {
+    frontend: Rc<RefCell<Box<dyn EventHandler>>>,
+}
+
+impl EditorEventHandler {
+    pub(crate) fn new(frontend: Rc<RefCell<Box<dyn EventHandler>>>) -> Self {
+        Self { frontend }
+    }
+}
+
+impl EventHandler for EditorEventHandler {
+    fn handle_event(&mut self, event: Event) {
+        if !matches!(
+            event,
+            Event::ReceivedUserMessage { .. } | Event::ReceivedAssistantMessage { .. }
+        ) {
+            self.frontend.borrow_mut().handle_event(event);
+        }
+    }
+
+    fn handle_action(&mut self, action: Action) -> Result<String, io::Error> {
+        match action {
+            Action::RequestUserInput => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "the editor cannot ask the user for input",
+            )),
+            action => self.frontend.borrow_mut().handle_action(action),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::models::Usage;
+    use crate::session::turn::{ToolCallDecision, ToolCallOutcome};
+
+    fn edit(tool_name: &str, path: &str, decision: ToolCallDecision) -> ToolCallOutcome {
+        ToolCallOutcome {
+            id: "1".to_string(),
+            tool_name: tool_name.to_string(),
+            arguments: HashMap::from([("path".to_string(), path.to_string())]),
+            response: format!("Updated {}\nwith details", path),
+            decision,
+        }
+    }
+
+    #[test]
+    fn from_arguments_requires_instructions() {
+        assert_eq!(
+            Handoff::from_arguments(&HashMap::from([(
+                "instructions".to_string(),
+                " ".to_string()
+            )])),
+            Err("The instructions argument is required".to_string())
+        );
+    }
+
+    #[test]
+    fn report_lists_edits_and_the_editors_reply() {
+        let turn = TurnResult {
+            message: "Renamed the function".to_string(),
+            tool_calls: vec![
+                edit("read_file", "src/lib.rs", ToolCallDecision::Allowed),
+                edit("edit_file", "src/lib.rs", ToolCallDecision::Approved),
+                edit("write_file", "src/new.rs", ToolCallDecision::Declined),
+            ],
+            usage: Usage::default(),
+            budget_exhausted: None,
+        };
+
+        assert_eq!(
+            Handoff::report(&turn),
+            "The editor made 2 edit(s):\n\
+             - edit_file src/lib.rs: Updated src/lib.rs\n\
+             - write_file src/new.rs: not applied\n\
+             Editor's reply: Renamed the function"
+        );
+    }
+}
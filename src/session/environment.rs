@@ -0,0 +1,285 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Section describing the environment, added to system prompts that use no variables.
+pub const ENVIRONMENT_TEMPLATE: &str = r#"Environment:
+    - Working directory: {{cwd}}
+    - Operating system: {{os}}
+    - Shell: {{shell}}
+    - Date: {{date}}
+    - Git branch: {{git_branch}}
+    - Git status: {{git_status}}
+    - Tools: {{tools}}
+
+Project files:
+{{file_tree}}"#;
+
+// Directories left out of the file tree because they are large or generated
+const IGNORED_DIRS: [&str; 4] = ["target", "node_modules", "dist", "build"];
+
+// Depth and size limits keeping the file tree short
+const FILE_TREE_DEPTH: usize = 2;
+const FILE_TREE_MAX_ENTRIES: usize = 60;
+
+/// Facts about the environment the assistant operates in, used to fill in system prompts.
+///
+/// System prompts refer to the facts with `{{variable}}` placeholders. The
+/// variables are `cwd`, `os`, `shell`, `date`, `git_branch`, `git_status`,
+/// `file_tree` and `tools`. Unknown placeholders are left as they are.
+///
+/// # Examples
+///
+/// ```rust
+/// use code_g::session::environment::PromptEnvironment;
+/// use std::path::PathBuf;
+///
+/// let environment = PromptEnvironment {
+///     cwd: PathBuf::from("/home/user/project"),
+///     os: "linux".to_string(),
+///     tools: vec!["read_file".to_string(), "write_file".to_string()],
+///     ..PromptEnvironment::default()
+/// };
+///
+/// assert_eq!(
+///     environment.render("Work in {{cwd}} on {{os}} using {{tools}}. {{unknown}}"),
+///     "Work in /home/user/project on linux using read_file, write_file. {{unknown}}"
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PromptEnvironment {
+    /// The working directory of the session
+    pub cwd: PathBuf,
+    /// The operating system, such as `linux` or `macos`
+    pub os: String,
+    /// The shell used to run commands
+    pub shell: String,
+    /// The current date as `YYYY-MM-DD`
+    pub date: String,
+    /// The checked out git branch, if the directory is a git repository
+    pub git_branch: Option<String>,
+    /// A summary of uncommitted changes, if the directory is a git repository
+    pub git_status: Option<String>,
+    /// The files and directories near the top of the working directory
+    pub file_tree: String,
+    /// The names of the tools offered to the assistant
+    pub tools: Vec<String>,
+}
+
+impl PromptEnvironment {
+    /// Detects the environment of a working directory.
+    ///
+    /// Git information is missing if git is not installed or the directory is
+    /// not a repository. The tools are left empty for the session to fill in.
+    ///
+    /// # Arguments
+    ///
+    /// * `cwd` - The working directory of the session
+    ///
+    /// # Returns
+    ///
+    /// The detected [`PromptEnvironment`].
+    pub fn detect(cwd: &Path) -> Self {
+        let shell = env::var("SHELL")
+            .or_else(|_| env::var("COMSPEC"))
+            .unwrap_or_else(|_| "unknown".to_string());
+        let git_status =
+            git(cwd, &["status", "--porcelain"]).map(|status| match status.lines().count() {
+                0 => "clean".to_string(),
+                1 => "1 uncommitted change".to_string(),
+                changes => format!("{} uncommitted changes", changes),
+            });
+
+        PromptEnvironment {
+            cwd: cwd.to_path_buf(),
+            os: env::consts::OS.to_string(),
+            shell,
+            date: today(),
+            git_branch: git(cwd, &["rev-parse", "--abbrev-ref", "HEAD"]),
+            git_status,
+            file_tree: file_tree(cwd),
+            tools: vec![],
+        }
+    }
+
+    /// Returns `true` if the template contains any of the environment variables.
+    ///
+    /// # Arguments
+    ///
+    /// * `template` - The template to check
+    pub fn is_used_in(template: &str) -> bool {
+        Self::default()
+            .variables()
+            .iter()
+            .any(|(name, _)| template.contains(&format!("{{{{{}}}}}", name)))
+    }
+
+    /// Replaces the `{{variable}}` placeholders in a template.
+    ///
+    /// # Arguments
+    ///
+    /// * `template` - The template to fill in
+    ///
+    /// # Returns
+    ///
+    /// The template with every known variable replaced by its value.
+    pub fn render(&self, template: &str) -> String {
+        self.variables()
+            .into_iter()
+            .fold(template.to_string(), |text, (name, value)| {
+                text.replace(&format!("{{{{{}}}}}", name), &value)
+            })
+    }
+
+    fn variables(&self) -> Vec<(&'static str, String)> {
+        let unknown = || "unknown".to_string();
+        vec![
+            ("cwd", self.cwd.display().to_string()),
+            ("os", self.os.clone()),
+            ("shell", self.shell.clone()),
+            ("date", self.date.clone()),
+            (
+                "git_branch",
+                self.git_branch
+                    .clone()
+                    .unwrap_or_else(|| "not a git repository".to_string()),
+            ),
+            (
+                "git_status",
+                self.git_status.clone().unwrap_or_else(unknown),
+            ),
+            ("file_tree", self.file_tree.clone()),
+            ("tools", self.tools.join(", ")),
+        ]
+    }
+}
+
+/// Runs a git command in a directory, returning its trimmed output on success.
+fn git(dir: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Returns the current UTC date as `YYYY-MM-DD`.
+fn today() -> String {
+    let days = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs() / 86_400)
+        .unwrap_or(0) as i64;
+
+    // Convert days since the epoch to a civil date (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// Lists the files and directories at the top of a directory as an indented tree.
+///
+/// Hidden entries and common build directories are skipped, and the listing
+/// is cut short after a fixed number of entries.
+fn file_tree(dir: &Path) -> String {
+    let mut lines = Vec::new();
+    add_entries(dir, 0, &mut lines);
+    if lines.len() > FILE_TREE_MAX_ENTRIES {
+        lines.truncate(FILE_TREE_MAX_ENTRIES);
+        lines.push("    ...".to_string());
+    }
+    lines.join("\n")
+}
+
+fn add_entries(dir: &Path, depth: usize, lines: &mut Vec<String>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    let mut entries: Vec<(String, PathBuf)> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| {
+            (
+                entry.file_name().to_string_lossy().to_string(),
+                entry.path(),
+            )
+        })
+        .filter(|(name, _)| !name.starts_with('.') && !IGNORED_DIRS.contains(&name.as_str()))
+        .collect();
+    entries.sort();
+
+    for (name, path) in entries {
+        // Stop early, one entry past the limit marks the tree as cut short
+        if lines.len() > FILE_TREE_MAX_ENTRIES {
+            return;
+        }
+        let indent = "    ".repeat(depth + 1);
+        if path.is_dir() {
+            lines.push(format!("{}{}/", indent, name));
+            if depth + 1 < FILE_TREE_DEPTH {
+                add_entries(&path, depth + 1, lines);
+            }
+        } else {
+            lines.push(format!("{}{}", indent, name));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_tree_lists_two_levels_without_hidden_or_build_dirs() {
+        let dir = env::temp_dir().join(format!("code_g_environment_tree_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("src/session")).unwrap();
+        fs::create_dir_all(dir.join("target/debug")).unwrap();
+        fs::create_dir_all(dir.join(".git")).unwrap();
+        fs::write(dir.join("Cargo.toml"), "").unwrap();
+        fs::write(dir.join("src/main.rs"), "").unwrap();
+        fs::write(dir.join("src/session/mod.rs"), "").unwrap();
+
+        let tree = file_tree(&dir);
+
+        assert_eq!(
+            tree,
+            "    Cargo.toml\n    src/\n        main.rs\n        session/"
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn render_fills_in_missing_git_information() {
+        let environment = PromptEnvironment::default();
+
+        assert_eq!(
+            environment.render("{{git_branch}} / {{git_status}}"),
+            "not a git repository / unknown"
+        );
+        assert!(PromptEnvironment::is_used_in("Today is {{date}}"));
+        assert!(!PromptEnvironment::is_used_in("No variables {{here}}"));
+    }
+
+    #[test]
+    fn today_formats_the_date() {
+        let date = today();
+
+        assert_eq!(date.len(), 10);
+        assert!(date.starts_with("20"));
+        assert_eq!(&date[4..5], "-");
+        assert_eq!(&date[7..8], "-");
+    }
+}
@@ -1,7 +1,8 @@
 use crate::client::error::ChatClientError;
+use crate::session::budget::BudgetLimit;
+use crate::session::loop_detector::LoopPattern;
 use thiserror::Error;
 
-
 /// Represents errors that can occur during chat session operations.
 ///
 /// This enum encompasses all possible error conditions that may arise when
@@ -28,6 +29,15 @@ pub enum ChatSessionError {
     )]
     MaxIterationsExceeded { max_iterations: usize },
 
+    /// The token, cost or time budget of a turn was exhausted and the
+    /// assistant failed to provide a final summary
+    #[error("Turn budget exceeded: {limit}")]
+    BudgetExceeded { limit: BudgetLimit },
+
+    /// The assistant kept calling tools in a loop after being told to stop
+    #[error("Tool calling loop detected: {pattern}. The turn was stopped.")]
+    LoopDetected { pattern: LoopPattern },
+
     /// Error during tool execution
     #[error("Tool execution error: {0}")]
     ToolError(String),
@@ -1,5 +1,14 @@
+use crate::permissions::grant::GrantScope;
+use crate::permissions::mode::PermissionMode;
+use crate::session::attachment::Attachment;
+use crate::session::budget::BudgetLimit;
+use crate::session::checkpoint::CheckpointSummary;
+use crate::session::loop_detector::LoopPattern;
+use crate::session::plan::Plan;
+use crate::session::todo::TodoList;
 use std::collections::HashMap;
 use std::io;
+use std::path::PathBuf;
 
 /// Events that can occur during a chat session.
 ///
@@ -12,7 +21,10 @@ use std::io;
 /// ```rust
 /// use code_g::session::event::Event;
 ///
-/// let event = Event::ReceivedUserMessage { message: "Hello".to_string() };
+/// let event = Event::ReceivedUserMessage {
+///     message: "Hello".to_string(),
+///     attachments: vec![],
+/// };
 /// ```
 #[derive(Debug, PartialEq, Clone)]
 pub enum Event {
@@ -20,9 +32,14 @@ pub enum Event {
     SessionStarted,
     /// The chat session has been ended
     SessionEnded,
+    /// An error occurred that ended the current turn, the session continues
+    Error { message: String },
 
-    /// A message was received from the user
-    ReceivedUserMessage { message: String },
+    /// A message was received from the user, with the files and directories it mentions
+    ReceivedUserMessage {
+        message: String,
+        attachments: Vec<Attachment>,
+    },
 
     /// A message was received from the assistant
     ReceivedAssistantMessage { message: String },
@@ -31,6 +48,20 @@ pub enum Event {
         tool_name: String,
         parameters: HashMap<String, String>,
     },
+    /// A tool call was blocked by a pre-tool hook with the given reason
+    ToolCallBlocked { tool_name: String, reason: String },
+    /// A tool call was denied by the permission policy, with the reason
+    ToolCallDenied { tool_name: String, reason: String },
+    /// The user approved similar tool calls for good, with the added rule
+    PermissionGranted { rule: String, scope: GrantScope },
+    /// The permission mode of the session changed
+    PermissionModeChanged { mode: PermissionMode },
+    /// A plan was approved or one of its steps was done
+    PlanUpdated { plan: Plan },
+    /// The user did not approve the proposed plan
+    PlanRejected,
+    /// The assistant wrote its todo list
+    TodosUpdated { todos: TodoList },
     /// A tool response was received with tool name, response, parameters, and approval status
     ReceivedToolResponse {
         tool_name: String,
@@ -40,6 +71,39 @@ pub enum Event {
     },
     /// The system is waiting for the assistant to respond
     AwaitingAssistantResponse,
+
+    /// A limit of the turn budget is nearly used up
+    BudgetWarning { limit: BudgetLimit },
+    /// A limit of the turn budget was reached and the assistant is asked to wrap up
+    BudgetExceeded { limit: BudgetLimit },
+
+    /// A configured hook could not be run or failed
+    HookFailed { message: String },
+
+    /// The assistant repeated tool calls in a loop and was told to change approach
+    LoopDetected { pattern: LoopPattern },
+    /// The assistant kept looping after the correction and the turn was stopped
+    LoopAborted { pattern: LoopPattern },
+
+    /// The check command is run on the files the assistant modified
+    VerificationStarted { command: String },
+    /// The check command finished, a failed check is sent back to the
+    /// assistant unless its retries are used up
+    VerificationFinished {
+        command: String,
+        passed: bool,
+        output: String,
+        retrying: bool,
+    },
+
+    /// The stored file checkpoints were listed by the user
+    CheckpointsListed { checkpoints: Vec<CheckpointSummary> },
+    /// The files of a checkpoint were restored to their pre-turn state
+    CheckpointRestored { checkpoint: CheckpointSummary },
+    /// Listing or restoring checkpoints failed
+    CheckpointFailed { message: String },
+    /// The conversation was written to a transcript file
+    TranscriptExported { path: PathBuf },
 }
 
 /// Actions that can be requested during a chat session.
@@ -63,7 +127,16 @@ pub enum Action {
     RequestUserApproval {
         approval_message: String,
         tool_name: String,
+        /// The parameters of the tool call, used to offer "always allow" options
+        parameters: HashMap<String, String>,
+        /// Why the permission policy asks for approval
+        reason: String,
     },
+    /// Request user approval for a plan proposed in plan mode.
+    ///
+    /// The response is `approved`, `declined`, or `edited:` followed by the
+    /// revised numbered steps.
+    RequestPlanApproval { plan: Plan },
 }
 
 /// Trait for handling chat session events and actions.
@@ -72,6 +145,10 @@ pub enum Action {
 /// Implementors can define custom behavior for processing events and handling actions
 /// that require external interaction.
 ///
+/// A session has exactly one event handler, its interactive frontend, which is the only
+/// receiver of [`Action`]s. Passive observers of events should implement
+/// [`EventSubscriber`](crate::session::event_bus::EventSubscriber) instead.
+///
 /// # Examples
 ///
 /// ```rust
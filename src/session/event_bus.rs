@@ -0,0 +1,178 @@
+use crate::session::event::Event;
+use async_trait::async_trait;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::task::JoinHandle;
+
+// Number of events buffered per subscriber before the oldest are dropped
+const EVENT_BUS_CAPACITY: usize = 1024;
+
+/// Trait for passive observers of chat session events.
+///
+/// Unlike the [`EventHandler`](crate::session::event::EventHandler), which is the single
+/// interactive frontend that also answers actions, any number of subscribers can observe
+/// a session. Each subscriber runs in its own task, so a slow subscriber such as a
+/// transcript writer never blocks the session.
+///
+/// # Examples
+///
+/// ```rust
+/// use async_trait::async_trait;
+/// use code_g::session::event::Event;
+/// use code_g::session::event_bus::EventSubscriber;
+///
+/// struct MetricsLogger {
+///     tool_calls: usize,
+/// }
+///
+/// #[async_trait]
+/// impl EventSubscriber for MetricsLogger {
+///     async fn handle_event(&mut self, event: Event) {
+///         if let Event::ReceivedToolCall { .. } = event {
+///             self.tool_calls += 1;
+///         }
+///     }
+/// }
+/// ```
+#[async_trait]
+pub trait EventSubscriber: Send {
+    /// Handle an event published by the chat session.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The event that occurred in the chat session
+    async fn handle_event(&mut self, event: Event);
+}
+
+/// Broadcasts chat session events to any number of subscribers.
+///
+/// Events are delivered asynchronously over a broadcast channel. A subscriber that
+/// falls too far behind skips the events it missed instead of slowing down the session.
+///
+/// # Examples
+///
+/// ```rust
+/// use code_g::session::event::Event;
+/// use code_g::session::event_bus::EventBus;
+///
+/// let bus = EventBus::new();
+/// let mut receiver = bus.subscribe();
+///
+/// bus.publish(Event::SessionStarted);
+///
+/// assert_eq!(receiver.try_recv().unwrap(), Event::SessionStarted);
+/// ```
+#[derive(Debug, Clone)]
+pub struct EventBus {
+    sender: broadcast::Sender<Event>,
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EventBus {
+    /// Creates an event bus without subscribers.
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(EVENT_BUS_CAPACITY);
+        Self { sender }
+    }
+
+    /// Publishes an event to all current subscribers.
+    ///
+    /// Publishing without subscribers is not an error, the event is dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The event to publish
+    pub fn publish(&self, event: Event) {
+        let _ = self.sender.send(event);
+    }
+
+    /// Returns a receiver for all events published from now on.
+    ///
+    /// The receiver is closed once the bus and all its clones are dropped.
+    pub fn subscribe(&self) -> broadcast::Receiver<Event> {
+        self.sender.subscribe()
+    }
+
+    /// Runs a subscriber in a background task until the bus is dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * `subscriber` - The [`EventSubscriber`] to deliver events to
+    ///
+    /// # Returns
+    ///
+    /// The handle of the task, which completes after the last event was handled.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a Tokio runtime.
+    pub fn spawn_subscriber(&self, mut subscriber: Box<dyn EventSubscriber>) -> JoinHandle<()> {
+        let mut receiver = self.subscribe();
+
+        tokio::spawn(async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(event) => subscriber.handle_event(event).await,
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    struct RecordingSubscriber {
+        events: Arc<Mutex<Vec<Event>>>,
+    }
+
+    #[async_trait]
+    impl EventSubscriber for RecordingSubscriber {
+        async fn handle_event(&mut self, event: Event) {
+            self.events.lock().unwrap().push(event);
+        }
+    }
+
+    #[test]
+    fn publish_without_subscribers_does_not_fail() {
+        let bus = EventBus::new();
+
+        bus.publish(Event::SessionStarted);
+    }
+
+    #[tokio::test]
+    async fn spawn_subscriber_delivers_events_to_every_subscriber() {
+        let bus = EventBus::new();
+        let first = Arc::new(Mutex::new(vec![]));
+        let second = Arc::new(Mutex::new(vec![]));
+
+        let handles = [
+            bus.spawn_subscriber(Box::new(RecordingSubscriber {
+                events: first.clone(),
+            })),
+            bus.spawn_subscriber(Box::new(RecordingSubscriber {
+                events: second.clone(),
+            })),
+        ];
+
+        bus.publish(Event::SessionStarted);
+        bus.publish(Event::SessionEnded);
+        drop(bus);
+
+        for handle in handles {
+            handle.await.unwrap();
+        }
+
+        let expected = vec![Event::SessionStarted, Event::SessionEnded];
+        assert_eq!(*first.lock().unwrap(), expected);
+        assert_eq!(*second.lock().unwrap(), expected);
+    }
+}
@@ -0,0 +1,81 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+/// Messages typed by the user while the assistant is working.
+///
+/// The queue is shared between the session and its frontend: the frontend
+/// pushes the lines the user types during a turn, and the session takes them
+/// off the queue. Plain messages are added to the running turn before the
+/// next completion, steering the assistant. Commands such as `/undo`, and
+/// messages typed after the last completion of a turn, are sent as the next
+/// turns instead, in the order they were typed.
+///
+/// # Examples
+///
+/// ```rust
+/// use code_g::session::input_queue::InputQueue;
+///
+/// let queue = InputQueue::new();
+/// let frontend = queue.clone();
+///
+/// frontend.push("Use the new API".to_string());
+/// frontend.push("/undo".to_string());
+/// frontend.push("Then run the tests".to_string());
+///
+/// assert_eq!(queue.take_steering(), vec!["Use the new API".to_string()]);
+/// assert_eq!(queue.pop(), Some("/undo".to_string()));
+/// assert_eq!(queue.pop(), Some("Then run the tests".to_string()));
+/// assert!(queue.is_empty());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct InputQueue {
+    messages: Arc<Mutex<VecDeque<String>>>,
+}
+
+impl InputQueue {
+    /// Creates an empty queue.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a message typed by the user to the end of the queue.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The message typed by the user
+    pub fn push(&self, message: String) {
+        self.messages.lock().unwrap().push_back(message);
+    }
+
+    /// Takes the oldest message off the queue, to send it as the next turn.
+    pub fn pop(&self) -> Option<String> {
+        self.messages.lock().unwrap().pop_front()
+    }
+
+    /// Takes the messages that can steer the running turn off the queue.
+    ///
+    /// Messages are taken up to the first command, so that the messages typed
+    /// after a command still reach the assistant after the command ran.
+    ///
+    /// # Returns
+    ///
+    /// The messages in the order they were typed.
+    pub fn take_steering(&self) -> Vec<String> {
+        let mut messages = self.messages.lock().unwrap();
+        let count = messages
+            .iter()
+            .take_while(|message| !message.starts_with('/') && message.as_str() != "exit")
+            .count();
+        messages.drain(..count).collect()
+    }
+
+    /// Returns the number of queued messages.
+    pub fn len(&self) -> usize {
+        self.messages.lock().unwrap().len()
+    }
+
+    /// Returns `true` if no messages are queued.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
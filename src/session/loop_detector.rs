@@ -0,0 +1,185 @@
+use crate::client::models::ToolCall;
+use std::collections::HashMap;
+use std::fmt;
+
+// Number of consecutive identical tool calls that count as a loop
+const REPEATED_CALL_THRESHOLD: usize = 3;
+
+/// A repetitive tool calling pattern detected within a turn.
+///
+/// # Examples
+///
+/// ```rust
+/// use code_g::session::loop_detector::LoopPattern;
+///
+/// let pattern = LoopPattern::RepeatedCall {
+///     tool_name: "read_file".to_string(),
+///     count: 3,
+/// };
+/// assert_eq!(
+///     pattern.to_string(),
+///     "read_file was called 3 times in a row with identical arguments"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum LoopPattern {
+    /// The same tool was called with identical arguments several times in a row
+    RepeatedCall { tool_name: String, count: usize },
+    /// Two tool calls keep alternating, such as an edit and its revert
+    Oscillation { first: String, second: String },
+}
+
+impl fmt::Display for LoopPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoopPattern::RepeatedCall { tool_name, count } => write!(
+                f,
+                "{} was called {} times in a row with identical arguments",
+                tool_name, count
+            ),
+            LoopPattern::Oscillation { first, second } => write!(
+                f,
+                "{} and {} calls keep alternating, undoing each other",
+                first, second
+            ),
+        }
+    }
+}
+
+/// Detects the assistant calling tools in a loop within a single turn.
+///
+/// Two patterns are detected: the same call with identical arguments made
+/// several times in a row, and two different calls alternating, such as an
+/// edit followed by its revert.
+///
+/// # Examples
+///
+/// ```rust
+/// use code_g::client::models::ToolCall;
+/// use code_g::session::loop_detector::LoopDetector;
+/// use std::collections::HashMap;
+///
+/// let call = ToolCall {
+///     id: "1".to_string(),
+///     name: "read_file".to_string(),
+///     arguments: HashMap::from([("path".to_string(), "src/main.rs".to_string())]),
+/// };
+///
+/// let mut detector = LoopDetector::new();
+/// assert!(detector.record(&call).is_none());
+/// assert!(detector.record(&call).is_none());
+/// assert!(detector.record(&call).is_some());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct LoopDetector {
+    history: Vec<(String, HashMap<String, String>)>,
+}
+
+impl LoopDetector {
+    /// Creates a detector with an empty history.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a tool call and checks the history for a loop.
+    ///
+    /// The call id is ignored, only the tool name and arguments are compared.
+    ///
+    /// # Arguments
+    ///
+    /// * `tool_call` - The tool call made by the assistant
+    ///
+    /// # Returns
+    ///
+    /// The detected [`LoopPattern`], or `None` if the recent calls do not form a loop.
+    pub fn record(&mut self, tool_call: &ToolCall) -> Option<LoopPattern> {
+        self.history
+            .push((tool_call.name.clone(), tool_call.arguments.clone()));
+
+        self.repeated_call().or_else(|| self.oscillation())
+    }
+
+    fn repeated_call(&self) -> Option<LoopPattern> {
+        let (last, rest) = self.history.split_last()?;
+        let count = 1 + rest.iter().rev().take_while(|call| *call == last).count();
+
+        (count >= REPEATED_CALL_THRESHOLD).then(|| LoopPattern::RepeatedCall {
+            tool_name: last.0.clone(),
+            count,
+        })
+    }
+
+    fn oscillation(&self) -> Option<LoopPattern> {
+        match self.history.as_slice() {
+            [.., a1, b1, a2, b2] if a1 == a2 && b1 == b2 && a1 != b1 => {
+                Some(LoopPattern::Oscillation {
+                    first: a1.0.clone(),
+                    second: b1.0.clone(),
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(name: &str, path: &str, content: &str) -> ToolCall {
+        ToolCall {
+            id: format!("{}-{}", name, content),
+            name: name.to_string(),
+            arguments: HashMap::from([
+                ("path".to_string(), path.to_string()),
+                ("content".to_string(), content.to_string()),
+            ]),
+        }
+    }
+
+    #[test]
+    fn record_detects_identical_consecutive_calls() {
+        let mut detector = LoopDetector::new();
+        let read = call("read_file", "a.rs", "");
+
+        assert_eq!(detector.record(&read), None);
+        assert_eq!(detector.record(&read), None);
+        assert_eq!(
+            detector.record(&read),
+            Some(LoopPattern::RepeatedCall {
+                tool_name: "read_file".to_string(),
+                count: 3
+            })
+        );
+    }
+
+    #[test]
+    fn record_ignores_identical_calls_that_are_not_consecutive() {
+        let mut detector = LoopDetector::new();
+        let test = call("execute_command", "", "cargo test");
+
+        assert_eq!(detector.record(&test), None);
+        assert_eq!(detector.record(&call("edit_file", "a.rs", "fix 1")), None);
+        assert_eq!(detector.record(&test), None);
+        assert_eq!(detector.record(&call("edit_file", "a.rs", "fix 2")), None);
+        assert_eq!(detector.record(&test), None);
+    }
+
+    #[test]
+    fn record_detects_edit_revert_oscillation() {
+        let mut detector = LoopDetector::new();
+        let edit = call("write_file", "a.rs", "new");
+        let revert = call("write_file", "a.rs", "old");
+
+        assert_eq!(detector.record(&edit), None);
+        assert_eq!(detector.record(&revert), None);
+        assert_eq!(detector.record(&edit), None);
+        assert_eq!(
+            detector.record(&revert),
+            Some(LoopPattern::Oscillation {
+                first: "write_file".to_string(),
+                second: "write_file".to_string()
+            })
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use crate::client::models::ChatMessage;
+use crate::client::models::{AssistantMessage, ChatMessage};
 
 /// A storage container for managing chat conversation history.
 ///
@@ -116,6 +116,108 @@ impl ChatMemory {
         &self.memory
     }
 
+    /// Returns the number of messages in the conversation history.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use code_g::session::memory::ChatMemory;
+    ///
+    /// let memory = ChatMemory::new();
+    /// assert_eq!(memory.len(), 0);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.memory.len()
+    }
+
+    /// Returns `true` if the conversation history contains no messages.
+    pub fn is_empty(&self) -> bool {
+        self.memory.is_empty()
+    }
+
+    /// Shortens the conversation history to the first `len` messages.
+    ///
+    /// This is used to roll back the messages of a turn that failed, so the
+    /// history never ends with a dangling user message or unanswered tool calls.
+    /// Has no effect if `len` is greater than the current length.
+    ///
+    /// # Arguments
+    ///
+    /// * `len` - The number of messages to keep
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use code_g::session::memory::ChatMemory;
+    /// use code_g::client::models::ChatMessage;
+    ///
+    /// let mut memory = ChatMemory::new();
+    /// let checkpoint = memory.len();
+    /// memory.add_message(ChatMessage::User {
+    ///     content: "Hello".to_string(),
+    /// });
+    /// memory.truncate(checkpoint);
+    /// assert!(memory.is_empty());
+    /// ```
+    pub fn truncate(&mut self, len: usize) {
+        self.memory.truncate(len);
+    }
+
+    /// Removes the last tool call message of the assistant if some of its calls
+    /// have no results.
+    ///
+    /// A turn that fails while running tools leaves a tool call message whose
+    /// results are missing, which the API rejects. The results of its calls that
+    /// did run are removed along with it. Has no effect if every call has a result.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use code_g::session::memory::ChatMemory;
+    /// use code_g::client::models::{AssistantMessage, ChatMessage, ToolCall};
+    /// use std::collections::HashMap;
+    ///
+    /// let mut memory = ChatMemory::new();
+    /// memory.add_message(ChatMessage::Assistant {
+    ///     message: AssistantMessage::ToolCalls(vec![ToolCall {
+    ///         id: "call_1".to_string(),
+    ///         name: "read_file".to_string(),
+    ///         arguments: HashMap::new(),
+    ///     }]),
+    /// });
+    /// memory.remove_unanswered_tool_calls();
+    /// assert!(memory.is_empty());
+    /// ```
+    pub fn remove_unanswered_tool_calls(&mut self) {
+        let Some(index) = self
+            .memory
+            .iter()
+            .rposition(|message| !matches!(message, ChatMessage::Tool { .. }))
+        else {
+            return;
+        };
+        let ChatMessage::Assistant {
+            message: AssistantMessage::ToolCalls(tool_calls),
+        } = &self.memory[index]
+        else {
+            return;
+        };
+
+        let answered: Vec<&String> = self.memory[index + 1..]
+            .iter()
+            .filter_map(|message| match message {
+                ChatMessage::Tool { tool_call_id, .. } => Some(tool_call_id),
+                _ => None,
+            })
+            .collect();
+        if tool_calls
+            .iter()
+            .any(|tool_call| !answered.contains(&&tool_call.id))
+        {
+            self.memory.truncate(index);
+        }
+    }
+
     /// Removes all messages from the conversation history.
     ///
     /// This operation empties the entire conversation, resetting the memory
@@ -143,6 +245,8 @@ impl ChatMemory {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::client::models::ToolCall;
+    use std::collections::HashMap;
 
     #[test]
     fn add_message_adds_message_to_memory() {
@@ -177,6 +281,25 @@ mod tests {
         );
     }
 
+    #[test]
+    fn truncate_removes_messages_after_len() {
+        let mut memory = ChatMemory::from(vec![ChatMessage::System {
+            content: "You are helpful".to_string(),
+        }]);
+        memory.add_message(ChatMessage::User {
+            content: "Hello, world!".to_string(),
+        });
+
+        memory.truncate(1);
+
+        assert_eq!(
+            memory.get_memory(),
+            &[ChatMessage::System {
+                content: "You are helpful".to_string(),
+            }]
+        );
+    }
+
     #[test]
     fn clear_clears_memory() {
         let mut memory = ChatMemory::new();
@@ -187,4 +310,56 @@ mod tests {
         assert_eq!(memory.get_memory().len(), 0);
         assert_eq!(memory.get_memory(), &[]);
     }
+
+    fn tool_calls(ids: &[&str]) -> ChatMessage {
+        ChatMessage::Assistant {
+            message: AssistantMessage::ToolCalls(
+                ids.iter()
+                    .map(|id| ToolCall {
+                        id: id.to_string(),
+                        name: "read_file".to_string(),
+                        arguments: HashMap::new(),
+                    })
+                    .collect(),
+            ),
+        }
+    }
+
+    fn tool_result(id: &str) -> ChatMessage {
+        ChatMessage::Tool {
+            content: "content".to_string(),
+            tool_call_id: id.to_string(),
+            tool_name: "read_file".to_string(),
+        }
+    }
+
+    #[test]
+    fn remove_unanswered_tool_calls_removes_partly_answered_calls() {
+        let user = ChatMessage::User {
+            content: "Read the files".to_string(),
+        };
+        let mut memory = ChatMemory::from(vec![
+            user.clone(),
+            tool_calls(&["call_1", "call_2"]),
+            tool_result("call_1"),
+        ]);
+
+        memory.remove_unanswered_tool_calls();
+
+        assert_eq!(memory.get_memory(), &vec![user]);
+    }
+
+    #[test]
+    fn remove_unanswered_tool_calls_keeps_answered_calls() {
+        let messages = vec![
+            tool_calls(&["call_1", "call_2"]),
+            tool_result("call_1"),
+            tool_result("call_2"),
+        ];
+        let mut memory = ChatMemory::from(messages.clone());
+
+        memory.remove_unanswered_tool_calls();
+
+        assert_eq!(memory.get_memory(), &messages);
+    }
 }
@@ -1,5 +1,22 @@
+pub mod attachment;
+pub mod budget;
+pub mod builder;
+pub mod checkpoint;
+pub mod command;
+pub mod editor;
+pub mod environment;
 pub mod error;
+pub mod event;
+pub mod event_bus;
+pub mod input_queue;
+pub mod loop_detector;
 pub mod memory;
+pub mod output_limit;
+pub mod plan;
 pub mod session;
+pub mod sub_agent;
 pub mod system_prompt;
-pub mod event;
\ No newline at end of file
+pub mod todo;
+pub mod transcript;
+pub mod turn;
+pub mod verification;
@@ -0,0 +1,409 @@
+use crate::client::models::{Function, Parameters, Property, Tool, ToolType};
+use std::collections::HashMap;
+use std::env;
+use std::fs::{self, DirBuilder};
+#[cfg(unix)]
+use std::os::unix::fs::DirBuilderExt;
+use std::path::PathBuf;
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Name of the tool the assistant calls to page through a truncated tool output.
+pub const READ_TOOL_OUTPUT_TOOL: &str = "read_tool_output";
+
+/// Default limit on the size of a tool output added to the conversation, in tokens.
+pub const DEFAULT_MAX_OUTPUT_TOKENS: usize = 8_000;
+
+// Rough number of characters per token, used to estimate output sizes
+const CHARS_PER_TOKEN: usize = 4;
+
+// Number of limiters created, keeping the outputs of sub-agents apart
+static LIMITERS: AtomicUsize = AtomicUsize::new(0);
+
+/// Keeps large tool outputs out of the conversation.
+///
+/// Outputs over the token limit are replaced by their first and last lines,
+/// and the full output is saved to a temporary file. The assistant can read
+/// the rest of it page by page with the [`READ_TOOL_OUTPUT_TOOL`] tool.
+/// Lines longer than half of the limit are split into several lines, so every
+/// page fits. Tokens are estimated at four characters each. The saved outputs
+/// are only readable by the user and are removed when the limiter is dropped.
+///
+/// # Examples
+///
+/// ```rust
+/// use code_g::session::output_limit::OutputLimiter;
+///
+/// let mut limiter = OutputLimiter::new(10);
+/// let output: String = (1..=100).map(|n| format!("line {}\n", n)).collect();
+///
+/// let limited = limiter.limit(&output);
+/// assert!(limited.starts_with("line 1\n"));
+/// assert!(limited.contains("saved as output 1"));
+/// assert!(limited.ends_with("line 100"));
+///
+/// let page = limiter.read("1", 50, 51).unwrap();
+/// assert_eq!(page, "Lines 50-51 of 100 of output 1:\n50: line 50\n51: line 51");
+/// ```
+pub struct OutputLimiter {
+    /// Maximum size of an output added to the conversation, in tokens
+    max_tokens: usize,
+    /// Directory the full outputs are saved to
+    dir: PathBuf,
+    /// Whether this limiter created the directory, which only it may remove
+    created_dir: bool,
+    /// Paths of the saved outputs, by id
+    outputs: HashMap<String, PathBuf>,
+}
+
+impl Default for OutputLimiter {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_OUTPUT_TOKENS)
+    }
+}
+
+impl OutputLimiter {
+    /// Creates a limiter for outputs over the given number of tokens.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_tokens` - Maximum size of an output added to the conversation
+    pub fn new(max_tokens: usize) -> Self {
+        Self {
+            max_tokens,
+            dir: env::temp_dir().join(format!(
+                "code-g-outputs-{}-{}-{}",
+                process::id(),
+                LIMITERS.fetch_add(1, Ordering::Relaxed),
+                SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map_or(0, |time| time.subsec_nanos())
+            )),
+            created_dir: false,
+            outputs: HashMap::new(),
+        }
+    }
+
+    /// Returns the maximum size of an output added to the conversation, in tokens.
+    pub fn max_tokens(&self) -> usize {
+        self.max_tokens
+    }
+
+    /// Returns `true` once an output has been saved, so the paging tool is useful.
+    pub fn has_outputs(&self) -> bool {
+        !self.outputs.is_empty()
+    }
+
+    /// Truncates an output that exceeds the token limit.
+    ///
+    /// Half of the limit is spent on the first lines and half on the last lines
+    /// of the output. If the full output cannot be saved, the note says so and
+    /// only the truncated view is kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `output` - The output of a tool
+    ///
+    /// # Returns
+    ///
+    /// The output itself if it fits, otherwise its head and tail with a note on
+    /// how to read the rest.
+    pub fn limit(&mut self, output: &str) -> String {
+        let max_chars = self.max_tokens * CHARS_PER_TOKEN;
+        if output.len() <= max_chars {
+            return output.to_string();
+        }
+
+        let lines = split_lines(output, max_chars / 2);
+        let head = take_lines(lines.iter().copied(), max_chars / 2);
+        let tail = take_lines(lines.iter().rev().copied(), max_chars / 2).min(lines.len() - head);
+        let omitted = lines.len() - head - tail;
+
+        let note = match self.save(output) {
+            Ok(id) => format!(
+                "[... {} of {} lines omitted. The full output was saved as output {}. Call {} with output_id \"{}\" and a line range to read the omitted lines ...]",
+                omitted,
+                lines.len(),
+                id,
+                READ_TOOL_OUTPUT_TOOL,
+                id
+            ),
+            Err(e) => format!(
+                "[... {} of {} lines omitted. The full output could not be saved: {} ...]",
+                omitted,
+                lines.len(),
+                e
+            ),
+        };
+
+        let mut view: Vec<&str> = lines[..head].to_vec();
+        view.push(&note);
+        view.extend_from_slice(&lines[lines.len() - tail..]);
+        view.join("\n")
+    }
+
+    /// Reads a range of lines of a saved output, within the token limit.
+    ///
+    /// Lines are numbered from 1 and the range is inclusive. A range that does
+    /// not fit the limit is cut short, and the response says where to continue.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the saved output
+    /// * `start` - The first line to read
+    /// * `end` - The last line to read
+    ///
+    /// # Errors
+    ///
+    /// Returns a message for the assistant if the output or the lines do not exist.
+    pub fn read(&self, id: &str, start: usize, end: usize) -> Result<String, String> {
+        let path = self
+            .outputs
+            .get(id)
+            .ok_or_else(|| format!("There is no saved output {}", id))?;
+        let content =
+            fs::read_to_string(path).map_err(|e| format!("Failed to read output {}: {}", id, e))?;
+        let max_chars = self.max_tokens * CHARS_PER_TOKEN;
+        let lines = split_lines(&content, max_chars / 2);
+        if start == 0 || start > end || start > lines.len() {
+            return Err(format!(
+                "Invalid line range {}-{}, output {} has lines 1 to {}",
+                start,
+                end,
+                id,
+                lines.len()
+            ));
+        }
+
+        let requested = &lines[start - 1..end.min(lines.len())];
+        let mut page = Vec::new();
+        let mut size = 0;
+        for (offset, line) in requested.iter().enumerate() {
+            let numbered = format!("{}: {}", start + offset, line);
+            size += numbered.len() + 1;
+            if size > max_chars && !page.is_empty() {
+                break;
+            }
+            page.push(numbered);
+        }
+
+        let last = start + page.len() - 1;
+        let mut response = format!(
+            "Lines {}-{} of {} of output {}:\n{}",
+            start,
+            last,
+            lines.len(),
+            id,
+            page.join("\n")
+        );
+        if last < end.min(lines.len()) {
+            response.push_str(&format!(
+                "\n[... the range was cut short to fit the limit, continue at line {} ...]",
+                last + 1
+            ));
+        }
+        Ok(response)
+    }
+
+    /// Returns the definition of the tool used to page through saved outputs.
+    pub fn read_tool() -> Tool {
+        let property = |description: &str| Property {
+            prop_type: "string".to_string(),
+            description: description.to_string(),
+        };
+
+        Tool {
+            tool_type: ToolType::Function,
+            function: Function {
+                name: READ_TOOL_OUTPUT_TOOL.to_string(),
+                description:
+                    "Read a range of lines of a tool output that was too large to show in full."
+                        .to_string(),
+                parameters: Parameters {
+                    param_type: "object".to_string(),
+                    properties: HashMap::from([
+                        (
+                            "output_id".to_string(),
+                            property("The id of the saved output"),
+                        ),
+                        (
+                            "start_line".to_string(),
+                            property("The first line to read, starting at 1"),
+                        ),
+                        (
+                            "end_line".to_string(),
+                            property("The last line to read, inclusive"),
+                        ),
+                    ]),
+                    required: vec![
+                        "output_id".to_string(),
+                        "start_line".to_string(),
+                        "end_line".to_string(),
+                    ],
+                    additional_properties: false,
+                },
+                strict: true,
+            },
+        }
+    }
+
+    fn save(&mut self, output: &str) -> Result<String, std::io::Error> {
+        let id = (self.outputs.len() + 1).to_string();
+        let path = self.dir.join(format!("output-{}.txt", id));
+        if !self.created_dir {
+            // A directory that already exists could belong to someone else, so
+            // creating it fails instead of saving outputs where others can read them
+            let mut builder = DirBuilder::new();
+            #[cfg(unix)]
+            builder.mode(0o700);
+            builder.create(&self.dir)?;
+            self.created_dir = true;
+        }
+        fs::write(&path, output)?;
+        self.outputs.insert(id.clone(), path);
+        Ok(id)
+    }
+}
+
+impl Drop for OutputLimiter {
+    fn drop(&mut self) {
+        if self.created_dir {
+            let _ = fs::remove_dir_all(&self.dir);
+        }
+    }
+}
+
+/// Splits an output into lines, splitting lines longer than `max_chars` into
+/// several lines of at most `max_chars` bytes.
+fn split_lines(output: &str, max_chars: usize) -> Vec<&str> {
+    let max_chars = max_chars.max(1);
+    let mut lines = Vec::new();
+    for mut line in output.lines() {
+        while line.len() > max_chars {
+            // Split at the last character boundary within the limit, but keep at
+            // least one character so the loop always makes progress
+            let split = (1..=max_chars)
+                .rev()
+                .find(|&index| line.is_char_boundary(index))
+                .unwrap_or_else(|| line.chars().next().map_or(1, char::len_utf8));
+            let (chunk, rest) = line.split_at(split);
+            lines.push(chunk);
+            line = rest;
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+/// Counts how many lines fit into the given number of characters.
+fn take_lines<'a>(lines: impl Iterator<Item = &'a str>, max_chars: usize) -> usize {
+    let mut size = 0;
+    lines
+        .take_while(|line| {
+            size += line.len() + 1;
+            size <= max_chars
+        })
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn output(lines: usize) -> String {
+        (1..=lines).map(|n| format!("line {}\n", n)).collect()
+    }
+
+    #[test]
+    fn limit_keeps_small_outputs() {
+        let mut limiter = OutputLimiter::new(100);
+
+        assert_eq!(limiter.limit("short"), "short");
+        assert!(!limiter.has_outputs());
+    }
+
+    #[test]
+    fn limit_keeps_head_and_tail_and_saves_output() {
+        let mut limiter = OutputLimiter::new(10);
+
+        let limited = limiter.limit(&output(100));
+
+        let lines: Vec<&str> = limited.lines().collect();
+        assert_eq!(lines[..2], ["line 1", "line 2"]);
+        assert!(lines[2].starts_with("[... 96 of 100 lines omitted."));
+        assert_eq!(lines[3..], ["line 99", "line 100"]);
+        assert!(limiter.has_outputs());
+    }
+
+    #[test]
+    fn read_pages_within_the_limit() {
+        let mut limiter = OutputLimiter::new(10);
+        limiter.limit(&output(100));
+
+        let page = limiter.read("1", 1, 100).unwrap();
+
+        assert_eq!(
+            page,
+            "Lines 1-4 of 100 of output 1:\n1: line 1\n2: line 2\n3: line 3\n4: line 4\n\
+             [... the range was cut short to fit the limit, continue at line 5 ...]"
+        );
+        assert!(limiter.read("1", 101, 120).is_err());
+        assert!(limiter.read("2", 1, 2).is_err());
+    }
+
+    #[test]
+    fn read_splits_lines_longer_than_the_limit() {
+        let mut limiter = OutputLimiter::new(10);
+        limiter.limit(&"é".repeat(50));
+
+        let page = limiter.read("1", 1, 5).unwrap();
+
+        assert_eq!(
+            page,
+            format!(
+                "Lines 1-1 of 5 of output 1:\n1: {}\n\
+                 [... the range was cut short to fit the limit, continue at line 2 ...]",
+                "é".repeat(10)
+            )
+        );
+    }
+
+    #[test]
+    fn drop_removes_the_saved_outputs() {
+        let mut limiter = OutputLimiter::new(10);
+        limiter.limit(&output(100));
+        let dir = limiter.dir.clone();
+        assert!(dir.is_dir());
+
+        drop(limiter);
+
+        assert!(!dir.exists());
+    }
+
+    #[test]
+    fn limit_does_not_save_to_a_directory_it_did_not_create() {
+        let mut limiter = OutputLimiter::new(10);
+        fs::create_dir(&limiter.dir).unwrap();
+        let dir = limiter.dir.clone();
+
+        let limited = limiter.limit(&output(100));
+        drop(limiter);
+
+        assert!(limited.contains("The full output could not be saved"));
+        assert!(dir.is_dir());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn save_makes_outputs_readable_by_the_user_only() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let mut limiter = OutputLimiter::new(10);
+        limiter.limit(&output(100));
+
+        let mode = fs::metadata(&limiter.dir).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o700);
+    }
+}
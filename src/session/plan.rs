@@ -0,0 +1,178 @@
+use crate::client::models::{Function, Parameters, Property, Tool, ToolType};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Name of the tool the assistant calls to mark a step of the approved plan as done.
+pub const COMPLETE_PLAN_STEP_TOOL: &str = "complete_plan_step";
+
+/// A single step of a plan.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlanStep {
+    /// What the step does
+    pub description: String,
+    /// Whether the assistant has finished the step
+    pub done: bool,
+}
+
+/// A step-by-step plan produced in plan mode and approved by the user.
+///
+/// # Examples
+///
+/// ```rust
+/// use code_g::session::plan::Plan;
+///
+/// let mut plan = Plan::parse("Here is my plan:\n1. Read main.rs\n2. Extract the parser\n\nOK?").unwrap();
+///
+/// plan.complete_step(1).unwrap();
+///
+/// assert_eq!(plan.to_string(), "1. [x] Read main.rs\n2. [ ] Extract the parser");
+/// assert!(!plan.is_complete());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Plan {
+    pub steps: Vec<PlanStep>,
+}
+
+impl Plan {
+    /// Extracts the numbered steps from a message.
+    ///
+    /// Lines starting with a number followed by `.` or `)` are steps. Any other
+    /// text, such as an introduction or a closing question, is ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The message containing the plan
+    ///
+    /// # Returns
+    ///
+    /// The plan, or `None` if the message contains no numbered steps.
+    pub fn parse(message: &str) -> Option<Self> {
+        let steps: Vec<PlanStep> = message
+            .lines()
+            .filter_map(|line| {
+                let line = line.trim();
+                let digits = line.chars().take_while(|c| c.is_ascii_digit()).count();
+                if digits == 0 {
+                    return None;
+                }
+                let rest = line[digits..].strip_prefix(['.', ')'])?.trim();
+                (!rest.is_empty()).then(|| PlanStep {
+                    description: rest.to_string(),
+                    done: false,
+                })
+            })
+            .collect();
+
+        (!steps.is_empty()).then_some(Plan { steps })
+    }
+
+    /// Marks a step as done.
+    ///
+    /// # Arguments
+    ///
+    /// * `number` - The number of the step, starting at 1
+    ///
+    /// # Errors
+    ///
+    /// Returns a message for the assistant if the plan has no such step.
+    pub fn complete_step(&mut self, number: usize) -> Result<(), String> {
+        let count = self.steps.len();
+        match number
+            .checked_sub(1)
+            .and_then(|index| self.steps.get_mut(index))
+        {
+            Some(step) => {
+                step.done = true;
+                Ok(())
+            }
+            None => Err(format!(
+                "The plan has no step {}, steps are numbered 1 to {}",
+                number, count
+            )),
+        }
+    }
+
+    /// Returns `true` if every step is done.
+    pub fn is_complete(&self) -> bool {
+        self.steps.iter().all(|step| step.done)
+    }
+
+    /// Returns the definition of the tool used to mark steps as done.
+    pub fn step_tool() -> Tool {
+        Tool {
+            tool_type: ToolType::Function,
+            function: Function {
+                name: COMPLETE_PLAN_STEP_TOOL.to_string(),
+                description: "Mark a step of the approved plan as done. Call this right after finishing each step.".to_string(),
+                parameters: Parameters {
+                    param_type: "object".to_string(),
+                    properties: HashMap::from([(
+                        "step".to_string(),
+                        Property {
+                            prop_type: "string".to_string(),
+                            description: "The number of the finished step".to_string(),
+                        },
+                    )]),
+                    required: vec!["step".to_string()],
+                    additional_properties: false,
+                },
+                strict: true,
+            },
+        }
+    }
+}
+
+impl fmt::Display for Plan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let steps: Vec<String> = self
+            .steps
+            .iter()
+            .enumerate()
+            .map(|(i, step)| {
+                let mark = if step.done { "x" } else { " " };
+                format!("{}. [{}] {}", i + 1, mark, step.description)
+            })
+            .collect();
+        write!(f, "{}", steps.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_reads_numbered_steps_and_ignores_other_lines() {
+        let plan = Plan::parse(
+            "I will:\n  1) Add the module\n2. Move the tests\n- a note\n3.\n10. Update docs",
+        )
+        .unwrap();
+
+        let descriptions: Vec<&str> = plan
+            .steps
+            .iter()
+            .map(|step| step.description.as_str())
+            .collect();
+        assert_eq!(
+            descriptions,
+            vec!["Add the module", "Move the tests", "Update docs"]
+        );
+        assert_eq!(Plan::parse("No plan here"), None);
+    }
+
+    #[test]
+    fn complete_step_marks_steps_until_plan_is_complete() {
+        let mut plan = Plan::parse("1. One\n2. Two").unwrap();
+
+        plan.complete_step(2).unwrap();
+        assert!(!plan.is_complete());
+        plan.complete_step(1).unwrap();
+
+        assert!(plan.is_complete());
+        assert_eq!(
+            plan.complete_step(3),
+            Err("The plan has no step 3, steps are numbered 1 to 2".to_string())
+        );
+        assert!(plan.complete_step(0).is_err());
+    }
+}
@@ -1,16 +1,63 @@
 use crate::client::error::{ChatClientError, ErrorRetryStrategy};
-use crate::client::models::{AssistantMessage, ChatMessage, ChatResult, Model};
+use crate::client::models::{
+    AssistantMessage, ChatMessage, ChatResult, Model, Tool as ToolModel, ToolCall, Usage,
+};
 use crate::client::providers::openai::schema::Model as OpenAiModel;
 use crate::client::traits::ChatClient;
+use crate::config::settings::Settings;
+use crate::hooks::config::HooksConfig;
+use crate::hooks::runner::{HookEvent, HookRunner};
+use crate::permissions::config::{PermissionDecision, PermissionRule, PermissionsConfig};
+use crate::permissions::grant::{ApprovalResponse, GrantScope, grant_rule};
+use crate::permissions::mode::PermissionMode;
+use crate::permissions::policy::PermissionPolicy;
+use crate::session::attachment::attach;
+use crate::session::budget::{BudgetLimit, TurnBudget, TurnBudgetTracker};
+use crate::session::checkpoint::CheckpointStore;
+use crate::session::command::Command;
+use crate::session::editor::{
+    EDITOR_PROMPT, EDITOR_TOOLS, Editor, EditorEventHandler, HAND_OFF_EDITS_TOOL, Handoff,
+};
+use crate::session::environment::{ENVIRONMENT_TEMPLATE, PromptEnvironment};
 use crate::session::error::{ChatSessionError, ChatSessionErrorHandling};
 use crate::session::event::{Action, Event, EventHandler};
+use crate::session::event_bus::{EventBus, EventSubscriber};
+use crate::session::input_queue::InputQueue;
+use crate::session::loop_detector::LoopDetector;
 use crate::session::memory::ChatMemory;
+use crate::session::output_limit::{OutputLimiter, READ_TOOL_OUTPUT_TOOL};
+use crate::session::plan::{COMPLETE_PLAN_STEP_TOOL, Plan};
+use crate::session::sub_agent::{SUB_AGENT_PROMPT, SubAgentEventHandler, TASK_TOOL, task_tool};
 use crate::session::system_prompt::{SYSTEM_PROMPT, SystemPromptConfig};
+use crate::session::todo::{TODO_READ_TOOL, TODO_WRITE_TOOL, TodoList};
+use crate::session::transcript::{Transcript, TranscriptFormat};
+use crate::session::turn::{ToolCallDecision, ToolCallOutcome, TurnResult};
+use crate::session::verification::{CheckResult, VerificationConfig, run_check};
+use crate::tools::registry::{EDIT_TOOLS, Registry};
 use crate::tools::traits::ToolRegistry;
-use std::collections::HashMap;
+use std::cell::RefCell;
+use std::env;
+use std::fs;
+use std::mem;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-// Maximum number of iterations per message to prevent infinite loops
-const MAX_ITERATIONS: usize = 50;
+// Message sent on the user's behalf to start carrying out an approved plan
+const EXECUTE_PLAN_PROMPT: &str = "Carry out the approved plan.";
+
+// System note asking the assistant to wrap up once the turn budget is exhausted
+const BUDGET_EXCEEDED_NOTE: &str = "The budget for this turn has been exhausted. Do not call any more tools. Reply to the user with a short summary of what you have done so far and what remains to be done.";
+
+// System note telling the assistant to break out of a tool calling loop
+const LOOP_DETECTED_NOTE: &str = "You are repeating the same tool calls without making progress. Stop repeating them and try a different approach, or explain to the user what is blocking you. Detected:";
+
+// System note telling the assistant which work of a failed turn is already done
+const FAILED_TURN_NOTE: &str = "The previous turn failed with an error after some tool calls ran. Their changes are in place, so check them before repeating any of them. Tools that ran:";
+
+// System note giving the assistant the output of a failing check command
+const VERIFICATION_FAILED_NOTE: &str = "Your changes do not pass the project's checks yet. Fix the problems below, then reply to the user once you are done. Output of";
 
 /// Core component that orchestrates conversations between a user and an AI assistant.
 ///
@@ -35,12 +82,57 @@ const MAX_ITERATIONS: usize = 50;
 pub struct ChatSession {
     /// Manages conversation history and context
     memory: ChatMemory,
-    /// Chat client for API communication
-    client: Box<dyn ChatClient>,
+    /// Chat client for API communication, shared with sub-agents
+    client: Arc<dyn ChatClient>,
     /// Registry of available tools for the AI to use
     tools: Box<dyn ToolRegistry>,
-    /// Handler for events and user interactions
+    /// Interactive frontend that receives events and answers user actions
     event_handler: Box<dyn EventHandler>,
+    /// Broadcasts events to passive subscribers
+    event_bus: EventBus,
+    /// Snapshots of files modified by tools, grouped by turn
+    checkpoints: CheckpointStore,
+    /// Token usage accumulated over all completions in the session
+    usage: Usage,
+    /// Model used for chat completions
+    model: Model,
+    /// Limits on the work done per turn
+    budget: TurnBudget,
+    /// Runs the configured lifecycle hooks
+    hooks: HookRunner,
+    /// Decides which tool calls run, need approval or are denied
+    permissions: PermissionPolicy,
+    /// How much the assistant may do without asking
+    mode: PermissionMode,
+    /// Mode restored when a plan made in plan mode is approved
+    mode_before_plan: PermissionMode,
+    /// The approved plan the assistant is carrying out
+    plan: Option<Plan>,
+    /// Directory of the project settings file that "always allow" grants are saved to,
+    /// also used to resolve `@` mentions
+    project_dir: Option<PathBuf>,
+    /// Whether the assistant can delegate tasks to sub-agents
+    sub_agents: bool,
+    /// Model that applies the changes the assistant hands off, if paired
+    editor: Option<Editor>,
+    /// Whether the assistant can keep a todo list
+    todo_tools: bool,
+    /// The todo list the assistant keeps to track its work
+    todos: TodoList,
+    /// Truncates large tool outputs and keeps them for paging
+    output_limiter: OutputLimiter,
+    /// Messages typed by the user while the assistant is working
+    input_queue: InputQueue,
+    /// Tool calls of the running turn, returned in its [`TurnResult`]
+    turn_tool_calls: Vec<ToolCallOutcome>,
+    /// The budget limit the running turn exhausted, returned in its [`TurnResult`]
+    turn_budget_exhausted: Option<BudgetLimit>,
+    /// Check command run after turns that modified files
+    verification: VerificationConfig,
+    /// Whether files were modified since the turn started or was last checked
+    files_modified: bool,
+    /// Message of the last turn that failed, sent again by `/retry`
+    failed_message: Option<String>,
 }
 
 impl ChatSession {
@@ -74,27 +166,370 @@ impl ChatSession {
             }
         };
 
+        Self::from_parts(
+            memory,
+            Arc::from(client),
+            Model::OpenAi(OpenAiModel::Gpt4oMini),
+            tools,
+            event_handler,
+        )
+    }
+
+    /// Creates a session nested in another one, such as a sub-agent or the editor.
+    ///
+    /// The nested session starts from its own system prompt and uses the
+    /// given client, model, tools and event handler. It publishes to the outer
+    /// session's event bus, so subscribers also see nested tool calls and
+    /// approvals. Everything else starts from the defaults, for the caller to
+    /// share from the outer session.
+    ///
+    /// # Arguments
+    ///
+    /// * `prompt` - The system prompt of the nested session
+    /// * `client` - The client to send the nested session's requests with
+    /// * `model` - The model of the nested session
+    /// * `tools` - The tools available in the nested session
+    /// * `event_handler` - Receives the nested session's events and actions
+    /// * `event_bus` - The event bus of the outer session
+    fn nested(
+        prompt: &str,
+        client: Arc<dyn ChatClient>,
+        model: Model,
+        tools: Registry,
+        event_handler: Box<dyn EventHandler>,
+        event_bus: EventBus,
+    ) -> Self {
+        Self {
+            event_bus,
+            ..Self::from_parts(
+                ChatMemory::from(vec![ChatMessage::System {
+                    content: prompt.to_string(),
+                }]),
+                client,
+                model,
+                Box::new(tools),
+                event_handler,
+            )
+        }
+    }
+
+    /// Creates a session from its memory, client, model, tools and event handler.
+    ///
+    /// Everything else starts from the defaults, which the constructors and
+    /// builder methods adjust.
+    ///
+    /// # Arguments
+    ///
+    /// * `memory` - The initial memory, holding the system prompt if there is one
+    /// * `client` - The client to send requests with
+    /// * `model` - The model to use
+    /// * `tools` - The tools available to the assistant
+    /// * `event_handler` - Receives the session's events and actions
+    fn from_parts(
+        memory: ChatMemory,
+        client: Arc<dyn ChatClient>,
+        model: Model,
+        tools: Box<dyn ToolRegistry>,
+        event_handler: Box<dyn EventHandler>,
+    ) -> Self {
         Self {
             memory,
             client,
             tools,
             event_handler,
+            event_bus: EventBus::new(),
+            checkpoints: CheckpointStore::new(),
+            usage: Usage::default(),
+            model,
+            budget: TurnBudget::default(),
+            hooks: HookRunner::default(),
+            permissions: PermissionPolicy::default(),
+            mode: PermissionMode::default(),
+            mode_before_plan: PermissionMode::default(),
+            plan: None,
+            project_dir: None,
+            sub_agents: false,
+            editor: None,
+            todo_tools: false,
+            todos: TodoList::default(),
+            output_limiter: OutputLimiter::default(),
+            input_queue: InputQueue::new(),
+            turn_tool_calls: vec![],
+            turn_budget_exhausted: None,
+            verification: VerificationConfig::default(),
+            files_modified: false,
+            failed_message: None,
+        }
+    }
+
+    /// Sets the hooks run on lifecycle events such as tool calls.
+    ///
+    /// # Arguments
+    ///
+    /// * `hooks` - The [`HooksConfig`] with the hooks to run
+    pub fn with_hooks(mut self, hooks: HooksConfig) -> Self {
+        self.hooks = HookRunner::new(hooks);
+        self
+    }
+
+    /// Sets the rules deciding which tool calls run, need approval or are denied.
+    ///
+    /// Calls matched by no rule fall back to the tool's own approval default.
+    ///
+    /// # Arguments
+    ///
+    /// * `permissions` - The [`PermissionsConfig`] with the rules to apply
+    pub fn with_permissions(mut self, permissions: PermissionsConfig) -> Self {
+        self.permissions = PermissionPolicy::new(permissions);
+        self
+    }
+
+    /// Sets the permission mode the session starts in.
+    ///
+    /// The mode can be changed during the session with the `/mode` command.
+    ///
+    /// # Arguments
+    ///
+    /// * `mode` - The [`PermissionMode`] to start in
+    pub fn with_permission_mode(mut self, mode: PermissionMode) -> Self {
+        if mode != self.mode {
+            self.memory.add_message(ChatMessage::System {
+                content: mode.system_note().to_string(),
+            });
+        }
+        self.mode = mode;
+        self
+    }
+
+    /// Sets the project directory that "always allow" grants for the project are saved to.
+    ///
+    /// Without a project directory, project grants only apply to the current session.
+    ///
+    /// # Arguments
+    ///
+    /// * `project_dir` - The directory containing the project's `.code-g` folder
+    pub fn with_project_dir(mut self, project_dir: PathBuf) -> Self {
+        self.project_dir = Some(project_dir);
+        self
+    }
+
+    /// Lets the assistant delegate focused, read-only tasks to sub-agents.
+    ///
+    /// A sub-agent is a separate session with its own memory and only the
+    /// read-only tools of this session. Only its final summary is added to this
+    /// session's conversation, which keeps large searches out of the context.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether the task tool is offered to the assistant
+    pub fn with_sub_agents(mut self, enabled: bool) -> Self {
+        self.sub_agents = enabled;
+        self
+    }
+
+    /// Pairs the assistant with an editor model that makes its file changes.
+    ///
+    /// The assistant acts as the architect: instead of the edit tools it gets a
+    /// tool to hand off a description of its changes. The editor, a separate
+    /// session with its own memory and only the file reading and editing tools,
+    /// applies them and reports back. This lets a strong model decide what to
+    /// change while a cheaper one writes the edits. The editor's tool calls go
+    /// through the same permission rules and approvals as the assistant's, and
+    /// its token usage is added to this session.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - [`ChatClient`] implementation the editor uses
+    /// * `model` - The [`Model`] the editor uses
+    pub fn with_editor(mut self, client: Box<dyn ChatClient>, model: Model) -> Self {
+        self.editor = Some(Editor {
+            client: Arc::from(client),
+            model,
+        });
+        self
+    }
+
+    /// Lets the assistant keep a todo list to track multi-step work.
+    ///
+    /// The list is kept by the session and shown by the frontend as it changes.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether the todo tools are offered to the assistant
+    pub fn with_todos(mut self, enabled: bool) -> Self {
+        self.todo_tools = enabled;
+        self
+    }
+
+    /// Sets the limit on the size of a tool output added to the conversation.
+    ///
+    /// Larger outputs are cut down to their first and last lines, and the full
+    /// output is saved to a temporary file the assistant can page through.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_tokens` - Maximum size of a tool output, in tokens
+    pub fn with_output_limit(mut self, max_tokens: usize) -> Self {
+        self.output_limiter = OutputLimiter::new(max_tokens);
+        self
+    }
+
+    /// Sets the queue of messages the user types while the assistant is working.
+    ///
+    /// The frontend pushes messages to a clone of the queue during a turn. Queued
+    /// messages are added to the running turn before the next completion, and
+    /// messages still queued when the turn ends are sent as the next turns.
+    ///
+    /// # Arguments
+    ///
+    /// * `input_queue` - The [`InputQueue`] shared with the frontend
+    pub fn with_input_queue(mut self, input_queue: InputQueue) -> Self {
+        self.input_queue = input_queue;
+        self
+    }
+
+    /// Sets the check command run after turns that modified files.
+    ///
+    /// The command runs in the project directory, or the current directory if
+    /// none is set. When it fails, its output is sent to the assistant, which
+    /// continues the turn until the check passes or its retries are used up.
+    ///
+    /// # Arguments
+    ///
+    /// * `verification` - The [`VerificationConfig`] with the command and retry budget
+    pub fn with_verification(mut self, verification: VerificationConfig) -> Self {
+        self.verification = verification;
+        self
+    }
+
+    /// Describes the environment the assistant operates in in the system prompt.
+    ///
+    /// The `{{variable}}` placeholders of the system prompt are replaced with the
+    /// environment's values. A prompt without placeholders, such as the default
+    /// prompt, gets a section describing the environment appended instead. The
+    /// tools are taken from this session, so this should be called after
+    /// [`ChatSession::with_sub_agents`]. Sessions without a system prompt are
+    /// left unchanged.
+    ///
+    /// # Arguments
+    ///
+    /// * `environment` - The [`PromptEnvironment`] to describe
+    pub fn with_environment(mut self, mut environment: PromptEnvironment) -> Self {
+        environment.tools = self
+            .available_tools()
+            .into_iter()
+            .map(|tool| tool.function.name)
+            .collect();
+
+        let mut messages = self.memory.get_memory().clone();
+        if let Some(ChatMessage::System { content }) = messages.first_mut() {
+            *content = if PromptEnvironment::is_used_in(content) {
+                environment.render(content)
+            } else {
+                format!(
+                    "{}\n\n{}",
+                    content.trim_end(),
+                    environment.render(ENVIRONMENT_TEMPLATE)
+                )
+            };
+            self.memory = ChatMemory::from(messages);
         }
+        self
+    }
+
+    /// Sets the model used for chat completions.
+    ///
+    /// The model also determines the price used for the cost budget.
+    ///
+    /// # Arguments
+    ///
+    /// * `model` - The [`Model`] to use
+    pub fn with_model(mut self, model: Model) -> Self {
+        self.model = model;
+        self
+    }
+
+    /// Sets the budget that limits the work done per turn.
+    ///
+    /// # Arguments
+    ///
+    /// * `budget` - The [`TurnBudget`] to apply to every turn
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use code_g::session::budget::TurnBudget;
+    /// use code_g::session::session::ChatSession;
+    /// use code_g::client::providers::openai::client::OpenAIClient;
+    /// use code_g::tools::registry::Registry;
+    /// use code_g::session::system_prompt::SystemPromptConfig;
+    /// use code_g::tui::tui::Tui;
+    ///
+    /// let client = Box::new(OpenAIClient::new("api_key".to_string()));
+    /// let tools = Box::new(Registry::new());
+    /// let event_handler = Box::new(Tui::new());
+    /// let session = ChatSession::new(client, tools, event_handler, SystemPromptConfig::Default)
+    ///     .with_turn_budget(TurnBudget {
+    ///         max_iterations: 10,
+    ///         max_cost: Some(0.25),
+    ///         ..TurnBudget::default()
+    ///     });
+    /// ```
+    pub fn with_turn_budget(mut self, budget: TurnBudget) -> Self {
+        self.budget = budget;
+        self
+    }
+
+    /// Returns a receiver for all events the session emits from now on.
+    ///
+    /// Events are delivered to subscribers in addition to the interactive
+    /// [`EventHandler`], which remains the only receiver of user actions.
+    pub fn subscribe(&self) -> tokio::sync::broadcast::Receiver<Event> {
+        self.event_bus.subscribe()
+    }
+
+    /// Runs a subscriber that observes all events the session emits from now on.
+    ///
+    /// The subscriber runs in its own task until the session is dropped, so
+    /// several subscribers such as a transcript writer and a metrics logger can
+    /// observe the session concurrently without blocking it.
+    ///
+    /// # Arguments
+    ///
+    /// * `subscriber` - The [`EventSubscriber`] to run
+    ///
+    /// # Returns
+    ///
+    /// The handle of the subscriber task, which completes once the session is dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a Tokio runtime.
+    pub fn add_subscriber(
+        &self,
+        subscriber: Box<dyn EventSubscriber>,
+    ) -> tokio::task::JoinHandle<()> {
+        self.event_bus.spawn_subscriber(subscriber)
     }
 
     /// Runs an interactive chat loop that continues until the user exits.
     ///
     /// Provides a complete interactive chat experience by continuously prompting for
-    /// user input, processing each message, and displaying responses. The loop exits
-    /// when the user types "exit".
+    /// user input, processing each message, and displaying responses. Slash commands
+    /// such as `/undo` and `/checkpoints` are handled by the session instead of being
+    /// sent to the assistant. The loop exits when the user types "exit".
+    ///
+    /// Errors do not end the session: a failed turn is rolled back and reported with
+    /// [`Event::Error`], after which the user can continue or send the message again
+    /// with `/retry`. The session only ends early if user input cannot be read.
     ///
     /// # Returns
     ///
-    /// Returns [`Ok(())`] when session completes normally.
+    /// Returns [`Ok(())`] when session completes.
     ///
     /// # Errors
     ///
-    /// Returns [`ChatSessionError`] if an error occurs during conversation.
+    /// Currently never returns an error, errors are reported as events instead.
     ///
     /// # Examples
     ///
@@ -119,33 +554,246 @@ impl ChatSession {
     /// ```
     pub async fn run(&mut self) -> Result<(), ChatSessionError> {
         // Clear the terminal
-        self.event_handler.handle_event(Event::SessionStarted);
+        self.emit(Event::SessionStarted);
+        if self.mode != PermissionMode::Default {
+            self.emit(Event::PermissionModeChanged { mode: self.mode });
+        }
 
         loop {
-            let user_input = self
-                .event_handler
-                .handle_action(Action::RequestUserInput)
-                .unwrap(); // TODO: Handle errors
+            // Messages typed during the last turn are sent before asking for more
+            let user_input = match self.input_queue.pop() {
+                Some(queued) => Ok(queued),
+                None => self.event_handler.handle_action(Action::RequestUserInput),
+            };
+            let user_input = match user_input {
+                Ok(user_input) => user_input,
+                Err(e) => {
+                    // Without input the session cannot continue, so end it
+                    self.emit(Event::Error {
+                        message: format!("Failed to read user input: {}", e),
+                    });
+                    break;
+                }
+            };
 
             if user_input == "exit" {
                 // Exit the loop
                 break;
             }
 
-            self.send_message(&user_input).await?;
+            match Command::parse(&user_input) {
+                Ok(Some(command)) => {
+                    self.handle_command(command).await;
+                    continue;
+                }
+                // A mistyped command is reported instead of being sent to the assistant
+                Err(e) => {
+                    self.emit(Event::Error {
+                        message: e.to_string(),
+                    });
+                    continue;
+                }
+                Ok(None) => {}
+            }
+
+            self.send_user_message(user_input).await;
         }
 
-        self.event_handler.handle_event(Event::SessionEnded);
+        self.emit(Event::SessionEnded);
 
         Ok(())
     }
 
-    /// Sends a message to the AI assistant and returns the response.
+    /// Runs a single turn for the given prompt without prompting for user input.
+    ///
+    /// This is the non-interactive counterpart of [`ChatSession::run`], used by the
+    /// headless mode. The session start and end events are still emitted so event
+    /// handlers can set up and tear down their state. Tools that require approval
+    /// are approved or declined by the event handler as usual.
+    ///
+    /// # Arguments
+    ///
+    /// * `prompt` - The user's message to send to the assistant
+    ///
+    /// # Returns
+    ///
+    /// The [`TurnResult`] of the turn.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ChatSessionError`] if the turn fails, for example because the
+    /// turn budget was exceeded without a summary or a fatal client error occurred.
+    pub async fn run_once(&mut self, prompt: &str) -> Result<TurnResult, ChatSessionError> {
+        self.emit(Event::SessionStarted);
+        let result = self.send_message(prompt).await;
+        self.emit(Event::SessionEnded);
+        result
+    }
+
+    /// Sends a message from the interactive loop, reporting a failed turn as an event.
+    ///
+    /// The message of a failed turn is kept so it can be sent again with `/retry`.
+    /// In plan mode, a reply containing numbered steps is shown to the user for
+    /// approval, and an approved plan is carried out right away.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The user's message to send to the assistant
+    async fn send_user_message(&mut self, message: String) {
+        let mut message = message;
+        loop {
+            match self.send_message(&message).await {
+                Ok(turn) if self.mode == PermissionMode::Plan => {
+                    if !self.review_plan(&turn.message) {
+                        break;
+                    }
+                    message = EXECUTE_PLAN_PROMPT.to_string();
+                }
+                Ok(_) => break,
+                Err(e) => {
+                    self.failed_message = Some(message);
+                    self.emit(Event::Error {
+                        message: format!("{}. Type /retry to send the message again.", e),
+                    });
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Returns the token usage accumulated over all completions in the session.
+    ///
+    /// Usage is only counted for clients that report it, so the usage of a
+    /// session using a client without usage reporting stays zero.
+    pub fn usage(&self) -> Usage {
+        self.usage
+    }
+
+    /// Sends a message to the AI assistant and returns the outcome of the turn.
     ///
     /// This method handles the complete conversation flow: adds the user message to memory,
     /// requests a response from the AI, processes any tool calls, handles errors with
     /// retry logic, update event handler with events, and returns the final assistant response.
-    /// The method continues until the AI returns a final message or maximum iterations are reached.
+    /// The method continues until the AI returns a final message or the turn budget is exhausted,
+    /// in which case the AI is asked for a final summary of its progress.
+    ///
+    /// Unlike [`ChatSession::run`], no user input is requested, which makes this the
+    /// entry point for embedding the session in other programs. Tool calls that need
+    /// approval are still passed to the event handler.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The user's message to send to the assistant
+    ///
+    /// # Returns
+    ///
+    /// The [`TurnResult`] with the assistant's final message, the tool calls of the
+    /// turn and how they were decided on, the token usage of the turn and the budget
+    /// limit it exhausted, if the final message is a summary of unfinished work.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ChatSessionError`] for API errors, an exhausted turn budget without
+    /// a final summary, or tool execution failures. A turn that failed before
+    /// any tool ran is removed from memory. Otherwise the work done so far is kept
+    /// with a note on the tools that ran, so the conversation can continue.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use code_g::client::providers::openai::client::OpenAIClient;
+    /// use code_g::session::builder::ChatSessionBuilder;
+    /// use code_g::tools::registry::Registry;
+    /// use tokio::runtime::Runtime;
+    ///
+    /// let mut session = ChatSessionBuilder::new(Box::new(OpenAIClient::new("api_key".to_string())))
+    ///     .tools(Box::new(Registry::read_only_tools()))
+    ///     .build();
+    ///
+    /// let rt = Runtime::new().unwrap();
+    /// let turn = rt.block_on(session.send_message("Where is the parser?")).unwrap();
+    /// for tool_call in &turn.tool_calls {
+    ///     println!("{} ({:?})", tool_call.tool_name, tool_call.decision);
+    /// }
+    /// println!("{}", turn.message);
+    /// ```
+    pub async fn send_message(&mut self, message: &str) -> Result<TurnResult, ChatSessionError> {
+        self.run_hooks(HookEvent::UserPromptSubmit {
+            prompt: message.to_string(),
+        })
+        .await;
+
+        let memory_len = self.memory.len();
+        let usage_before = self.usage;
+        self.turn_tool_calls.clear();
+        self.turn_budget_exhausted = None;
+
+        let result = self.run_turn(message).await;
+        if result.is_err() {
+            self.recover_failed_turn(memory_len);
+        }
+
+        self.run_hooks(HookEvent::TurnEnd {
+            message: result.as_ref().ok().cloned(),
+            error: result.as_ref().err().map(|e| e.to_string()),
+        })
+        .await;
+
+        result.map(|message| TurnResult {
+            message,
+            tool_calls: std::mem::take(&mut self.turn_tool_calls),
+            usage: self.usage.since(&usage_before),
+            budget_exhausted: self.turn_budget_exhausted.take(),
+        })
+    }
+
+    /// Repairs the memory after a turn failed.
+    ///
+    /// A turn that failed before any tool ran is removed, so it can be sent again
+    /// with `/retry`. Otherwise the work done so far is kept: only a tool call
+    /// message left without results is removed, and a system note tells the
+    /// assistant which tools ran and which files they modified.
+    ///
+    /// # Arguments
+    ///
+    /// * `memory_len` - The length of the memory before the turn started
+    fn recover_failed_turn(&mut self, memory_len: usize) {
+        let executed: Vec<&ToolCallOutcome> = self
+            .turn_tool_calls
+            .iter()
+            .filter(|tool_call| tool_call.decision.is_executed())
+            .collect();
+        if executed.is_empty() {
+            self.memory.truncate(memory_len);
+            return;
+        }
+
+        let mut note = FAILED_TURN_NOTE.to_string();
+        let mut modified_paths: Vec<String> = vec![];
+        for tool_call in executed {
+            note.push_str(&format!("\n- {}", tool_call.tool_name));
+            if let Some(tool) = self.tools.get_tool(&tool_call.tool_name) {
+                for path in tool.modified_paths(&tool_call.arguments) {
+                    let path = path.display().to_string();
+                    if !modified_paths.contains(&path) {
+                        modified_paths.push(path);
+                    }
+                }
+            }
+        }
+        if !modified_paths.is_empty() {
+            note.push_str(&format!(
+                "\nFiles they modified: {}",
+                modified_paths.join(", ")
+            ));
+        }
+
+        self.memory.remove_unanswered_tool_calls();
+        self.memory
+            .add_message(ChatMessage::System { content: note });
+    }
+
+    /// Runs the tool loop of a turn until the assistant returns its final response.
     ///
     /// # Arguments
     ///
@@ -157,49 +805,65 @@ impl ChatSession {
     ///
     /// # Errors
     ///
-    /// Returns [`ChatSessionError`] for API errors, maximum iteration limit exceeded,
-    /// or tool execution failures.
-    async fn send_message(&mut self, message: &str) -> Result<String, ChatSessionError> {
-        // Add user message to memory
-        self.memory.add_message(ChatMessage::User {
-            content: message.to_string(),
-        });
+    /// Returns [`ChatSessionError`] if the turn fails, leaving the messages added
+    /// so far in memory.
+    async fn run_turn(&mut self, message: &str) -> Result<String, ChatSessionError> {
+        // Start a new checkpoint turn so file changes can be undone
+        self.checkpoints.begin_turn(message);
+        self.files_modified = false;
 
-        // Notify event handler about the user message
-        self.event_handler.handle_event(Event::ReceivedUserMessage {
-            message: message.to_string(),
-        });
+        // Add user message to memory and notify the event handler
+        self.add_user_message(message);
+
+        // Track the turn budget to prevent infinite loops and runaway costs
+        let mut budget = TurnBudgetTracker::start(self.budget.clone());
+
+        // Track tool calls to detect the assistant repeating itself
+        let mut loop_detector = LoopDetector::new();
+        let mut loop_corrected = false;
 
-        // Track iterations to prevent infinite loops
-        let mut iterations = 0;
+        // Count how often the assistant was sent back to fix a failing check
+        let mut verification_retries = 0;
 
-        // Loop until the client returns a message or max iterations reached
+        // Loop until the client returns a message or the budget is exhausted
         loop {
-            iterations += 1;
+            // 1. Check the budget, warning when a limit is nearly used up
+            if let Some(limit) = budget.exceeded() {
+                return self.finish_over_budget(limit).await;
+            }
+            for limit in budget.take_warnings() {
+                self.emit(Event::BudgetWarning { limit });
+            }
+            budget.record_iteration();
 
-            // 1. Check if we've exceeded the maximum number of iterations
-            if iterations > MAX_ITERATIONS {
-                return Err(ChatSessionError::MaxIterationsExceeded {
-                    max_iterations: MAX_ITERATIONS,
-                });
+            // 1.1 Add messages the user typed since the last completion to steer the turn
+            if budget.iterations() > 1 {
+                for message in self.input_queue.take_steering() {
+                    self.add_user_message(&message);
+                }
             }
 
             // 2. Set the status message to thinking
-            self.event_handler
-                .handle_event(Event::AwaitingAssistantResponse);
+            self.emit(Event::AwaitingAssistantResponse);
 
             // 3. Get a response from the client
             let response = match self
                 .client
-                .create_chat_completion(
-                    &Model::OpenAi(OpenAiModel::Gpt4oMini), // TODO: Make this configurable
-                    &self.memory.get_memory(),
-                    &self.tools.to_tools(),
+                .create_chat_completion_with_usage(
+                    &self.model,
+                    self.memory.get_memory(),
+                    &self.available_tools(),
                 )
                 .await
             {
-                Ok(response) => response,
-                Err(e) => match self.handle_chat_client_error(e, iterations) {
+                Ok((response, usage)) => {
+                    if let Some(usage) = usage {
+                        self.usage.add(&usage);
+                        budget.record_usage(&usage, self.model.cost(&usage));
+                    }
+                    response
+                }
+                Err(e) => match self.handle_chat_client_error(e, budget.iterations()) {
                     ChatSessionErrorHandling::Fatal(err) => {
                         return Err(err);
                     }
@@ -224,12 +888,16 @@ impl ChatSession {
                     });
 
                     // 5.2 Render the memory to the event handler (only if not silent)
-                    self.event_handler
-                        .handle_event(Event::ReceivedAssistantMessage {
-                            message: content.clone(),
-                        });
+                    self.emit(Event::ReceivedAssistantMessage {
+                        message: content.clone(),
+                    });
 
-                    // 5.3 Return the content only if turn is over, otherwise continue
+                    // 5.3 Verify modified files, sending a failing check back to the assistant
+                    if turn_over && self.verify_changes(&mut verification_retries).await {
+                        continue;
+                    }
+
+                    // 5.4 Return the content only if turn is over, otherwise continue
                     if turn_over {
                         return Ok(content);
                     }
@@ -243,121 +911,946 @@ impl ChatSession {
                     });
 
                     // 6.2 Call each tool and collect responses
+                    let mut loop_pattern = None;
                     for tool_call in &tool_calls {
+                        if let Some(pattern) = loop_detector.record(tool_call) {
+                            loop_pattern.get_or_insert(pattern);
+                        }
+
                         // 6.2.1 Set the status message to the tool call name
-                        self.event_handler.handle_event(Event::ReceivedToolCall {
+                        self.emit(Event::ReceivedToolCall {
                             tool_name: tool_call.name.clone(),
                             parameters: tool_call.arguments.clone(),
                         });
 
-                        // 6.2.2 Check if tool requires approval and request if needed
-                        let (tool_response, approved) = if self
-                            .tools
-                            .get_tool(&tool_call.name)
-                            .map(|tool| tool.requires_approval())
-                            .unwrap_or(false)
-                        {
-                            match self.request_approval(&tool_call.name, &tool_call.arguments) {
-                                Ok(true) => {
-                                    // User approved, proceed with tool execution
-                                    let response = self
-                                        .tools
-                                        .call_tool(
-                                            tool_call.name.as_str(),
-                                            tool_call.arguments.clone(),
-                                        )
-                                        .unwrap_or_else(|e| e);
-                                    (response, true)
+                        // 6.2.2 Let pre-tool hooks block the call, otherwise apply the permission policy
+                        let internal = self.is_internal_tool(&tool_call.name);
+                        let blocked = if internal {
+                            None
+                        } else {
+                            self.run_hooks(HookEvent::PreToolUse {
+                                tool_call: tool_call.clone(),
+                            })
+                            .await
+                        };
+                        let (mut tool_response, decision) = if internal {
+                            // Internal tools are handled by the session itself and need no approval
+                            (
+                                self.run_internal_tool(tool_call, &mut budget).await,
+                                ToolCallDecision::Allowed,
+                            )
+                        } else if let Some(reason) = blocked {
+                            self.emit(Event::ToolCallBlocked {
+                                tool_name: tool_call.name.clone(),
+                                reason: reason.clone(),
+                            });
+                            (
+                                format!("Tool call blocked by hook: {}", reason),
+                                ToolCallDecision::Blocked,
+                            )
+                        } else {
+                            let requires_approval = self
+                                .tools
+                                .get_tool(&tool_call.name)
+                                .map(|tool| tool.requires_approval())
+                                .unwrap_or(false);
+                            let verdict = self.mode.apply(
+                                self.permissions.evaluate(
+                                    tool_call,
+                                    requires_approval,
+                                    self.project_dir.as_deref(),
+                                ),
+                                tool_call,
+                                self.project_dir.as_deref(),
+                            );
+
+                            match verdict.decision {
+                                PermissionDecision::Allow => {
+                                    (self.execute_tool(tool_call), ToolCallDecision::Allowed)
                                 }
-                                Ok(false) => {
-                                    // User declined, return cancellation message
+                                PermissionDecision::Deny => {
+                                    self.emit(Event::ToolCallDenied {
+                                        tool_name: tool_call.name.clone(),
+                                        reason: verdict.reason.clone(),
+                                    });
                                     let response = format!(
-                                        "Operation cancelled by user: {} with parameters {:?}",
-                                        tool_call.name, tool_call.arguments
+                                        "Tool call denied by permission policy: {}",
+                                        verdict.reason
                                     );
-                                    (response, false)
+                                    (response, ToolCallDecision::Denied)
                                 }
-                                Err(e) => {
-                                    // Error requesting approval
-                                    let response = format!(
-                                        "Failed to request approval for {}: {}",
-                                        tool_call.name, e
-                                    );
-                                    (response, false)
+                                PermissionDecision::Ask => {
+                                    match self.request_approval(tool_call, verdict.reason) {
+                                        Ok(
+                                            ApprovalResponse::Approved
+                                            | ApprovalResponse::Always { .. },
+                                        ) => {
+                                            // User approved, proceed with tool execution
+                                            (
+                                                self.execute_tool(tool_call),
+                                                ToolCallDecision::Approved,
+                                            )
+                                        }
+                                        Ok(ApprovalResponse::Declined { reason }) => {
+                                            // User declined, return cancellation message with their reason
+                                            let mut response = format!(
+                                                "Operation cancelled by user: {} with parameters {:?}",
+                                                tool_call.name, tool_call.arguments
+                                            );
+                                            if let Some(reason) = reason {
+                                                response.push_str(&format!(
+                                                    "\nReason given by the user: {}",
+                                                    reason
+                                                ));
+                                            }
+                                            (response, ToolCallDecision::Declined)
+                                        }
+                                        Err(e) => {
+                                            // Error requesting approval
+                                            let response = format!(
+                                                "Failed to request approval for {}: {}",
+                                                tool_call.name, e
+                                            );
+                                            (response, ToolCallDecision::Declined)
+                                        }
+                                    }
                                 }
                             }
-                        } else {
-                            // Tool doesn't require approval, execute directly
-                            let response = self
-                                .tools
-                                .call_tool(tool_call.name.as_str(), tool_call.arguments.clone())
-                                .unwrap_or_else(|e| e);
-                            (response, true)
                         };
 
-                        // 6.2.3 Add tool response to memory
+                        // 6.2.3 Run post-tool hooks for executed calls, passing their feedback on
+                        let approved = decision.is_executed();
+                        if approved && !internal {
+                            let feedback = self
+                                .run_hooks(HookEvent::PostToolUse {
+                                    tool_call: tool_call.clone(),
+                                    tool_response: tool_response.clone(),
+                                })
+                                .await;
+                            if let Some(feedback) = feedback {
+                                tool_response = format!(
+                                    "{}\n\nPost-tool hook feedback:\n{}",
+                                    tool_response, feedback
+                                );
+                            }
+                        }
+
+                        // 6.2.4 Truncate large outputs, then add the tool response to memory
+                        if !internal {
+                            tool_response = self.output_limiter.limit(&tool_response);
+                        }
                         self.memory.add_message(ChatMessage::Tool {
                             content: tool_response.clone(),
                             tool_call_id: tool_call.id.clone(),
                             tool_name: tool_call.name.clone(),
                         });
 
-                        // 6.2.4 Send tool response event to the event handler
-                        self.event_handler
-                            .handle_event(Event::ReceivedToolResponse {
-                                tool_name: tool_call.name.clone(),
-                                response: tool_response.clone(),
-                                parameters: tool_call.arguments.clone(),
-                                approved,
-                            });
-                    }
+                        // 6.2.5 Send tool response event to the event handler
+                        self.emit(Event::ReceivedToolResponse {
+                            tool_name: tool_call.name.clone(),
+                            response: tool_response.clone(),
+                            parameters: tool_call.arguments.clone(),
+                            approved,
+                        });
+
+                        // 6.2.6 Record the call for the turn result
+                        self.turn_tool_calls.push(ToolCallOutcome {
+                            id: tool_call.id.clone(),
+                            tool_name: tool_call.name.clone(),
+                            arguments: tool_call.arguments.clone(),
+                            response: tool_response,
+                            decision,
+                        });
+                    }
+
+                    // 6.3 Correct a tool calling loop once, then stop the turn if it persists
+                    if let Some(pattern) = loop_pattern {
+                        if loop_corrected {
+                            self.emit(Event::LoopAborted {
+                                pattern: pattern.clone(),
+                            });
+                            return Err(ChatSessionError::LoopDetected { pattern });
+                        }
 
-                    // 6.3 Continue the loop to get the assistants response
+                        loop_corrected = true;
+                        self.memory.add_message(ChatMessage::System {
+                            content: format!("{} {}", LOOP_DETECTED_NOTE, pattern),
+                        });
+                        self.emit(Event::LoopDetected { pattern });
+                    }
+
+                    // 6.4 Continue the loop to get the assistants response
                     continue;
                 }
             }
         }
     }
 
+    /// Ends a turn whose budget is exhausted by asking the assistant for a summary.
+    ///
+    /// The assistant is told to stop working and is given no tools, so its reply
+    /// is a final message describing what was done and what remains.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - The budget limit that was reached
+    ///
+    /// # Returns
+    ///
+    /// The assistant's summary message. The limit is kept for the [`TurnResult`],
+    /// so callers can tell the summary from a finished task.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ChatSessionError::MaxIterationsExceeded`] or
+    /// [`ChatSessionError::BudgetExceeded`] if the summary could not be obtained.
+    async fn finish_over_budget(&mut self, limit: BudgetLimit) -> Result<String, ChatSessionError> {
+        self.emit(Event::BudgetExceeded {
+            limit: limit.clone(),
+        });
+
+        self.memory.add_message(ChatMessage::System {
+            content: BUDGET_EXCEEDED_NOTE.to_string(),
+        });
+
+        self.emit(Event::AwaitingAssistantResponse);
+
+        let response = self
+            .client
+            .create_chat_completion_with_usage(&self.model, self.memory.get_memory(), &[])
+            .await;
+
+        match response {
+            Ok((ChatResult::Message { content, .. }, usage)) => {
+                if let Some(usage) = usage {
+                    self.usage.add(&usage);
+                }
+
+                self.memory.add_message(ChatMessage::Assistant {
+                    message: AssistantMessage::Content(content.clone()),
+                });
+                self.emit(Event::ReceivedAssistantMessage {
+                    message: content.clone(),
+                });
+
+                self.turn_budget_exhausted = Some(limit);
+                Ok(content)
+            }
+            _ => Err(match limit {
+                BudgetLimit::Iterations { max, .. } => ChatSessionError::MaxIterationsExceeded {
+                    max_iterations: max,
+                },
+                limit => ChatSessionError::BudgetExceeded { limit },
+            }),
+        }
+    }
+
+    /// Emits an event to the interactive frontend and all subscribers.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The event to emit
+    fn emit(&mut self, event: Event) {
+        self.event_bus.publish(event.clone());
+        self.event_handler.handle_event(event);
+    }
+
+    /// Runs the hooks for a lifecycle event, reporting failed hooks as events.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The lifecycle event to run the hooks for
+    ///
+    /// # Returns
+    ///
+    /// The feedback of a hook that exited with status 2, if any.
+    async fn run_hooks(&mut self, event: HookEvent) -> Option<String> {
+        if self.hooks.is_empty() {
+            return None;
+        }
+
+        // Hooks can run until they time out, so they run off the async runtime's threads
+        let hooks = self.hooks.clone();
+        let outcome = match tokio::task::spawn_blocking(move || hooks.run(&event)).await {
+            Ok(outcome) => outcome,
+            Err(e) => {
+                self.emit(Event::HookFailed {
+                    message: format!("Failed to run hooks: {}", e),
+                });
+                return None;
+            }
+        };
+        for error in outcome.errors {
+            self.emit(Event::HookFailed {
+                message: error.to_string(),
+            });
+        }
+        outcome.feedback
+    }
+
+    /// Runs the check command if files were modified since the last check.
+    ///
+    /// A failing check is added to memory for the assistant to fix, as long as
+    /// the turn's retries are not used up.
+    ///
+    /// # Arguments
+    ///
+    /// * `retries` - How often the assistant was sent back in this turn
+    ///
+    /// # Returns
+    ///
+    /// `true` if the assistant was sent back to fix a failing check.
+    async fn verify_changes(&mut self, retries: &mut usize) -> bool {
+        let Some(command) = self.verification.command.clone() else {
+            return false;
+        };
+        if !self.files_modified {
+            return false;
+        }
+        self.files_modified = false;
+
+        self.emit(Event::VerificationStarted {
+            command: command.clone(),
+        });
+        let dir = match &self.project_dir {
+            Some(dir) => dir.clone(),
+            None => env::current_dir().unwrap_or_default(),
+        };
+        // The check can run for minutes, so it runs off the async runtime's threads
+        let timeout = Duration::from_secs(self.verification.timeout_secs);
+        let check = command.clone();
+        let result = tokio::task::spawn_blocking(move || run_check(&check, &dir, timeout))
+            .await
+            .unwrap_or_else(|e| CheckResult {
+                passed: false,
+                output: format!("Failed to run the check command: {}", e),
+            });
+        let retrying = !result.passed && *retries < self.verification.max_retries;
+        self.emit(Event::VerificationFinished {
+            command: command.clone(),
+            passed: result.passed,
+            output: result.output.clone(),
+            retrying,
+        });
+
+        if retrying {
+            *retries += 1;
+            self.memory.add_message(ChatMessage::System {
+                content: format!(
+                    "{} `{}`:\n{}",
+                    VERIFICATION_FAILED_NOTE,
+                    command,
+                    self.output_limiter.limit(&result.output)
+                ),
+            });
+        }
+        retrying
+    }
+
+    /// Executes a tool call, snapshotting the files it modifies beforehand.
+    ///
+    /// The snapshots are stored in the current checkpoint turn so the changes
+    /// can later be reverted with `/undo` or `/checkpoints`. With a project
+    /// directory, the tool works in that directory rather than the current one.
+    ///
+    /// # Arguments
+    ///
+    /// * `tool_call` - The tool call to execute
+    ///
+    /// # Returns
+    ///
+    /// The tool output, or the error message if the tool failed.
+    fn execute_tool(&mut self, tool_call: &ToolCall) -> String {
+        if let Some(tool) = self.tools.get_tool(&tool_call.name) {
+            self.files_modified |= tool.modifies_files(&tool_call.arguments);
+            for path in tool.modified_paths(&tool_call.arguments) {
+                let path = match &self.project_dir {
+                    Some(dir) => dir.join(path),
+                    None => path,
+                };
+                // A file that cannot be read cannot be restored either, so the
+                // tool is still allowed to run without a snapshot
+                let _ = self.checkpoints.snapshot(&path);
+            }
+        }
+
+        let arguments = tool_call.arguments.clone();
+        match &self.project_dir {
+            Some(dir) => self
+                .tools
+                .call_tool_in_dir(tool_call.name.as_str(), arguments, dir),
+            None => self.tools.call_tool(tool_call.name.as_str(), arguments),
+        }
+        .unwrap_or_else(|e| e)
+    }
+
+    /// Handles a slash command entered by the user.
+    ///
+    /// Commands are executed locally and their outcome is reported through events,
+    /// except `/retry`, which sends the message of the last failed turn again.
+    /// Restoring a checkpoint also adds a system note to memory so the assistant
+    /// knows that its earlier file changes were reverted.
+    ///
+    /// # Arguments
+    ///
+    /// * `command` - The command to handle
+    async fn handle_command(&mut self, command: Command) {
+        let restored = match command {
+            Command::Mode { mode } => {
+                let mode = mode.unwrap_or_else(|| self.mode.next());
+                self.set_permission_mode(mode);
+                return;
+            }
+            Command::Export { format, path } => {
+                self.export_transcript(format, path);
+                return;
+            }
+            Command::Retry => {
+                match self.failed_message.take() {
+                    Some(message) => self.send_user_message(message).await,
+                    None => self.emit(Event::Error {
+                        message: "There is no failed message to retry".to_string(),
+                    }),
+                }
+                return;
+            }
+            Command::Checkpoints { restore: None } => {
+                self.emit(Event::CheckpointsListed {
+                    checkpoints: self.checkpoints.list(),
+                });
+                return;
+            }
+            Command::Checkpoints { restore: Some(id) } => self.checkpoints.restore(id),
+            Command::Undo => self.checkpoints.undo(),
+        };
+
+        match restored {
+            Ok(checkpoint) => {
+                self.memory.add_message(ChatMessage::System {
+                    content: format!(
+                        "The user reverted the file changes made for the request \"{}\". These files were restored to their previous content: {}",
+                        checkpoint.prompt,
+                        checkpoint.paths.join(", ")
+                    ),
+                });
+                self.emit(Event::CheckpointRestored { checkpoint });
+            }
+            Err(e) => self.emit(Event::CheckpointFailed {
+                message: e.to_string(),
+            }),
+        }
+    }
+
+    /// Writes the conversation to a transcript file.
+    ///
+    /// Relative paths are resolved against the project directory. Without a
+    /// path, the file is named after the current time, such as
+    /// `transcript-1760780000.md`.
+    ///
+    /// # Arguments
+    ///
+    /// * `format` - The [`TranscriptFormat`] to write
+    /// * `path` - Where to write the transcript, if chosen by the user
+    fn export_transcript(&mut self, format: TranscriptFormat, path: Option<PathBuf>) {
+        let path = path.unwrap_or_else(|| {
+            let seconds = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|elapsed| elapsed.as_secs())
+                .unwrap_or(0);
+            PathBuf::from(format!("transcript-{}.{}", seconds, format.extension()))
+        });
+        let path = match &self.project_dir {
+            Some(project_dir) => project_dir.join(path),
+            None => path,
+        };
+
+        let result = Transcript::new(self.memory.get_memory())
+            .render(format)
+            .map_err(|e| e.to_string())
+            .and_then(|content| {
+                if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+                    fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+                }
+                fs::write(&path, content).map_err(|e| e.to_string())
+            });
+        match result {
+            Ok(()) => self.emit(Event::TranscriptExported { path }),
+            Err(e) => self.emit(Event::Error {
+                message: format!("Failed to export transcript to {}: {}", path.display(), e),
+            }),
+        }
+    }
+
+    /// Switches the permission mode and tells the assistant what it now allows.
+    ///
+    /// # Arguments
+    ///
+    /// * `mode` - The new [`PermissionMode`]
+    fn set_permission_mode(&mut self, mode: PermissionMode) {
+        if mode == PermissionMode::Plan && self.mode != PermissionMode::Plan {
+            self.mode_before_plan = self.mode;
+        }
+        self.mode = mode;
+        self.memory.add_message(ChatMessage::System {
+            content: mode.system_note().to_string(),
+        });
+        self.emit(Event::PermissionModeChanged { mode });
+    }
+
+    /// Adds a user message to memory and notifies the event handler.
+    ///
+    /// The files and directories the message mentions are attached to it.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The message typed by the user
+    fn add_user_message(&mut self, message: &str) {
+        let cwd = match &self.project_dir {
+            Some(project_dir) => project_dir.clone(),
+            None => env::current_dir().unwrap_or_default(),
+        };
+        let attached = attach(message, &cwd);
+        self.memory.add_message(ChatMessage::User {
+            content: attached.content,
+        });
+        self.emit(Event::ReceivedUserMessage {
+            message: message.to_string(),
+            attachments: attached.attachments,
+        });
+    }
+
+    /// Returns the tools offered to the assistant in the current permission mode.
+    ///
+    /// While an approved plan is being carried out, the tool to mark its steps
+    /// as done is offered as well. With an editor, the edit tools are replaced
+    /// by the tool to hand off changes to it.
+    fn available_tools(&self) -> Vec<ToolModel> {
+        let mut tools: Vec<ToolModel> = self
+            .tools
+            .to_tools()
+            .into_iter()
+            .filter(|tool| self.mode.allows_tool(&tool.function.name))
+            .filter(|tool| {
+                self.editor.is_none() || !EDIT_TOOLS.contains(&tool.function.name.as_str())
+            })
+            .collect();
+        if self.editor.is_some() && EDIT_TOOLS.iter().all(|tool| self.mode.allows_tool(tool)) {
+            tools.push(Handoff::tool());
+        }
+        if self.plan.is_some() {
+            tools.push(Plan::step_tool());
+        }
+        if self.sub_agents {
+            tools.push(task_tool());
+        }
+        if self.todo_tools {
+            tools.push(TodoList::write_tool());
+            tools.push(TodoList::read_tool());
+        }
+        if self.output_limiter.has_outputs() {
+            tools.push(OutputLimiter::read_tool());
+        }
+        tools
+    }
+
+    /// Returns `true` if the session handles calls of the tool itself.
+    ///
+    /// # Arguments
+    ///
+    /// * `tool_name` - The name of the called tool
+    fn is_internal_tool(&self, tool_name: &str) -> bool {
+        match tool_name {
+            COMPLETE_PLAN_STEP_TOOL => true,
+            TASK_TOOL => self.sub_agents,
+            HAND_OFF_EDITS_TOOL => self.editor.is_some(),
+            TODO_WRITE_TOOL | TODO_READ_TOOL => self.todo_tools,
+            READ_TOOL_OUTPUT_TOOL => self.output_limiter.has_outputs(),
+            _ => false,
+        }
+    }
+
+    /// Handles a call of a tool the session provides itself.
+    ///
+    /// # Arguments
+    ///
+    /// * `tool_call` - The call of an internal tool, see [`ChatSession::is_internal_tool`]
+    /// * `budget` - The budget tracker of the current turn
+    ///
+    /// # Returns
+    ///
+    /// The tool response for the assistant.
+    async fn run_internal_tool(
+        &mut self,
+        tool_call: &ToolCall,
+        budget: &mut TurnBudgetTracker,
+    ) -> String {
+        match tool_call.name.as_str() {
+            // Plan progress and the todo list are tracked by the session itself
+            COMPLETE_PLAN_STEP_TOOL => self.complete_plan_step(tool_call),
+            TODO_WRITE_TOOL => self.write_todos(tool_call),
+            TODO_READ_TOOL => self.read_todos(),
+            // Pages of saved outputs are already within the limit
+            READ_TOOL_OUTPUT_TOOL => self.read_tool_output(tool_call),
+            // The editor asks for approval of its own edits
+            HAND_OFF_EDITS_TOOL => self.run_editor(tool_call, budget).await,
+            // Sub-agents only get read-only tools, so they need no approval
+            TASK_TOOL => self.run_sub_agent(tool_call, budget).await,
+            name => format!("Error: {} is not handled by the session", name),
+        }
+    }
+
+    /// Replaces the todo list with the list passed by the assistant.
+    ///
+    /// # Arguments
+    ///
+    /// * `tool_call` - The call of the todo write tool
+    ///
+    /// # Returns
+    ///
+    /// The tool response for the assistant.
+    fn write_todos(&mut self, tool_call: &ToolCall) -> String {
+        let Some(json) = tool_call.arguments.get("todos") else {
+            return "Error: The todos argument is required".to_string();
+        };
+        match TodoList::parse(json) {
+            Ok(todos) => {
+                self.todos = todos.clone();
+                self.emit(Event::TodosUpdated { todos });
+                "Todo list updated".to_string()
+            }
+            Err(e) => format!("Error: {}", e),
+        }
+    }
+
+    /// Returns the todo list for the assistant.
+    fn read_todos(&self) -> String {
+        if self.todos.is_empty() {
+            "The todo list is empty".to_string()
+        } else {
+            self.todos.to_string()
+        }
+    }
+
+    /// Reads a range of lines of a tool output that was too large for the conversation.
+    ///
+    /// # Arguments
+    ///
+    /// * `tool_call` - The call of the tool output paging tool
+    ///
+    /// # Returns
+    ///
+    /// The requested lines, or an error message for the assistant.
+    fn read_tool_output(&self, tool_call: &ToolCall) -> String {
+        let argument = |name: &str| tool_call.arguments.get(name).map(|value| value.trim());
+        let line = |name: &str| argument(name).and_then(|value| value.parse().ok());
+
+        let (Some(id), Some(start), Some(end)) =
+            (argument("output_id"), line("start_line"), line("end_line"))
+        else {
+            return "Error: output_id, start_line and end_line are required, and the lines must be numbers".to_string();
+        };
+        self.output_limiter
+            .read(id, start, end)
+            .unwrap_or_else(|e| format!("Error: {}", e))
+    }
+
+    /// Runs a task delegated by the assistant in a sub-agent.
+    ///
+    /// The sub-agent is a new session sharing the client, model, tool hooks and
+    /// permission rules of this session, but with its own memory and only the
+    /// read-only tools of this session. It runs with what is left of the turn
+    /// budget, and its token usage is added to this session and turn.
+    ///
+    /// # Arguments
+    ///
+    /// * `tool_call` - The call of the task tool
+    /// * `budget` - The budget tracker of the current turn
+    ///
+    /// # Returns
+    ///
+    /// The sub-agent's final summary, or an error message if it failed.
+    async fn run_sub_agent(
+        &mut self,
+        tool_call: &ToolCall,
+        budget: &mut TurnBudgetTracker,
+    ) -> String {
+        let Some(task) = tool_call.arguments.get("task") else {
+            return "Error: The task argument is required".to_string();
+        };
+
+        let read_only = PermissionMode::Plan;
+        let tools = Registry::from_tools(
+            self.tools
+                .get_tools()
+                .iter()
+                .filter(|tool| read_only.allows_tool(&tool.name()))
+                .cloned()
+                .collect(),
+        );
+        let mut sub_agent = ChatSession {
+            budget: budget.remaining(),
+            hooks: self.hooks.tool_hooks(),
+            permissions: self.permissions.clone(),
+            project_dir: self.project_dir.clone(),
+            output_limiter: OutputLimiter::new(self.output_limiter.max_tokens()),
+            ..ChatSession::nested(
+                SUB_AGENT_PROMPT,
+                Arc::clone(&self.client),
+                self.model.clone(),
+                tools,
+                Box::new(SubAgentEventHandler),
+                self.event_bus.clone(),
+            )
+        };
+
+        // Sub-agents cannot delegate further, so this recursion is one level deep
+        let result = Box::pin(sub_agent.send_message(task)).await;
+        self.usage.add(&sub_agent.usage);
+        budget.record_usage(&sub_agent.usage, self.model.cost(&sub_agent.usage));
+        match result {
+            Ok(turn) => turn.message,
+            Err(e) => format!("Error: The sub-agent failed: {}", e),
+        }
+    }
+
+    /// Has the editor apply the changes handed off by the assistant.
+    ///
+    /// The editor is a new session with its own client, model and memory and
+    /// only the file reading and editing tools of this session. It shares the
+    /// tool hooks, permission rules and mode of this session, and the frontend
+    /// for the length of its turn so the user approves its edits. It runs with
+    /// what is left of the turn budget, and its usage counts against it. Its
+    /// file snapshots join this turn's checkpoint, and "always allow" grants
+    /// made while it runs are kept for the session.
+    ///
+    /// # Arguments
+    ///
+    /// * `tool_call` - The call of the handoff tool
+    /// * `budget` - The budget tracker of the current turn
+    ///
+    /// # Returns
+    ///
+    /// A report of the editor's edits, or an error message if it failed.
+    async fn run_editor(&mut self, tool_call: &ToolCall, budget: &mut TurnBudgetTracker) -> String {
+        let Some(editor) = self.editor.clone() else {
+            return "Error: No editor is paired with this session".to_string();
+        };
+        let handoff = match Handoff::from_arguments(&tool_call.arguments) {
+            Ok(handoff) => handoff,
+            Err(e) => return format!("Error: {}", e),
+        };
+
+        let tools = Registry::from_tools(
+            self.tools
+                .get_tools()
+                .iter()
+                .filter(|tool| EDITOR_TOOLS.contains(&tool.name().as_str()))
+                .cloned()
+                .collect(),
+        );
+        let frontend = Rc::new(RefCell::new(mem::replace(
+            &mut self.event_handler,
+            Box::new(SubAgentEventHandler),
+        )));
+        let mut editor = ChatSession {
+            budget: budget.remaining(),
+            hooks: self.hooks.tool_hooks(),
+            permissions: self.permissions.clone(),
+            mode: self.mode,
+            mode_before_plan: self.mode,
+            project_dir: self.project_dir.clone(),
+            output_limiter: OutputLimiter::new(self.output_limiter.max_tokens()),
+            ..ChatSession::nested(
+                EDITOR_PROMPT,
+                editor.client,
+                editor.model,
+                tools,
+                Box::new(EditorEventHandler::new(Rc::clone(&frontend))),
+                self.event_bus.clone(),
+            )
+        };
+
+        let result = Box::pin(editor.send_message(&handoff.to_string())).await;
+        self.event_handler =
+            mem::replace(&mut *frontend.borrow_mut(), Box::new(SubAgentEventHandler));
+        self.usage.add(&editor.usage);
+        budget.record_usage(&editor.usage, editor.model.cost(&editor.usage));
+        self.checkpoints.absorb(editor.checkpoints);
+        self.files_modified |= editor.files_modified;
+        self.permissions = editor.permissions;
+        match result {
+            Ok(turn) => Handoff::report(&turn),
+            Err(e) => format!("Error: The editor failed: {}", e),
+        }
+    }
+
+    /// Asks the user to approve the plan in a reply made in plan mode.
+    ///
+    /// An approved plan, possibly edited by the user, is added to memory and the
+    /// permission mode from before plan mode is restored so the plan can be
+    /// carried out.
+    ///
+    /// # Arguments
+    ///
+    /// * `response` - The assistant's reply in plan mode
+    ///
+    /// # Returns
+    ///
+    /// `true` if the user approved a plan.
+    fn review_plan(&mut self, response: &str) -> bool {
+        let Some(plan) = Plan::parse(response) else {
+            return false;
+        };
+
+        let answer = match self
+            .event_handler
+            .handle_action(Action::RequestPlanApproval { plan: plan.clone() })
+        {
+            Ok(answer) => answer,
+            Err(e) => {
+                self.emit(Event::Error {
+                    message: format!("Failed to request plan approval: {}", e),
+                });
+                return false;
+            }
+        };
+
+        let plan = match answer.strip_prefix("edited:") {
+            Some(edited) => Plan::parse(edited),
+            None if answer == "approved" => Some(plan),
+            None => None,
+        };
+        let Some(plan) = plan else {
+            self.memory.add_message(ChatMessage::System {
+                content: "The user did not approve the plan. Wait for their feedback before planning again.".to_string(),
+            });
+            self.emit(Event::PlanRejected);
+            return false;
+        };
+
+        self.memory.add_message(ChatMessage::System {
+            content: format!(
+                "The user approved this plan:\n{}\nCarry it out step by step. After finishing each step, call the {} tool with the step number.",
+                plan, COMPLETE_PLAN_STEP_TOOL
+            ),
+        });
+        self.plan = Some(plan.clone());
+        self.emit(Event::PlanUpdated { plan });
+        self.set_permission_mode(self.mode_before_plan);
+        true
+    }
+
+    /// Marks a step of the approved plan as done.
+    ///
+    /// The plan is cleared once all of its steps are done.
+    ///
+    /// # Arguments
+    ///
+    /// * `tool_call` - The call of the plan step tool
+    ///
+    /// # Returns
+    ///
+    /// The tool response for the assistant.
+    fn complete_plan_step(&mut self, tool_call: &ToolCall) -> String {
+        let Some(plan) = self.plan.as_mut() else {
+            return "There is no approved plan".to_string();
+        };
+
+        let number = tool_call
+            .arguments
+            .get("step")
+            .and_then(|step| step.trim().parse().ok())
+            .unwrap_or(0);
+        if let Err(e) = plan.complete_step(number) {
+            return e;
+        }
+
+        let plan = plan.clone();
+        let response = if plan.is_complete() {
+            self.plan = None;
+            format!("Step {} is done. All steps of the plan are done.", number)
+        } else {
+            format!("Step {} is done.", number)
+        };
+        self.emit(Event::PlanUpdated { plan });
+        response
+    }
+
     /// Requests user approval for a potentially dangerous operation.
     ///
     /// This method prompts the user to approve or decline the execution of a tool
-    /// that could modify the filesystem or execute system commands.
+    /// that could modify the filesystem or execute system commands. If the user
+    /// approves similar calls for good, the grant is remembered.
     ///
     /// # Arguments
     ///
-    /// * `tool_name` - The name of the tool requiring approval
-    /// * `parameters` - The parameters being passed to the tool
+    /// * `tool_call` - The tool call requiring approval
+    /// * `reason` - Why the permission policy asks for approval
     ///
     /// # Returns
     ///
-    /// `true` if the user approved the operation, `false` if declined.
+    /// The user's [`ApprovalResponse`], including the reason if the operation was declined.
     ///
     /// # Errors
     ///
     /// Returns [`ChatSessionError`] if the approval request fails.
     fn request_approval(
         &mut self,
-        tool_name: &str,
-        parameters: &HashMap<String, String>,
-    ) -> Result<bool, ChatSessionError> {
-        let approval_message = if let Some(tool) = self.tools.get_tool(tool_name) {
-            tool.approval_message(parameters)
+        tool_call: &ToolCall,
+        reason: String,
+    ) -> Result<ApprovalResponse, ChatSessionError> {
+        let approval_message = if let Some(tool) = self.tools.get_tool(&tool_call.name) {
+            tool.approval_message(&tool_call.arguments)
         } else {
-            format!("CodeG wants to use tool: {}", tool_name)
+            format!("CodeG wants to use tool: {}", tool_call.name)
         };
 
         let response = self
             .event_handler
             .handle_action(Action::RequestUserApproval {
                 approval_message,
-                tool_name: tool_name.to_string(),
+                tool_name: tool_call.name.clone(),
+                parameters: tool_call.arguments.clone(),
+                reason,
             })
             .map_err(|e| {
                 ChatSessionError::ToolError(format!("Failed to request approval: {}", e))
             })?;
 
-        Ok(response == "approved")
+        let response = ApprovalResponse::parse(&response);
+        if let ApprovalResponse::Always { kind, scope } = response
+            && let Some(rule) = grant_rule(kind, tool_call, self.project_dir.as_deref())
+        {
+            self.remember_grant(rule, scope);
+        }
+
+        Ok(response)
+    }
+
+    /// Adds an "always allow" rule to the permission policy.
+    ///
+    /// Project grants are also written to the project settings file. If that
+    /// fails, the grant still applies for the rest of the session.
+    ///
+    /// # Arguments
+    ///
+    /// * `rule` - The allow rule granted by the user
+    /// * `scope` - Where the grant is remembered
+    fn remember_grant(&mut self, rule: PermissionRule, scope: GrantScope) {
+        if scope == GrantScope::Project
+            && let Some(project_dir) = &self.project_dir
+            && let Err(e) = Settings::add_permission_rule(project_dir, &rule)
+        {
+            self.emit(Event::Error {
+                message: format!("Failed to save permission for the project: {}", e),
+            });
+        }
+
+        self.emit(Event::PermissionGranted {
+            rule: rule.to_string(),
+            scope,
+        });
+        self.permissions.add_rule(rule);
     }
 
     /// Categorizes and handles chat client errors with appropriate recovery strategies.
@@ -0,0 +1,90 @@
+use crate::client::models::{Function, Parameters, Property, Tool, ToolType};
+use crate::session::event::{Action, Event, EventHandler};
+use std::collections::HashMap;
+use std::io;
+
+/// Name of the tool the assistant calls to delegate a task to a sub-agent.
+pub const TASK_TOOL: &str = "task";
+
+/// System prompt of a sub-agent, followed by the task it was given.
+pub const SUB_AGENT_PROMPT: &str = "You are a sub-agent of a coding assistant. You have been given a single, focused task and only read-only tools. Investigate as much as the task requires, then reply with a concise summary of your findings. Your reply is all the assistant will see, so include the file paths, names and details it needs, but leave out everything irrelevant to the task.";
+
+/// Returns the definition of the tool used to delegate tasks to a sub-agent.
+///
+/// # Examples
+///
+/// ```rust
+/// use code_g::session::sub_agent::{TASK_TOOL, task_tool};
+///
+/// let tool = task_tool();
+///
+/// assert_eq!(tool.function.name, TASK_TOOL);
+/// assert_eq!(tool.function.parameters.required, vec!["task".to_string()]);
+/// ```
+pub fn task_tool() -> Tool {
+    Tool {
+        tool_type: ToolType::Function,
+        function: Function {
+            name: TASK_TOOL.to_string(),
+            description: "Delegate a focused, read-only task such as a broad code search to a sub-agent with its own context. The sub-agent can read and search files but not modify them, and only its final summary is returned. Use it to keep large explorations out of the conversation.".to_string(),
+            parameters: Parameters {
+                param_type: "object".to_string(),
+                properties: HashMap::from([(
+                    "task".to_string(),
+                    Property {
+                        prop_type: "string".to_string(),
+                        description: "A self-contained description of the task and of what the summary should contain".to_string(),
+                    },
+                )]),
+                required: vec!["task".to_string()],
+                additional_properties: false,
+            },
+            strict: true,
+        },
+    }
+}
+
+/// Event handler of a sub-agent's session.
+///
+/// A sub-agent runs without a frontend: its events are dropped and tool calls
+/// that would need the user's approval are declined.
+pub(crate) struct SubAgentEventHandler;
+
+impl EventHandler for SubAgentEventHandler {
+    fn handle_event(&mut self, _event: Event) {}
+
+    fn handle_action(&mut self, action: Action) -> Result<String, io::Error> {
+        match action {
+            Action::RequestUserInput => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "a sub-agent cannot ask the user for input",
+            )),
+            Action::RequestUserApproval { .. } | Action::RequestPlanApproval { .. } => {
+                Ok("declined:Sub-agents cannot ask the user for approval".to_string())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sub_agent_event_handler_declines_approvals_and_rejects_input() {
+        let mut handler = SubAgentEventHandler;
+
+        let approval = handler.handle_action(Action::RequestUserApproval {
+            approval_message: "AI wants to write a file".to_string(),
+            tool_name: "write_file".to_string(),
+            parameters: HashMap::new(),
+            reason: "write_file requires approval by default".to_string(),
+        });
+
+        assert_eq!(
+            approval.unwrap(),
+            "declined:Sub-agents cannot ask the user for approval"
+        );
+        assert!(handler.handle_action(Action::RequestUserInput).is_err());
+    }
+}
@@ -1,3 +1,5 @@
+use crate::config::instructions::ProjectInstructions;
+
 /// Configuration options for system prompts in chat sessions.
 ///
 /// This enum allows you to control whether and how a system prompt is applied
@@ -28,6 +30,62 @@ pub enum SystemPromptConfig {
     Custom(String),
 }
 
+impl SystemPromptConfig {
+    /// Adds the user's and the project's instructions to the system prompt.
+    ///
+    /// The instructions are appended to the default or custom prompt. Without
+    /// a prompt, they become the whole system prompt.
+    ///
+    /// # Arguments
+    ///
+    /// * `instructions` - The [`ProjectInstructions`] to add
+    ///
+    /// # Returns
+    ///
+    /// The config with the instructions added, or unchanged if there are none.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use code_g::config::instructions::{InstructionFile, ProjectInstructions};
+    /// use code_g::session::system_prompt::SystemPromptConfig;
+    /// use std::path::PathBuf;
+    ///
+    /// let instructions = ProjectInstructions {
+    ///     files: vec![InstructionFile {
+    ///         path: PathBuf::from("CODEG.md"),
+    ///         content: "Never edit files in vendor/.".to_string(),
+    ///     }],
+    /// };
+    ///
+    /// let config = SystemPromptConfig::Custom("You are a helpful assistant.".to_string())
+    ///     .with_instructions(&instructions);
+    ///
+    /// match config {
+    ///     SystemPromptConfig::Custom(prompt) => {
+    ///         assert!(prompt.starts_with("You are a helpful assistant.\n\n# Project Instructions"));
+    ///         assert!(prompt.ends_with("Never edit files in vendor/."));
+    ///     }
+    ///     _ => panic!("Expected a custom prompt"),
+    /// }
+    /// ```
+    pub fn with_instructions(self, instructions: &ProjectInstructions) -> Self {
+        let Some(section) = instructions.to_prompt() else {
+            return self;
+        };
+
+        match self {
+            SystemPromptConfig::None => SystemPromptConfig::Custom(section),
+            SystemPromptConfig::Default => {
+                SystemPromptConfig::Custom(format!("{}\n\n{}", SYSTEM_PROMPT.trim(), section))
+            }
+            SystemPromptConfig::Custom(prompt) => {
+                SystemPromptConfig::Custom(format!("{}\n\n{}", prompt.trim_end(), section))
+            }
+        }
+    }
+}
+
 /// The default system prompt used for chat sessions.
 ///
 /// This constant contains the standard system prompt that will be used when
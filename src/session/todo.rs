@@ -0,0 +1,195 @@
+use crate::client::models::{Function, Parameters, Property, Tool, ToolType};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Name of the tool the assistant calls to replace its todo list.
+pub const TODO_WRITE_TOOL: &str = "todo_write";
+
+/// Name of the tool the assistant calls to read its todo list.
+pub const TODO_READ_TOOL: &str = "todo_read";
+
+/// How far the assistant got with a todo.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TodoStatus {
+    /// Not started yet
+    Pending,
+    /// Being worked on
+    InProgress,
+    /// Finished
+    Done,
+}
+
+/// A single task of the todo list.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TodoItem {
+    /// What needs to be done
+    pub content: String,
+    /// How far the assistant got with it
+    pub status: TodoStatus,
+}
+
+/// The task list the assistant keeps to track multi-step work.
+///
+/// The assistant replaces the whole list with the [`TODO_WRITE_TOOL`] tool,
+/// passing the items as a JSON array, and reads it back with the
+/// [`TODO_READ_TOOL`] tool.
+///
+/// # Examples
+///
+/// ```rust
+/// use code_g::session::todo::TodoList;
+///
+/// let todos = TodoList::parse(
+///     r#"[
+///         {"content": "Read main.rs", "status": "done"},
+///         {"content": "Extract the parser", "status": "in_progress"},
+///         {"content": "Run the tests", "status": "pending"}
+///     ]"#,
+/// )
+/// .unwrap();
+///
+/// assert_eq!(
+///     todos.to_string(),
+///     "[x] Read main.rs\n[~] Extract the parser\n[ ] Run the tests"
+/// );
+/// assert!(!todos.is_complete());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TodoList {
+    pub items: Vec<TodoItem>,
+}
+
+impl TodoList {
+    /// Reads a todo list from the JSON array passed to the write tool.
+    ///
+    /// # Arguments
+    ///
+    /// * `json` - The items as a JSON array of objects with `content` and `status`
+    ///
+    /// # Errors
+    ///
+    /// Returns a message for the assistant if the JSON is not a valid list, an
+    /// item has no content, or more than one item is in progress.
+    pub fn parse(json: &str) -> Result<Self, String> {
+        let items: Vec<TodoItem> =
+            serde_json::from_str(json).map_err(|e| format!("Invalid todo list: {}", e))?;
+
+        if items.iter().any(|item| item.content.trim().is_empty()) {
+            return Err("Every todo needs a content".to_string());
+        }
+        let in_progress = items
+            .iter()
+            .filter(|item| item.status == TodoStatus::InProgress)
+            .count();
+        if in_progress > 1 {
+            return Err(format!(
+                "Only one todo can be in progress at a time, {} are",
+                in_progress
+            ));
+        }
+        Ok(Self { items })
+    }
+
+    /// Returns `true` if the list has no items.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Returns `true` if every item is done.
+    pub fn is_complete(&self) -> bool {
+        self.items
+            .iter()
+            .all(|item| item.status == TodoStatus::Done)
+    }
+
+    /// Returns the definition of the tool used to replace the todo list.
+    pub fn write_tool() -> Tool {
+        Tool {
+            tool_type: ToolType::Function,
+            function: Function {
+                name: TODO_WRITE_TOOL.to_string(),
+                description: "Replace your todo list. Use it for requests that take several steps: write the list before starting, keep exactly one item in_progress while working on it, and mark items done as soon as they are finished.".to_string(),
+                parameters: Parameters {
+                    param_type: "object".to_string(),
+                    properties: HashMap::from([(
+                        "todos".to_string(),
+                        Property {
+                            prop_type: "string".to_string(),
+                            description: "The complete list as a JSON array of objects with a \"content\" and a \"status\" of \"pending\", \"in_progress\" or \"done\"".to_string(),
+                        },
+                    )]),
+                    required: vec!["todos".to_string()],
+                    additional_properties: false,
+                },
+                strict: true,
+            },
+        }
+    }
+
+    /// Returns the definition of the tool used to read the todo list.
+    pub fn read_tool() -> Tool {
+        Tool {
+            tool_type: ToolType::Function,
+            function: Function {
+                name: TODO_READ_TOOL.to_string(),
+                description: "Read your todo list with the status of each item.".to_string(),
+                parameters: Parameters {
+                    param_type: "object".to_string(),
+                    properties: HashMap::new(),
+                    required: vec![],
+                    additional_properties: false,
+                },
+                strict: true,
+            },
+        }
+    }
+}
+
+impl fmt::Display for TodoList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let items: Vec<String> = self
+            .items
+            .iter()
+            .map(|item| {
+                let mark = match item.status {
+                    TodoStatus::Pending => " ",
+                    TodoStatus::InProgress => "~",
+                    TodoStatus::Done => "x",
+                };
+                format!("[{}] {}", mark, item.content)
+            })
+            .collect();
+        write!(f, "{}", items.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_rejects_invalid_lists() {
+        assert!(TodoList::parse("not json").is_err());
+        assert!(TodoList::parse(r#"[{"content": "Test", "status": "started"}]"#).is_err());
+        assert_eq!(
+            TodoList::parse(r#"[{"content": " ", "status": "pending"}]"#),
+            Err("Every todo needs a content".to_string())
+        );
+        assert_eq!(
+            TodoList::parse(
+                r#"[{"content": "A", "status": "in_progress"}, {"content": "B", "status": "in_progress"}]"#
+            ),
+            Err("Only one todo can be in progress at a time, 2 are".to_string())
+        );
+    }
+
+    #[test]
+    fn is_complete_once_every_item_is_done() {
+        let todos = TodoList::parse(r#"[{"content": "A", "status": "done"}]"#).unwrap();
+
+        assert!(todos.is_complete());
+        assert!(TodoList::parse("[]").unwrap().is_empty());
+    }
+}
@@ -0,0 +1,389 @@
+use crate::client::models::{AssistantMessage, ChatMessage, ToolCall};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::str::FromStr;
+
+/// File format of an exported transcript.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TranscriptFormat {
+    /// Readable Markdown, with tool calls in collapsible sections
+    Markdown,
+    /// A self-contained HTML page, with tool calls in collapsible sections
+    Html,
+    /// Every message of the conversation, including system messages
+    Json,
+}
+
+impl TranscriptFormat {
+    /// Returns the file extension used for the format.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            TranscriptFormat::Markdown => "md",
+            TranscriptFormat::Html => "html",
+            TranscriptFormat::Json => "json",
+        }
+    }
+}
+
+impl fmt::Display for TranscriptFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TranscriptFormat::Markdown => write!(f, "markdown"),
+            TranscriptFormat::Html => write!(f, "html"),
+            TranscriptFormat::Json => write!(f, "json"),
+        }
+    }
+}
+
+impl FromStr for TranscriptFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "md" | "markdown" => Ok(TranscriptFormat::Markdown),
+            "html" => Ok(TranscriptFormat::Html),
+            "json" => Ok(TranscriptFormat::Json),
+            _ => Err(format!("Unknown transcript format '{}'", s)),
+        }
+    }
+}
+
+/// A step of the conversation as shown in a readable transcript.
+#[derive(Debug, Clone, PartialEq)]
+enum Entry {
+    User(String),
+    Assistant(String),
+    Tool {
+        name: String,
+        arguments: BTreeMap<String, String>,
+        result: String,
+        diff: Option<String>,
+    },
+}
+
+/// The lossless JSON form of a transcript.
+#[derive(Serialize)]
+struct JsonTranscript<'a> {
+    messages: &'a [ChatMessage],
+}
+
+/// A conversation exported for code reviews and incident writeups.
+///
+/// The Markdown and HTML forms show the user's and the assistant's messages,
+/// with each tool call collapsed under a summary line together with its
+/// arguments, its result and, for file edits, a diff. System messages are
+/// left out of them. The JSON form keeps every message as it is.
+///
+/// # Examples
+///
+/// ```rust
+/// use code_g::client::models::ChatMessage;
+/// use code_g::session::transcript::{Transcript, TranscriptFormat};
+///
+/// let transcript = Transcript::new(&[
+///     ChatMessage::System { content: "You are CodeG".to_string() },
+///     ChatMessage::User { content: "Hello".to_string() },
+/// ]);
+///
+/// let markdown = transcript.render(TranscriptFormat::Markdown).unwrap();
+/// assert_eq!(markdown, "# CodeG transcript\n\n## User\n\nHello\n");
+///
+/// let json = transcript.render(TranscriptFormat::Json).unwrap();
+/// assert!(json.contains("You are CodeG"));
+/// ```
+pub struct Transcript {
+    messages: Vec<ChatMessage>,
+}
+
+impl Transcript {
+    /// Creates a transcript of the given messages.
+    ///
+    /// # Arguments
+    ///
+    /// * `messages` - The messages of the conversation, usually the session's memory
+    pub fn new(messages: &[ChatMessage]) -> Self {
+        Self {
+            messages: messages.to_vec(),
+        }
+    }
+
+    /// Renders the transcript in the given format.
+    ///
+    /// # Arguments
+    ///
+    /// * `format` - The [`TranscriptFormat`] to render
+    ///
+    /// # Returns
+    ///
+    /// The content of the transcript file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the messages cannot be serialized to JSON.
+    pub fn render(&self, format: TranscriptFormat) -> Result<String, serde_json::Error> {
+        match format {
+            TranscriptFormat::Markdown => Ok(self.to_markdown()),
+            TranscriptFormat::Html => Ok(self.to_html()),
+            TranscriptFormat::Json => serde_json::to_string_pretty(&JsonTranscript {
+                messages: &self.messages,
+            }),
+        }
+    }
+
+    /// Pairs each tool response with the call it answers.
+    fn entries(&self) -> Vec<Entry> {
+        let mut calls: HashMap<&str, &ToolCall> = HashMap::new();
+        let mut entries = Vec::new();
+
+        for message in &self.messages {
+            match message {
+                ChatMessage::System { .. } => {}
+                ChatMessage::User { content } => entries.push(Entry::User(content.clone())),
+                ChatMessage::Assistant {
+                    message: AssistantMessage::Content(content),
+                } => entries.push(Entry::Assistant(content.clone())),
+                ChatMessage::Assistant {
+                    message: AssistantMessage::ToolCalls(tool_calls),
+                } => {
+                    for call in tool_calls {
+                        calls.insert(call.id.as_str(), call);
+                    }
+                }
+                ChatMessage::Tool {
+                    content,
+                    tool_call_id,
+                    tool_name,
+                } => {
+                    let arguments: BTreeMap<String, String> = calls
+                        .get(tool_call_id.as_str())
+                        .map(|call| call.arguments.clone().into_iter().collect())
+                        .unwrap_or_default();
+                    let diff = edit_diff(tool_name, &arguments);
+                    entries.push(Entry::Tool {
+                        name: tool_name.clone(),
+                        arguments,
+                        result: content.clone(),
+                        diff,
+                    });
+                }
+            }
+        }
+        entries
+    }
+
+    fn to_markdown(&self) -> String {
+        let mut sections = vec!["# CodeG transcript".to_string()];
+        for entry in self.entries() {
+            sections.push(match entry {
+                Entry::User(content) => format!("## User\n\n{}", content),
+                Entry::Assistant(content) => format!("## Assistant\n\n{}", content),
+                Entry::Tool {
+                    name,
+                    arguments,
+                    result,
+                    diff,
+                } => {
+                    let mut details = vec![
+                        format!(
+                            "<details>\n<summary>Tool call: {}</summary>",
+                            summary(&name, &arguments)
+                        ),
+                        format!("**Arguments**\n\n{}", code_block("json", &json(&arguments))),
+                    ];
+                    if let Some(diff) = diff {
+                        details.push(format!("**Diff**\n\n{}", code_block("diff", &diff)));
+                    }
+                    details.push(format!("**Result**\n\n{}", code_block("", &result)));
+                    details.push("</details>".to_string());
+                    details.join("\n\n")
+                }
+            });
+        }
+        sections.join("\n\n") + "\n"
+    }
+
+    fn to_html(&self) -> String {
+        let mut body = Vec::new();
+        for entry in self.entries() {
+            body.push(match entry {
+                Entry::User(content) => format!(
+                    "<section class=\"user\"><h2>User</h2><pre>{}</pre></section>",
+                    escape(&content)
+                ),
+                Entry::Assistant(content) => format!(
+                    "<section class=\"assistant\"><h2>Assistant</h2><pre>{}</pre></section>",
+                    escape(&content)
+                ),
+                Entry::Tool {
+                    name,
+                    arguments,
+                    result,
+                    diff,
+                } => {
+                    let diff = diff
+                        .map(|diff| {
+                            let lines: Vec<String> = diff
+                                .lines()
+                                .map(|line| {
+                                    let class = if line.starts_with('+') { "add" } else { "del" };
+                                    format!("<span class=\"{}\">{}</span>", class, escape(line))
+                                })
+                                .collect();
+                            format!("<h3>Diff</h3><pre class=\"diff\">{}</pre>", lines.join("\n"))
+                        })
+                        .unwrap_or_default();
+                    format!(
+                        "<details class=\"tool\"><summary>Tool call: {}</summary><h3>Arguments</h3><pre>{}</pre>{}<h3>Result</h3><pre>{}</pre></details>",
+                        escape(&summary(&name, &arguments)),
+                        escape(&json(&arguments)),
+                        diff,
+                        escape(&result)
+                    )
+                }
+            });
+        }
+
+        format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>CodeG transcript</title>\n<style>\n{}\n</style>\n</head>\n<body>\n<h1>CodeG transcript</h1>\n{}\n</body>\n</html>\n",
+            HTML_STYLE,
+            body.join("\n")
+        )
+    }
+}
+
+// Styles embedded in HTML transcripts so they render without other files
+const HTML_STYLE: &str = "body { font-family: sans-serif; max-width: 60rem; margin: 2rem auto; }
+pre { white-space: pre-wrap; background: #f6f8fa; padding: 0.75rem; }
+section.user h2 { color: #0969da; }
+section.assistant h2 { color: #8250df; }
+details.tool { margin: 1rem 0; border-left: 3px solid #d0d7de; padding-left: 0.75rem; }
+.add { color: #1a7f37; }
+.del { color: #cf222e; }";
+
+/// Builds a diff of the change made by an `edit_file` call.
+fn edit_diff(tool_name: &str, arguments: &BTreeMap<String, String>) -> Option<String> {
+    if tool_name != "edit_file" {
+        return None;
+    }
+    let old = arguments.get("old_string")?;
+    let new = arguments.get("new_string")?;
+
+    let removed = old.lines().map(|line| format!("-{}", line));
+    let added = new.lines().map(|line| format!("+{}", line));
+    Some(removed.chain(added).collect::<Vec<_>>().join("\n"))
+}
+
+/// Returns the summary line of a tool call, naming the tool and its path or command.
+fn summary(name: &str, arguments: &BTreeMap<String, String>) -> String {
+    match arguments.get("path").or_else(|| arguments.get("command")) {
+        Some(target) => format!("{} ({})", name, target),
+        None => name.to_string(),
+    }
+}
+
+fn json(arguments: &BTreeMap<String, String>) -> String {
+    serde_json::to_string_pretty(arguments).unwrap_or_default()
+}
+
+/// Wraps content in a code fence longer than any run of backticks it contains.
+fn code_block(language: &str, content: &str) -> String {
+    let longest = content.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    let fence = "`".repeat(longest.max(2) + 1);
+    format!(
+        "{}{}\n{}\n{}",
+        fence,
+        language,
+        content.trim_end_matches('\n'),
+        fence
+    )
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conversation() -> Vec<ChatMessage> {
+        vec![
+            ChatMessage::System {
+                content: "You are CodeG".to_string(),
+            },
+            ChatMessage::User {
+                content: "Rename the <main> function".to_string(),
+            },
+            ChatMessage::Assistant {
+                message: AssistantMessage::ToolCalls(vec![ToolCall {
+                    id: "1".to_string(),
+                    name: "edit_file".to_string(),
+                    arguments: HashMap::from([
+                        ("path".to_string(), "src/main.rs".to_string()),
+                        ("old_string".to_string(), "fn main()".to_string()),
+                        ("new_string".to_string(), "fn run()".to_string()),
+                    ]),
+                }]),
+            },
+            ChatMessage::Tool {
+                content: "Edited src/main.rs".to_string(),
+                tool_call_id: "1".to_string(),
+                tool_name: "edit_file".to_string(),
+            },
+            ChatMessage::Assistant {
+                message: AssistantMessage::Content("Done".to_string()),
+            },
+        ]
+    }
+
+    #[test]
+    fn render_markdown_collapses_tool_calls_with_diff() {
+        let markdown = Transcript::new(&conversation())
+            .render(TranscriptFormat::Markdown)
+            .unwrap();
+
+        assert_eq!(
+            markdown,
+            "# CodeG transcript\n\n## User\n\nRename the <main> function\n\n\
+             <details>\n<summary>Tool call: edit_file (src/main.rs)</summary>\n\n\
+             **Arguments**\n\n```json\n{\n  \"new_string\": \"fn run()\",\n  \"old_string\": \"fn main()\",\n  \"path\": \"src/main.rs\"\n}\n```\n\n\
+             **Diff**\n\n```diff\n-fn main()\n+fn run()\n```\n\n\
+             **Result**\n\n```\nEdited src/main.rs\n```\n\n</details>\n\n\
+             ## Assistant\n\nDone\n"
+        );
+    }
+
+    #[test]
+    fn render_html_escapes_content() {
+        let html = Transcript::new(&conversation())
+            .render(TranscriptFormat::Html)
+            .unwrap();
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<pre>Rename the &lt;main&gt; function</pre>"));
+        assert!(html.contains("<span class=\"del\">-fn main()</span>"));
+        assert!(!html.contains("You are CodeG"));
+    }
+
+    #[test]
+    fn render_json_keeps_every_message() {
+        let messages = conversation();
+
+        let json = Transcript::new(&messages)
+            .render(TranscriptFormat::Json)
+            .unwrap();
+
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let parsed: Vec<ChatMessage> = serde_json::from_value(value["messages"].clone()).unwrap();
+        assert_eq!(parsed, messages);
+    }
+
+    #[test]
+    fn code_block_uses_a_longer_fence_than_the_content() {
+        assert_eq!(code_block("", "```rust\n```"), "````\n```rust\n```\n````");
+    }
+}
@@ -0,0 +1,90 @@
+use crate::client::models::Usage;
+use crate::session::budget::BudgetLimit;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// How a tool call of the assistant was decided on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolCallDecision {
+    /// The call ran without asking, because the permission policy allowed it
+    /// or the session handles the tool itself
+    Allowed,
+    /// The user approved the call, once or for good
+    Approved,
+    /// The user declined the call, or could not be asked
+    Declined,
+    /// The permission policy denied the call
+    Denied,
+    /// A pre-tool hook blocked the call
+    Blocked,
+}
+
+impl ToolCallDecision {
+    /// Returns `true` if the tool was run.
+    pub fn is_executed(&self) -> bool {
+        matches!(self, Self::Allowed | Self::Approved)
+    }
+}
+
+/// A tool call made during a turn, together with its outcome.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ToolCallOutcome {
+    /// The id the assistant gave the call
+    pub id: String,
+    /// The name of the called tool
+    pub tool_name: String,
+    /// The arguments of the call
+    pub arguments: HashMap<String, String>,
+    /// The response added to the conversation, as the assistant saw it
+    pub response: String,
+    /// How the call was decided on
+    pub decision: ToolCallDecision,
+}
+
+/// The outcome of a turn sent with
+/// [`ChatSession::send_message`](crate::session::session::ChatSession::send_message).
+///
+/// # Examples
+///
+/// ```rust
+/// use code_g::client::models::Usage;
+/// use code_g::session::turn::{ToolCallDecision, ToolCallOutcome, TurnResult};
+/// use std::collections::HashMap;
+///
+/// let turn = TurnResult {
+///     message: "The parser is in src/parser.rs".to_string(),
+///     tool_calls: vec![ToolCallOutcome {
+///         id: "call_1".to_string(),
+///         tool_name: "search_files".to_string(),
+///         arguments: HashMap::from([("pattern".to_string(), "parser".to_string())]),
+///         response: "src/parser.rs".to_string(),
+///         decision: ToolCallDecision::Allowed,
+///     }],
+///     usage: Usage::default(),
+///     budget_exhausted: None,
+/// };
+///
+/// assert_eq!(turn.executed_tool_calls().count(), 1);
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TurnResult {
+    /// The final message of the assistant
+    pub message: String,
+    /// The tool calls of the turn, in the order they were made
+    pub tool_calls: Vec<ToolCallOutcome>,
+    /// The token usage of the turn
+    pub usage: Usage,
+    /// The budget limit the turn exhausted, in which case the message is a
+    /// summary of the unfinished work
+    pub budget_exhausted: Option<BudgetLimit>,
+}
+
+impl TurnResult {
+    /// Returns the tool calls that were run.
+    pub fn executed_tool_calls(&self) -> impl Iterator<Item = &ToolCallOutcome> {
+        self.tool_calls
+            .iter()
+            .filter(|tool_call| tool_call.decision.is_executed())
+    }
+}
@@ -0,0 +1,159 @@
+use crate::shell::runner::run_shell;
+use serde::Deserialize;
+use std::path::Path;
+use std::time::Duration;
+
+/// Default number of times the assistant is asked to fix a failing check in a turn.
+pub const DEFAULT_MAX_RETRIES: usize = 3;
+
+/// Default number of seconds the check command may run before it is stopped.
+pub const DEFAULT_TIMEOUT_SECS: u64 = 600;
+
+/// The check command run after turns that modified files.
+///
+/// When the assistant finishes a turn in which it changed files, the command
+/// is run in the project directory. If it fails, its output is given to the
+/// assistant, which continues the turn to fix the problems. This repeats until
+/// the check passes or the assistant was sent back `max_retries` times. A
+/// check running longer than `timeout_secs` is stopped and counts as failed.
+///
+/// # Examples
+///
+/// ```rust
+/// use code_g::session::verification::VerificationConfig;
+///
+/// let config: VerificationConfig = serde_json::from_str(r#"{
+///     "command": "cargo check && cargo test"
+/// }"#).unwrap();
+///
+/// assert_eq!(config.command.as_deref(), Some("cargo check && cargo test"));
+/// assert_eq!(config.max_retries, 3);
+/// assert_eq!(config.timeout_secs, 600);
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct VerificationConfig {
+    /// The shell command checking the project, `None` to skip verification
+    pub command: Option<String>,
+    /// How often the assistant is asked to fix a failing check per turn
+    pub max_retries: usize,
+    /// How many seconds the command may run before it is stopped
+    pub timeout_secs: u64,
+}
+
+impl Default for VerificationConfig {
+    fn default() -> Self {
+        Self {
+            command: None,
+            max_retries: DEFAULT_MAX_RETRIES,
+            timeout_secs: DEFAULT_TIMEOUT_SECS,
+        }
+    }
+}
+
+impl VerificationConfig {
+    /// Applies the settings of another file on top of these.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The settings that take precedence
+    pub fn merge(&mut self, other: VerificationConfig) {
+        if other.command.is_some() {
+            *self = other;
+        }
+    }
+}
+
+/// The outcome of a run of the check command.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CheckResult {
+    /// Whether the command exited successfully
+    pub passed: bool,
+    /// The command's stdout and stderr
+    pub output: String,
+}
+
+/// Runs a check command in the directory with the system shell.
+///
+/// # Arguments
+///
+/// * `command` - The shell command to run
+/// * `dir` - The directory to run it in
+/// * `timeout` - How long the command may run before it is stopped
+///
+/// # Returns
+///
+/// The [`CheckResult`]. A command that cannot be started or does not finish
+/// in time fails with the reason as its output.
+///
+/// # Examples
+///
+/// ```rust
+/// use code_g::session::verification::run_check;
+/// use std::path::Path;
+/// use std::time::Duration;
+///
+/// let result = run_check("echo ok", Path::new("."), Duration::from_secs(10));
+/// assert!(result.passed);
+/// assert_eq!(result.output.trim(), "ok");
+/// ```
+pub fn run_check(command: &str, dir: &Path, timeout: Duration) -> CheckResult {
+    match run_shell(command, Some(dir), None, Some(timeout)) {
+        Ok(output) => CheckResult {
+            passed: output.success(),
+            output: output.combined(),
+        },
+        Err(e) => CheckResult {
+            passed: false,
+            output: format!("Failed to run the check command: {}", e),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merge_keeps_settings_without_command() {
+        let mut config = VerificationConfig {
+            command: Some("cargo test".to_string()),
+            max_retries: 1,
+            timeout_secs: DEFAULT_TIMEOUT_SECS,
+        };
+
+        config.merge(VerificationConfig::default());
+        assert_eq!(config.command.as_deref(), Some("cargo test"));
+
+        config.merge(VerificationConfig {
+            command: Some("make check".to_string()),
+            max_retries: 5,
+            timeout_secs: 60,
+        });
+        assert_eq!(config.command.as_deref(), Some("make check"));
+        assert_eq!(config.max_retries, 5);
+    }
+
+    #[test]
+    fn run_check_reports_failing_commands_with_output() {
+        let result = run_check(
+            "echo broken && exit 1",
+            Path::new("."),
+            Duration::from_secs(10),
+        );
+
+        assert!(!result.passed);
+        assert_eq!(result.output.trim(), "broken");
+    }
+
+    #[test]
+    fn run_check_fails_commands_that_time_out() {
+        let result = run_check("sleep 10", Path::new("."), Duration::from_millis(100));
+
+        assert!(!result.passed);
+        assert_eq!(
+            result.output,
+            "Failed to run the check command: 'sleep 10' did not finish within 0.1 seconds"
+        );
+    }
+}
@@ -0,0 +1,32 @@
+use std::io;
+use std::time::Duration;
+use thiserror::Error;
+
+/// Represents errors that can occur while running a shell command.
+///
+/// # Examples
+///
+/// ```rust
+/// use code_g::shell::error::ShellError;
+/// use std::time::Duration;
+///
+/// let error = ShellError::TimedOut {
+///     command: "cargo test".to_string(),
+///     timeout: Duration::from_secs(60),
+/// };
+/// assert_eq!(error.to_string(), "'cargo test' did not finish within 60 seconds");
+/// ```
+#[derive(Error, Debug)]
+pub enum ShellError {
+    /// The shell could not be started or its output could not be read
+    #[error("Failed to run '{command}': {source}")]
+    Spawn {
+        command: String,
+        #[source]
+        source: io::Error,
+    },
+
+    /// The command was killed because it ran longer than its timeout
+    #[error("'{command}' did not finish within {} seconds", timeout.as_secs_f64())]
+    TimedOut { command: String, timeout: Duration },
+}
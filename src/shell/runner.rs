@@ -0,0 +1,216 @@
+use crate::shell::error::ShellError;
+use std::io::{Read, Write};
+use std::path::Path;
+use std::process::{Child, Command, Stdio};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+// How often a running command is checked for completion
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// The outcome of a shell command that ran to completion.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShellOutput {
+    /// The exit status, `None` if the command was ended by a signal
+    pub status: Option<i32>,
+    /// Everything the command wrote to stdout
+    pub stdout: String,
+    /// Everything the command wrote to stderr
+    pub stderr: String,
+}
+
+impl ShellOutput {
+    /// Returns `true` if the command exited with status 0.
+    pub fn success(&self) -> bool {
+        self.status == Some(0)
+    }
+
+    /// Returns stdout followed by stderr.
+    pub fn combined(&self) -> String {
+        format!("{}{}", self.stdout, self.stderr)
+    }
+}
+
+/// Returns a [`Command`] running `command` with the system shell.
+///
+/// Commands run with `sh -c` on Unix and `cmd /C` on Windows.
+///
+/// # Arguments
+///
+/// * `command` - The shell command to run
+pub fn shell_command(command: &str) -> Command {
+    let (shell, flag) = if cfg!(target_os = "windows") {
+        ("cmd", "/C")
+    } else {
+        ("sh", "-c")
+    };
+
+    let mut shell = Command::new(shell);
+    shell.arg(flag).arg(command);
+    shell
+}
+
+/// Runs a shell command, optionally with input and a time limit.
+///
+/// The input is written and the output read on separate threads, so a
+/// command that writes a lot before reading its input cannot block on a full
+/// pipe. A command running longer than `timeout` is killed.
+///
+/// # Arguments
+///
+/// * `command` - The shell command to run
+/// * `dir` - The directory to run it in, the current directory if `None`
+/// * `input` - Written to the command's stdin, which is closed if `None`
+/// * `timeout` - How long the command may run, without limit if `None`
+///
+/// # Returns
+///
+/// The [`ShellOutput`] of the command.
+///
+/// # Errors
+///
+/// Returns [`ShellError::Spawn`] if the shell cannot be started, or
+/// [`ShellError::TimedOut`] if the command was killed after `timeout`.
+///
+/// # Examples
+///
+/// ```rust
+/// use code_g::shell::runner::run_shell;
+/// use std::time::Duration;
+///
+/// let output = run_shell("cat", None, Some("hello"), Some(Duration::from_secs(5))).unwrap();
+///
+/// assert!(output.success());
+/// assert_eq!(output.stdout, "hello");
+/// ```
+pub fn run_shell(
+    command: &str,
+    dir: Option<&Path>,
+    input: Option<&str>,
+    timeout: Option<Duration>,
+) -> Result<ShellOutput, ShellError> {
+    let spawn_error = |source| ShellError::Spawn {
+        command: command.to_string(),
+        source,
+    };
+
+    let mut shell = shell_command(command);
+    if let Some(dir) = dir {
+        shell.current_dir(dir);
+    }
+    let mut child = shell
+        .stdin(if input.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(spawn_error)?;
+
+    if let (Some(mut stdin), Some(input)) = (child.stdin.take(), input) {
+        let input = input.to_string();
+        // Commands are free to ignore their input and exit early
+        thread::spawn(move || {
+            let _ = stdin.write_all(input.as_bytes());
+        });
+    }
+    let stdout = read_in_background(child.stdout.take());
+    let stderr = read_in_background(child.stderr.take());
+
+    let status = match wait(&mut child, timeout).map_err(spawn_error)? {
+        Some(status) => status,
+        None => {
+            let _ = child.kill();
+            let _ = child.wait();
+            // The readers are left behind, since processes started by the
+            // command may keep its output open
+            return Err(ShellError::TimedOut {
+                command: command.to_string(),
+                timeout: timeout.unwrap_or_default(),
+            });
+        }
+    };
+
+    Ok(ShellOutput {
+        status: status.code(),
+        stdout: stdout.join().unwrap_or_default(),
+        stderr: stderr.join().unwrap_or_default(),
+    })
+}
+
+/// Reads a pipe to the end on a separate thread.
+fn read_in_background(pipe: Option<impl Read + Send + 'static>) -> JoinHandle<String> {
+    thread::spawn(move || {
+        let mut output = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut output);
+        }
+        String::from_utf8_lossy(&output).to_string()
+    })
+}
+
+/// Waits for the child to exit, returning `None` if it is still running after `timeout`.
+fn wait(
+    child: &mut Child,
+    timeout: Option<Duration>,
+) -> std::io::Result<Option<std::process::ExitStatus>> {
+    let Some(timeout) = timeout else {
+        return child.wait().map(Some);
+    };
+
+    let deadline = Instant::now() + timeout;
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Some(status));
+        }
+        if Instant::now() >= deadline {
+            return Ok(None);
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn run_shell_does_not_block_on_output_written_before_reading_input() {
+        // Writes far more than a pipe buffer holds before reading its input
+        let output = run_shell(
+            "head -c 1000000 /dev/zero; cat > /dev/null; echo done >&2",
+            None,
+            Some(&"x".repeat(1_000_000)),
+            Some(Duration::from_secs(30)),
+        )
+        .unwrap();
+
+        assert!(output.success());
+        assert_eq!(output.stdout.len(), 1_000_000);
+        assert_eq!(output.stderr, "done\n");
+    }
+
+    #[test]
+    fn run_shell_kills_commands_that_exceed_the_timeout() {
+        let started = Instant::now();
+
+        let result = run_shell("sleep 10", None, None, Some(Duration::from_millis(100)));
+
+        assert!(matches!(result, Err(ShellError::TimedOut { .. })));
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn run_shell_runs_in_the_given_directory() {
+        let dir = std::env::temp_dir();
+
+        let output = run_shell("pwd", Some(&dir), None, None).unwrap();
+
+        assert_eq!(
+            Path::new(output.stdout.trim()).canonicalize().unwrap(),
+            dir.canonicalize().unwrap()
+        );
+    }
+}
@@ -4,6 +4,7 @@ use crate::tui::diff::Diff;
 use crate::tui::models::Status;
 use std::collections::HashMap;
 use std::fs;
+use std::path::PathBuf;
 
 /// A tool for editing files by replacing specific strings with new content.
 ///
@@ -148,6 +149,11 @@ impl Tool for EditFile {
         format!("Edited {} lines in {}", lines, path)
     }
 
+    /// The edit file tool modifies the file at the given path.
+    fn modified_paths(&self, args: &HashMap<String, String>) -> Vec<PathBuf> {
+        args.get("path").map(PathBuf::from).into_iter().collect()
+    }
+
     /// Executes the file editing operation.
     ///
     /// Reads the specified file, finds the exact occurrence of the old string,
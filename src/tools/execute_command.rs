@@ -1,8 +1,11 @@
 use crate::client::models::{Parameters, Property};
+use crate::permissions::grant::is_read_only_command;
+use crate::shell::error::ShellError;
+use crate::shell::runner::run_shell;
 use crate::tools::traits::Tool;
 use crate::tui::models::Status;
 use std::collections::HashMap;
-use std::process::Command;
+use std::path::Path;
 
 /// A tool for executing shell commands and returning their output.
 ///
@@ -86,7 +89,9 @@ impl Tool for ExecuteCommand {
     /// Generates the TUI status for the execute command tool with the given arguments.
     fn status(&self, args: &HashMap<String, String>) -> Status {
         let command = args.get("command").map(|s| s.as_str()).unwrap_or("unknown");
-        Status::ExecutingCommand { command: command.to_string() }
+        Status::ExecutingCommand {
+            command: command.to_string(),
+        }
     }
 
     /// Generates the summary message for the execute command tool with the given arguments.
@@ -95,6 +100,13 @@ impl Tool for ExecuteCommand {
         format!("Executed command '{}'", command)
     }
 
+    /// A shell command can change any file, so only commands known to just read
+    /// files, such as `ls` or `git status`, are assumed to leave them unchanged.
+    fn modifies_files(&self, args: &HashMap<String, String>) -> bool {
+        args.get("command")
+            .is_none_or(|command| !is_read_only_command(command))
+    }
+
     /// Executes the shell command and returns its output.
     ///
     /// Runs the specified command using the system shell and captures both
@@ -117,41 +129,44 @@ impl Tool for ExecuteCommand {
     /// - The command fails to execute
     /// - The command returns a non-zero exit code
     fn call(&self, args: HashMap<String, String>) -> Result<String, String> {
-        let command = args.get("command").ok_or("Command is required")?;
+        Self::execute(&args, None)
+    }
 
-        // Determine the shell based on the operating system
-        let (shell, flag) = if cfg!(target_os = "windows") {
-            ("cmd", "/C")
-        } else {
-            ("sh", "-c")
-        };
+    /// Executes the shell command in the given directory.
+    fn call_in_dir(&self, args: HashMap<String, String>, dir: &Path) -> Result<String, String> {
+        Self::execute(&args, Some(dir))
+    }
+}
+
+impl ExecuteCommand {
+    /// Runs the command argument in the directory, the current one if `None`.
+    fn execute(args: &HashMap<String, String>, dir: Option<&Path>) -> Result<String, String> {
+        let command = args.get("command").ok_or("Command is required")?;
 
         // Execute the command
-        let output = Command::new(shell)
-            .arg(flag)
-            .arg(command)
-            .output()
-            .map_err(|e| format!("Failed to execute command '{}': {}", command, e))?;
+        let output = run_shell(command, dir, None, None).map_err(|e| match e {
+            ShellError::Spawn { source, .. } => {
+                format!("Failed to execute command '{}': {}", command, source)
+            }
+            e => e.to_string(),
+        })?;
 
         // Combine stdout and stderr
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let stderr = String::from_utf8_lossy(&output.stderr);
-
         let mut result = String::new();
-        if !stdout.is_empty() {
-            result.push_str(&stdout);
+        if !output.stdout.is_empty() {
+            result.push_str(&output.stdout);
         }
-        if !stderr.is_empty() {
+        if !output.stderr.is_empty() {
             if !result.is_empty() {
                 result.push('\n');
             }
             result.push_str("STDERR:\n");
-            result.push_str(&stderr);
+            result.push_str(&output.stderr);
         }
 
         // Check if the command was successful
-        if !output.status.success() {
-            let exit_code = output.status.code().unwrap_or(-1);
+        if !output.success() {
+            let exit_code = output.status.unwrap_or(-1);
             return Err(format!(
                 "Command '{}' failed with exit code {}\nOutput: {}",
                 command, exit_code, result
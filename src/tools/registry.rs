@@ -6,6 +6,16 @@ use crate::tools::search_files::SearchFiles;
 use crate::tools::traits::{Tool, ToolRegistry};
 use crate::tools::write_file::WriteFile;
 use std::collections::HashMap;
+use std::path::Path;
+
+/// Names of the tools that only read, the tools of [`Registry::read_only_tools`].
+pub const READ_ONLY_TOOLS: [&str; 2] = ["read_file", "search_files"];
+
+/// Names of the tools that change files.
+///
+/// Accept-edits mode approves them automatically, and they are only available
+/// to the editor when one is paired.
+pub const EDIT_TOOLS: [&str; 2] = ["edit_file", "write_file"];
 
 /// A registry for managing and executing tools.
 ///
@@ -69,13 +79,44 @@ impl ToolRegistry for Registry {
     /// let result = registry.call_tool("read_file", args);
     /// ```
     fn call_tool(&self, tool_name: &str, args: HashMap<String, String>) -> Result<String, String> {
-        let tool = self.get_tool(tool_name).ok_or(format!("Tool {} not found", tool_name))?;
+        let tool = self
+            .get_tool(tool_name)
+            .ok_or(format!("Tool {} not found", tool_name))?;
         match tool.call(args) {
             Ok(result) => Ok(result),
             Err(error_message) => Err(format!("Error: {}", error_message)), // Ensure error message is always prefixed with "Error:"
         }
     }
 
+    /// Executes a tool by name in the given directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `tool_name` - The name of the tool to execute.
+    /// * `args` - A HashMap containing the arguments to pass to the tool.
+    /// * `dir` - The directory relative paths and commands are resolved against.
+    ///
+    /// # Returns
+    ///
+    /// The output from the tool execution as a String.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tool is not found in the registry or if the tool
+    /// execution fails, prefixed like the errors of [`ToolRegistry::call_tool`].
+    fn call_tool_in_dir(
+        &self,
+        tool_name: &str,
+        args: HashMap<String, String>,
+        dir: &Path,
+    ) -> Result<String, String> {
+        let tool = self
+            .get_tool(tool_name)
+            .ok_or(format!("Tool {} not found", tool_name))?;
+        tool.call_in_dir(args, dir)
+            .map_err(|error_message| format!("Error: {}", error_message))
+    }
+
     /// Converts all tools in the registry to tool format.
     ///
     /// # Returns
@@ -259,6 +300,16 @@ impl Registry {
 mod tests {
     use super::*;
 
+    #[test]
+    fn read_only_tool_names_match_the_read_only_registry() {
+        let names: Vec<String> = Registry::read_only_tools()
+            .get_tools()
+            .iter()
+            .map(|tool| tool.name())
+            .collect();
+        assert_eq!(names, READ_ONLY_TOOLS);
+    }
+
     #[test]
     fn new_creates_a_tool_registry_with_no_tools() {
         let registry = Registry::new();
@@ -129,8 +129,19 @@ impl Tool for SearchFiles {
     /// - No files match the specified pattern
     /// - The directory cannot be read due to permissions or other I/O errors
     fn call(&self, args: HashMap<String, String>) -> Result<String, String> {
+        Self::search(&args, Path::new("."))
+    }
+
+    /// Searches recursively from the given directory instead of the current one.
+    fn call_in_dir(&self, args: HashMap<String, String>, dir: &Path) -> Result<String, String> {
+        Self::search(&args, dir)
+    }
+}
+
+impl SearchFiles {
+    /// Searches the directory for the pattern argument and formats the matches.
+    fn search(args: &HashMap<String, String>, directory: &Path) -> Result<String, String> {
         let pattern = args.get("pattern").ok_or("Pattern is required")?;
-        let directory = ".";
 
         match Self::search_files(pattern, directory) {
             Ok((files, truncated)) => {
@@ -155,9 +166,7 @@ impl Tool for SearchFiles {
             Err(e) => Err(format!("Error searching for files: {}", e)),
         }
     }
-}
 
-impl SearchFiles {
     /// Searches for files matching a pattern in the specified directory.
     ///
     /// Performs a recursive search starting from the given directory for files
@@ -177,19 +186,21 @@ impl SearchFiles {
     ///
     /// Returns an error if the directory doesn't exist, isn't a directory, or
     /// cannot be read.
-    fn search_files(pattern: &str, directory: &str) -> Result<(Vec<String>, bool), String> {
+    fn search_files(pattern: &str, directory: &Path) -> Result<(Vec<String>, bool), String> {
         let mut found_files = Vec::new();
-        let search_path = Path::new(directory);
 
-        if !search_path.exists() {
-            return Err(format!("Directory '{}' does not exist", directory));
+        if !directory.exists() {
+            return Err(format!(
+                "Directory '{}' does not exist",
+                directory.display()
+            ));
         }
 
-        if !search_path.is_dir() {
-            return Err(format!("'{}' is not a directory", directory));
+        if !directory.is_dir() {
+            return Err(format!("'{}' is not a directory", directory.display()));
         }
 
-        Self::search_directory_recursive(search_path, pattern, &mut found_files)?;
+        Self::search_directory_recursive(directory, pattern, &mut found_files)?;
         found_files.sort();
 
         // Check if we hit the limit (indicating there might be more files)
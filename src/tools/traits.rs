@@ -1,7 +1,7 @@
 use crate::client::models::{Function, Parameters, Tool as ToolModel, ToolType};
 use crate::tui::models::Status;
 use std::collections::HashMap;
-
+use std::path::{Path, PathBuf};
 
 /// A trait defining the interface for tool registries.
 ///
@@ -91,6 +91,29 @@ pub trait ToolRegistry {
     /// # Returns
     /// A slice of references to all tools in the registry.
     fn get_tools(&self) -> &[Box<dyn Tool>];
+
+    /// Executes a tool by name, resolving relative paths against a directory.
+    ///
+    /// The default implementation ignores `dir` and calls
+    /// [`ToolRegistry::call_tool`], for registries whose tools do not use the
+    /// file system.
+    ///
+    /// # Arguments
+    /// * `tool_name` - The name of the tool to execute.
+    /// * `args` - A HashMap containing the arguments to pass to the tool.
+    /// * `dir` - The directory the tool works in instead of the current directory.
+    ///
+    /// # Errors
+    /// Returns an error if the tool is not found in the registry or if the tool
+    /// execution fails.
+    fn call_tool_in_dir(
+        &self,
+        tool_name: &str,
+        args: HashMap<String, String>,
+        _dir: &Path,
+    ) -> Result<String, String> {
+        self.call_tool(tool_name, args)
+    }
 }
 
 /// A trait defining the interface for tools that can be called with arguments.
@@ -271,6 +294,37 @@ pub trait Tool: ToolClone {
     /// A `String` containing the summary message.
     fn summary_message(&self, args: &HashMap<String, String>, result: &str) -> String;
 
+    /// Returns the files this tool will modify when called with the given arguments.
+    ///
+    /// The chat session snapshots these files before the tool runs so the changes
+    /// can be undone later. Tools that do not modify files can rely on the default
+    /// implementation, which returns an empty list.
+    ///
+    /// # Arguments
+    ///
+    /// * `args` - A HashMap containing the tool arguments as key-value string pairs.
+    ///
+    /// # Returns
+    ///
+    /// A `Vec<PathBuf>` containing the paths of the files that will be modified.
+    fn modified_paths(&self, _args: &HashMap<String, String>) -> Vec<PathBuf> {
+        vec![]
+    }
+
+    /// Returns `true` if calling this tool with the given arguments may change files.
+    ///
+    /// The chat session runs the verification command after turns in which
+    /// such a tool ran. The default implementation reports the tools that
+    /// list [`Tool::modified_paths`]; tools that can change any file, such as
+    /// shell commands, override it.
+    ///
+    /// # Arguments
+    ///
+    /// * `args` - A HashMap containing the tool arguments as key-value string pairs.
+    fn modifies_files(&self, args: &HashMap<String, String>) -> bool {
+        !self.modified_paths(args).is_empty()
+    }
+
     /// Executes the tool with the provided arguments.
     ///
     /// This is the main execution method for the tool. It receives a map of
@@ -292,6 +346,28 @@ pub trait Tool: ToolClone {
     /// such as invalid arguments, I/O errors, or internal processing errors.
     fn call(&self, args: HashMap<String, String>) -> Result<String, String>;
 
+    /// Executes the tool in a directory other than the current one.
+    ///
+    /// Chat sessions working on a project directory call tools through this
+    /// method. The default implementation resolves a relative `path` argument
+    /// against `dir` and calls [`Tool::call`]; tools that use the current
+    /// directory in other ways override it.
+    ///
+    /// # Arguments
+    ///
+    /// * `args` - A HashMap containing the tool arguments as key-value string pairs.
+    /// * `dir` - The directory relative paths are resolved against.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error string if the tool execution fails, as [`Tool::call`] does.
+    fn call_in_dir(&self, mut args: HashMap<String, String>, dir: &Path) -> Result<String, String> {
+        if let Some(path) = args.get_mut("path") {
+            *path = dir.join(&*path).to_string_lossy().to_string();
+        }
+        self.call(args)
+    }
+
     /// Converts the tool to tool format.
     ///
     /// This is useful when integrating with AI models that support tool calling.
@@ -4,6 +4,7 @@ use crate::tui::diff::Diff;
 use crate::tui::models::Status;
 use std::collections::HashMap;
 use std::fs;
+use std::path::PathBuf;
 
 /// A tool for writing content to files in the filesystem.
 ///
@@ -125,6 +126,11 @@ impl Tool for WriteFile {
         format!("Wrote {} lines to {}", lines, path)
     }
 
+    /// The write file tool modifies the file at the given path.
+    fn modified_paths(&self, args: &HashMap<String, String>) -> Vec<PathBuf> {
+        args.get("path").map(PathBuf::from).into_iter().collect()
+    }
+
     /// Executes the write file operation with the provided arguments.
     ///
     /// Creates a new file or overwrites an existing file at the specified path
@@ -174,6 +180,18 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn modified_paths_returns_the_target_path() {
+        let tool = WriteFile;
+
+        let paths = tool.modified_paths(&HashMap::from([(
+            "path".to_string(),
+            "src/main.rs".to_string(),
+        )]));
+
+        assert_eq!(paths, vec![PathBuf::from("src/main.rs")]);
+    }
+
     #[test]
     fn call_returns_error_when_content_is_not_provided() {
         let tool = WriteFile;
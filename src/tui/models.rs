@@ -19,6 +19,10 @@ pub enum Message {
     Assistant { content: String },
     /// A tool response. Contains the summary of the tool response and whether it is an error.
     ToolResponse { summary: String, is_error: bool },
+    /// A notice from the session itself, such as the result of a slash command.
+    Notice { content: String, is_error: bool },
+    /// The files and directories attached to the previous user message, shown as chips.
+    Attachments { chips: Vec<String> },
 }
 
 /// The status of the TUI.
@@ -49,6 +53,12 @@ pub enum Status {
     ExecutingCommand { command: String },
     /// The assistant is executing a miscellaneous tool.
     ExecutingTool { tool_name: String },
+    /// A sub-agent is working on a task delegated by the assistant.
+    RunningSubAgent { task: String },
+    /// The editor is applying the changes handed off by the assistant.
+    HandingOffEdits { files: String },
+    /// The check command is verifying the assistant's changes.
+    Verifying { command: String },
 }
 
 impl Status {
@@ -78,6 +88,9 @@ impl Status {
             Status::EditingFile { path } => format!("Editing {}...", path),
             Status::ExecutingCommand { command } => format!("Executing '{}'...", command),
             Status::ExecutingTool { tool_name } => format!("Calling tool '{}'", tool_name),
+            Status::RunningSubAgent { task } => format!("Sub-agent working on '{}'...", task),
+            Status::HandingOffEdits { files } => format!("Editor changing {}...", files),
+            Status::Verifying { command } => format!("Checking changes with '{}'...", command),
         }
     }
 }
@@ -1,4 +1,8 @@
 use super::models::{Message, Status};
+use crate::permissions::mode::PermissionMode;
+use crate::session::attachment::Attachment;
+use crate::session::plan::Plan;
+use crate::session::todo::TodoList;
 
 /// The state of the TUI.
 ///
@@ -8,6 +12,9 @@ use super::models::{Message, Status};
 ///
 /// - `messages`: [`Vec<Message>`] The messages to display
 /// - `current_status`: [`Option<Status>`] The current status of the TUI
+/// - `permission_mode`: [`PermissionMode`] The permission mode shown next to the input prompt
+/// - `plan`: [`Option<Plan>`] The approved plan shown below the messages while it is carried out
+/// - `todos`: [`TodoList`] The assistant's todo list shown below the messages until it is done
 ///
 /// # Examples
 ///
@@ -24,6 +31,9 @@ use super::models::{Message, Status};
 pub struct TuiState {
     pub messages: Vec<Message>,
     pub current_status: Option<Status>,
+    pub permission_mode: PermissionMode,
+    pub plan: Option<Plan>,
+    pub todos: TodoList,
 }
 
 impl TuiState {
@@ -44,6 +54,9 @@ impl TuiState {
         Self {
             messages: Vec::new(),
             current_status: None,
+            permission_mode: PermissionMode::default(),
+            plan: None,
+            todos: TodoList::default(),
         }
     }
 
@@ -106,6 +119,68 @@ impl TuiState {
         self.current_status = None;
     }
 
+    /// Add a session notice to the state.
+    ///
+    /// # Arguments
+    ///
+    /// - `content`: [`String`] The content of the notice
+    /// - `is_error`: [`bool`] Whether the notice reports an error
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use code_g::tui::state::TuiState;
+    ///
+    /// let mut state = TuiState::new();
+    /// state.add_notice("Restored 2 files".to_string(), false);
+    /// ```
+    pub fn add_notice(&mut self, content: String, is_error: bool) {
+        self.messages.push(Message::Notice { content, is_error });
+        self.current_status = None;
+    }
+
+    /// Add the attachments of a user message to the state.
+    ///
+    /// # Arguments
+    ///
+    /// - `attachments`: [`Vec<Attachment>`] The files and directories mentioned in the message
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use code_g::session::attachment::{Attachment, AttachmentKind};
+    /// use code_g::tui::models::Message;
+    /// use code_g::tui::state::TuiState;
+    ///
+    /// let mut state = TuiState::new();
+    /// state.add_attachments(vec![Attachment {
+    ///     path: "src/".to_string(),
+    ///     kind: AttachmentKind::Directory,
+    ///     truncated: true,
+    /// }]);
+    ///
+    /// assert_eq!(
+    ///     state.messages[0],
+    ///     Message::Attachments { chips: vec!["@src/ (truncated)".to_string()] }
+    /// );
+    /// ```
+    pub fn add_attachments(&mut self, attachments: Vec<Attachment>) {
+        if attachments.is_empty() {
+            return;
+        }
+        let chips = attachments
+            .iter()
+            .map(|attachment| {
+                if attachment.truncated {
+                    format!("{} (truncated)", attachment)
+                } else {
+                    attachment.to_string()
+                }
+            })
+            .collect();
+        self.messages.push(Message::Attachments { chips });
+    }
+
     /// Set the current status of the TUI.
     ///
     /// # Arguments
@@ -138,6 +213,9 @@ impl TuiState {
     pub fn clear(&mut self) {
         self.messages.clear();
         self.current_status = None;
+        self.permission_mode = PermissionMode::default();
+        self.plan = None;
+        self.todos = TodoList::default();
     }
 }
 
@@ -223,6 +301,23 @@ mod tests {
         );
     }
 
+    #[test]
+    fn add_notice_adds_notice_and_clears_status() {
+        let mut state = TuiState::new();
+        state.current_status = Some(Status::Thinking);
+
+        state.add_notice("Restored 1 file".to_string(), false);
+
+        assert_eq!(
+            state.messages,
+            vec![Message::Notice {
+                content: "Restored 1 file".to_string(),
+                is_error: false
+            }]
+        );
+        assert!(state.current_status.is_none());
+    }
+
     #[test]
     fn set_status_updates_current_status() {
         let mut state = TuiState::new();
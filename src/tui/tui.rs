@@ -1,9 +1,22 @@
 use super::formatter::{terminal::TerminalFormatter, text::TextFormatter};
 use super::models::{Message, Status};
 use super::state::TuiState;
+use crate::permissions::grant::{ApprovalResponse, GrantKind, GrantScope};
+use crate::permissions::mode::PermissionMode;
+use crate::session::editor::HAND_OFF_EDITS_TOOL;
 use crate::session::event::{Action, Event, EventHandler};
+use crate::session::input_queue::InputQueue;
+use crate::session::plan::Plan;
+use crate::session::sub_agent::TASK_TOOL;
+use crate::session::todo::{TODO_READ_TOOL, TODO_WRITE_TOOL, TodoList};
 use crate::tools::registry::Registry;
+use std::collections::HashMap;
 use std::io::{self, BufRead, Write};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+
+// Number of output lines shown for a failed check
+const VERIFICATION_OUTPUT_LINES: usize = 10;
 
 /// Terminal User Interface for the chat application.
 ///
@@ -20,7 +33,10 @@ use std::io::{self, BufRead, Write};
 /// let mut tui = Tui::new();
 ///
 /// tui.handle_event(Event::SessionStarted);
-/// tui.handle_event(Event::ReceivedUserMessage { message: "Hello, how are you?".to_string() });
+/// tui.handle_event(Event::ReceivedUserMessage {
+///     message: "Hello, how are you?".to_string(),
+///     attachments: vec![],
+/// });
 /// tui.handle_event(Event::AwaitingAssistantResponse);
 /// tui.handle_event(Event::ReceivedAssistantMessage { message: "I'm doing well, thank you!".to_string() });
 /// tui.handle_event(Event::SessionEnded);
@@ -34,6 +50,15 @@ pub struct Tui {
     state: TuiState,
     writer: Box<dyn Write>,
     reader: Box<dyn BufRead>,
+    type_ahead: Option<TypeAhead>,
+}
+
+/// Lines read from stdin in the background, so the user can type during a turn.
+struct TypeAhead {
+    /// Lines in the order they were typed, without their line endings
+    lines: Receiver<String>,
+    /// Queue the lines typed during a turn are handed to the session through
+    queue: InputQueue,
 }
 
 impl EventHandler for Tui {
@@ -41,13 +66,26 @@ impl EventHandler for Tui {
     ///
     /// This method processes chat session events and updates the terminal display accordingly:
     /// - `SessionStarted/Ended`: Clears the terminal and resets state
-    /// - `ReceivedUserMessage/AssistantMessage`: Adds messages to chat history and re-renders
-    /// - `ReceivedToolCall`: Updates status display to show tool execution in progress
+    /// - `ReceivedUserMessage/AssistantMessage`: Adds messages, and chips for attached files, to chat history and re-renders
+    /// - `ReceivedToolCall`: Updates status display to show tool execution or a sub-agent in progress
     /// - `ReceivedToolResponse`: Adds tool response to chat history and clears status
     /// - `AwaitingAssistantResponse`: Shows "thinking" status indicator
+    /// - `Error`: Adds an error notice
+    /// - `CheckpointsListed/Restored/Failed`: Adds a notice describing the checkpoint command result
+    /// - `BudgetWarning/Exceeded`: Adds a notice about the turn budget
+    /// - `LoopDetected/Aborted`: Adds a notice about repeated tool calls
+    /// - `ToolCallBlocked/HookFailed`: Adds a notice about the hook
+    /// - `ToolCallDenied`: Adds a notice with the permission rule that denied the call
+    /// - `PermissionGranted`: Adds a notice with the rule added by an "always allow" approval
+    /// - `PermissionModeChanged`: Shows the new mode next to the input prompt and adds a notice
+    /// - `TranscriptExported`: Adds a notice with the path of the transcript file
+    /// - `PlanUpdated/Rejected`: Shows the plan's progress below the messages, or a notice when rejected
+    /// - `TodosUpdated`: Shows the todo list as a checklist below the messages until every item is done
     ///
-    /// After processing each event, the entire terminal is cleared and re-rendered to ensure
-    /// a consistent display state.
+    /// Before processing an event, lines the user typed since the last prompt are
+    /// queued for the session and shown as queued notices. After processing each
+    /// event, the entire terminal is cleared and re-rendered to ensure a consistent
+    /// display state.
     ///
     /// # Arguments
     ///
@@ -64,6 +102,7 @@ impl EventHandler for Tui {
     /// tui.handle_event(Event::SessionStarted);
     /// ```
     fn handle_event(&mut self, event: Event) {
+        self.queue_typed_lines();
         match event {
             Event::SessionStarted => {
                 self.state.clear();
@@ -72,8 +111,15 @@ impl EventHandler for Tui {
             Event::SessionEnded => {
                 self.clear_terminal().unwrap();
             }
-            Event::ReceivedUserMessage { message } => {
+            Event::Error { message } => {
+                self.state.add_notice(format!("Error: {}", message), true);
+            }
+            Event::ReceivedUserMessage {
+                message,
+                attachments,
+            } => {
                 self.state.add_user_message(message);
+                self.state.add_attachments(attachments);
             }
             Event::ReceivedAssistantMessage { message } => {
                 self.state.add_assistant_message(message);
@@ -85,6 +131,16 @@ impl EventHandler for Tui {
                 if let Some(tool) = Registry::get_from_all_tools(&tool_name) {
                     let status = tool.status(&parameters);
                     self.state.set_status(Some(status));
+                } else if let (TASK_TOOL, Some(task)) = (tool_name.as_str(), parameters.get("task"))
+                {
+                    self.state
+                        .set_status(Some(Status::RunningSubAgent { task: task.clone() }));
+                } else if let (HAND_OFF_EDITS_TOOL, Some(files)) =
+                    (tool_name.as_str(), parameters.get("files"))
+                {
+                    self.state.set_status(Some(Status::HandingOffEdits {
+                        files: files.clone(),
+                    }));
                 } else {
                     self.state
                         .set_status(Some(Status::ExecutingTool { tool_name }));
@@ -103,6 +159,14 @@ impl EventHandler for Tui {
 
                     let summary = if let Some(tool) = tool {
                         tool.summary_message(&parameters, &response)
+                    } else if tool_name == TASK_TOOL {
+                        "Sub-agent finished its task".to_string()
+                    } else if tool_name == HAND_OFF_EDITS_TOOL {
+                        "Editor applied the changes".to_string()
+                    } else if tool_name == TODO_WRITE_TOOL && !is_error {
+                        "Updated the todo list".to_string()
+                    } else if tool_name == TODO_READ_TOOL {
+                        "Read the todo list".to_string()
                     } else {
                         format!("Tool '{}' executed successfully", tool_name)
                     };
@@ -120,6 +184,145 @@ impl EventHandler for Tui {
             Event::AwaitingAssistantResponse => {
                 self.state.set_status(Some(Status::Thinking));
             }
+            Event::CheckpointsListed { checkpoints } => {
+                let content = if checkpoints.is_empty() {
+                    "No checkpoints yet".to_string()
+                } else {
+                    let lines: Vec<String> = checkpoints
+                        .iter()
+                        .map(|checkpoint| {
+                            format!(
+                                "  [{}] \"{}\": {}",
+                                checkpoint.id,
+                                checkpoint.prompt,
+                                checkpoint.paths.join(", ")
+                            )
+                        })
+                        .collect();
+                    format!("Checkpoints:\n{}", lines.join("\n"))
+                };
+                self.state.add_notice(content, false);
+            }
+            Event::CheckpointRestored { checkpoint } => {
+                self.state.add_notice(
+                    format!(
+                        "Restored {} file(s) from checkpoint {}: {}",
+                        checkpoint.paths.len(),
+                        checkpoint.id,
+                        checkpoint.paths.join(", ")
+                    ),
+                    false,
+                );
+            }
+            Event::CheckpointFailed { message } => {
+                self.state.add_notice(message, true);
+            }
+            Event::BudgetWarning { limit } => {
+                self.state
+                    .add_notice(format!("Turn budget almost used up: {}", limit), false);
+            }
+            Event::BudgetExceeded { limit } => {
+                self.state.add_notice(
+                    format!("Turn budget exhausted: {}. Asking for a summary", limit),
+                    true,
+                );
+            }
+            Event::ToolCallBlocked { tool_name, reason } => {
+                self.state.add_notice(
+                    format!("Hook blocked tool '{}': {}", tool_name, reason),
+                    false,
+                );
+            }
+            Event::ToolCallDenied { tool_name, reason } => {
+                self.state.add_notice(
+                    format!("Permission denied for tool '{}': {}", tool_name, reason),
+                    true,
+                );
+            }
+            Event::PermissionGranted { rule, scope } => {
+                self.state
+                    .add_notice(format!("Added rule for this {}: {}", scope, rule), false);
+            }
+            Event::PermissionModeChanged { mode } => {
+                self.state.permission_mode = mode;
+                self.state.add_notice(
+                    format!("Permission mode: {} (switch with /mode)", mode),
+                    false,
+                );
+            }
+            Event::TodosUpdated { todos } => {
+                if !todos.is_empty() && todos.is_complete() {
+                    self.state.todos = TodoList::default();
+                    self.state
+                        .add_notice(format!("All todos done:\n{}", todos), false);
+                } else {
+                    self.state.todos = todos;
+                }
+            }
+            Event::PlanUpdated { plan } => {
+                if plan.is_complete() {
+                    self.state.plan = None;
+                    self.state
+                        .add_notice(format!("Plan complete:\n{}", plan), false);
+                } else {
+                    self.state.plan = Some(plan);
+                }
+            }
+            Event::TranscriptExported { path } => {
+                self.state
+                    .add_notice(format!("Transcript saved to {}", path.display()), false);
+            }
+            Event::PlanRejected => {
+                self.state.add_notice(
+                    "Plan rejected. Tell the assistant what to change.".to_string(),
+                    false,
+                );
+            }
+            Event::HookFailed { message } => {
+                self.state.add_notice(message, true);
+            }
+            Event::LoopDetected { pattern } => {
+                self.state.add_notice(
+                    format!(
+                        "Loop detected: {}. Asking for a different approach",
+                        pattern
+                    ),
+                    false,
+                );
+            }
+            Event::LoopAborted { pattern } => {
+                self.state
+                    .add_notice(format!("Turn stopped, loop persisted: {}", pattern), true);
+            }
+            Event::VerificationStarted { command } => {
+                self.state.set_status(Some(Status::Verifying { command }));
+            }
+            Event::VerificationFinished {
+                command,
+                passed,
+                output,
+                retrying,
+            } => {
+                self.state.set_status(None);
+                if passed {
+                    self.state
+                        .add_notice(format!("Check passed: {}", command), false);
+                } else {
+                    // The last lines of a check usually say what failed
+                    let lines: Vec<&str> = output.trim_end().lines().collect();
+                    let tail =
+                        lines[lines.len().saturating_sub(VERIFICATION_OUTPUT_LINES)..].join("\n");
+                    let outcome = if retrying {
+                        "asking the assistant to fix it"
+                    } else {
+                        "no retries left"
+                    };
+                    self.state.add_notice(
+                        format!("Check failed, {}: {}\n{}", outcome, command, tail),
+                        true,
+                    );
+                }
+            }
         }
         self.render().unwrap();
     }
@@ -139,8 +342,8 @@ impl EventHandler for Tui {
     /// - `String` The user input
     ///
     /// # Errors
-    /// Returns `io::Error` if reading from stdin fails or if terminal cursor
-    /// operations cannot be performed.
+    /// Returns `io::Error` if reading from stdin fails, stdin was closed, or if
+    /// terminal cursor operations cannot be performed.
     ///
     /// # Examples
     ///
@@ -159,7 +362,10 @@ impl EventHandler for Tui {
             Action::RequestUserApproval {
                 approval_message,
                 tool_name: _,
-            } => self.request_user_approval(&approval_message),
+                parameters,
+                reason,
+            } => self.request_user_approval(&approval_message, &parameters, &reason),
+            Action::RequestPlanApproval { plan } => self.request_plan_approval(&plan),
         }
     }
 }
@@ -183,6 +389,75 @@ impl Tui {
             state: TuiState::new(),
             writer: Box::new(io::stdout()),
             reader: Box::new(io::stdin().lock()),
+            type_ahead: None,
+        }
+    }
+
+    /// Lets the user type messages while the assistant is working.
+    ///
+    /// Stdin is read on a background thread. Lines typed while a turn is
+    /// running are pushed to the queue, which should be shared with the
+    /// session, and shown as queued until the session picks them up. Lines
+    /// typed before an approval prompt is shown are queued as well, so they
+    /// are never taken as the answer.
+    ///
+    /// # Arguments
+    ///
+    /// - `queue`: [`InputQueue`] The queue shared with the session
+    ///
+    /// # Returns
+    ///
+    /// - `Tui` The TUI reading input in the background
+    pub fn with_input_queue(mut self, queue: InputQueue) -> Self {
+        let (sender, lines) = mpsc::channel();
+        thread::spawn(move || {
+            for line in io::stdin().lock().lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        // All input now arrives through the background thread
+        self.reader = Box::new(io::empty());
+        self.type_ahead = Some(TypeAhead { lines, queue });
+        self
+    }
+
+    /// Moves the lines typed since the last prompt to the input queue.
+    fn queue_typed_lines(&mut self) {
+        let Some(type_ahead) = &self.type_ahead else {
+            return;
+        };
+        let typed: Vec<String> = type_ahead
+            .lines
+            .try_iter()
+            .map(|line| line.trim().to_string())
+            .filter(|line| !line.is_empty())
+            .collect();
+        for line in typed {
+            type_ahead.queue.push(line.clone());
+            self.state.add_notice(format!("Queued: {}", line), false);
+        }
+    }
+
+    /// Reads a line typed by the user, returning the number of bytes read.
+    ///
+    /// Zero bytes are read once the input stream was closed.
+    fn read_line(&mut self, input: &mut String) -> Result<usize, io::Error> {
+        match &self.type_ahead {
+            Some(type_ahead) => match type_ahead.lines.recv() {
+                Ok(line) => {
+                    input.push_str(&line);
+                    input.push('\n');
+                    Ok(line.len() + 1)
+                }
+                Err(_) => Ok(0),
+            },
+            None => self.reader.read_line(input),
         }
     }
 
@@ -195,6 +470,20 @@ impl Tui {
             self.render_message(message)?;
         }
 
+        // Render the plan being carried out if any
+        if let Some(plan) = &self.state.plan {
+            writeln!(self.writer, "{}", TextFormatter::bold_text("Plan"))?;
+            writeln!(self.writer, "{}", plan)?;
+            writeln!(self.writer)?;
+        }
+
+        // Render the assistant's todo list if any
+        if !self.state.todos.is_empty() {
+            writeln!(self.writer, "{}", TextFormatter::bold_text("Todos"))?;
+            writeln!(self.writer, "{}", self.state.todos)?;
+            writeln!(self.writer)?;
+        }
+
         // Render current status if any
         if let Some(status) = &self.state.current_status {
             writeln!(
@@ -213,12 +502,20 @@ impl Tui {
         // Save current cursor position and move to bottom to show prompt
         print!("{}", TerminalFormatter::save_cursor());
         print!("{}", TerminalFormatter::move_to_bottom());
-        print!("> ");
+        match self.state.permission_mode {
+            PermissionMode::Default => print!("> "),
+            mode => print!("{} > ", TextFormatter::gray_italic(&format!("[{}]", mode))),
+        }
         io::stdout().flush()?;
 
-        // Capture the user's input
+        // Capture the user's input, a closed input stream ends the session
         let mut input = String::new();
-        self.reader.read_line(&mut input)?;
+        if self.read_line(&mut input)? == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "input stream was closed",
+            ));
+        }
 
         // Clear the prompt line and restore cursor position
         print!("{}", TerminalFormatter::move_to_bottom_and_clear());
@@ -228,31 +525,131 @@ impl Tui {
         Ok(input.trim().to_string())
     }
 
-    fn request_user_approval(&mut self, approval_message: &str) -> Result<String, io::Error> {
+    fn request_user_approval(
+        &mut self,
+        approval_message: &str,
+        parameters: &HashMap<String, String>,
+        reason: &str,
+    ) -> Result<String, io::Error> {
+        // Lines typed before the prompt was shown are messages, not the answer
+        self.queue_typed_lines();
+
         // Move to bottom and then save current cursor position to show approval prompt
         print!("{}", TerminalFormatter::move_to_bottom());
         print!("{}", TerminalFormatter::save_cursor());
 
         // Display approval prompt as provided (already colorized if applicable)
         writeln!(self.writer, "{}", approval_message)?;
-        print!("[A]pprove / [D]ecline: ");
+        writeln!(
+            self.writer,
+            "{}",
+            TextFormatter::gray_italic(&format!("Asking because {}", reason))
+        )?;
+
+        // Only offer "always allow" options that apply to this call
+        let grants = GrantKind::available(parameters);
+        let mut options = vec!["[A]pprove", "[D]ecline", "Always allow [T]ool"];
+        if grants.contains(&GrantKind::CommandPrefix) {
+            options.push("Always allow [C]ommand prefix");
+        }
+        if grants.contains(&GrantKind::Path) {
+            options.push("Always allow [P]ath");
+        }
+        print!("{}: ", options.join(" / "));
         io::stdout().flush()?;
 
         // Capture the user's response
-        let mut input = String::new();
-        self.reader.read_line(&mut input)?;
-        let response = input.trim().to_lowercase();
+        let response = self.read_approval_input()?;
+        let response = match response.as_str() {
+            "a" | "approve" | "y" | "yes" => ApprovalResponse::Approved,
+            "t" | "tool" => self.request_grant_scope(GrantKind::Tool)?,
+            "c" | "command" if grants.contains(&GrantKind::CommandPrefix) => {
+                self.request_grant_scope(GrantKind::CommandPrefix)?
+            }
+            "p" | "path" if grants.contains(&GrantKind::Path) => {
+                self.request_grant_scope(GrantKind::Path)?
+            }
+            "d" | "decline" | "n" | "no" => {
+                print!("Reason for declining (optional): ");
+                io::stdout().flush()?;
+                let reason = self.read_line_trimmed()?;
+                ApprovalResponse::Declined {
+                    reason: (!reason.is_empty()).then_some(reason),
+                }
+            }
+            _ => ApprovalResponse::Declined { reason: None },
+        };
 
         // Clear the approval prompt and restore cursor position
         print!("{}", TerminalFormatter::move_to_bottom_and_clear());
         print!("{}", TerminalFormatter::restore_cursor());
         io::stdout().flush()?;
 
-        // Return "approved" or "declined" based on user input
-        match response.as_str() {
-            "a" | "approve" | "y" | "yes" => Ok("approved".to_string()),
-            _ => Ok("declined".to_string()),
-        }
+        Ok(response.to_string())
+    }
+
+    fn request_plan_approval(&mut self, plan: &Plan) -> Result<String, io::Error> {
+        // Lines typed before the prompt was shown are messages, not the answer
+        self.queue_typed_lines();
+
+        // Move to bottom and then save current cursor position to show approval prompt
+        print!("{}", TerminalFormatter::move_to_bottom());
+        print!("{}", TerminalFormatter::save_cursor());
+
+        writeln!(self.writer, "{}", TextFormatter::bold_text("Proposed plan"))?;
+        writeln!(self.writer, "{}", plan)?;
+        print!("[A]pprove / [E]dit / [R]eject: ");
+        io::stdout().flush()?;
+
+        let response = match self.read_approval_input()?.as_str() {
+            "a" | "approve" | "y" | "yes" => "approved".to_string(),
+            "e" | "edit" => {
+                writeln!(
+                    self.writer,
+                    "Enter the revised steps, one per line. Finish with an empty line:"
+                )?;
+                self.writer.flush()?;
+
+                let mut steps = Vec::new();
+                loop {
+                    let step = self.read_line_trimmed()?;
+                    if step.is_empty() {
+                        break;
+                    }
+                    steps.push(format!("{}. {}", steps.len() + 1, step));
+                }
+                format!("edited:{}", steps.join("\n"))
+            }
+            _ => "declined".to_string(),
+        };
+
+        // Clear the approval prompt and restore cursor position
+        print!("{}", TerminalFormatter::move_to_bottom_and_clear());
+        print!("{}", TerminalFormatter::restore_cursor());
+        io::stdout().flush()?;
+
+        Ok(response)
+    }
+
+    fn request_grant_scope(&mut self, kind: GrantKind) -> Result<ApprovalResponse, io::Error> {
+        print!("Remember for this [S]ession or the [P]roject: ");
+        io::stdout().flush()?;
+
+        let scope = match self.read_approval_input()?.as_str() {
+            "p" | "project" => GrantScope::Project,
+            _ => GrantScope::Session,
+        };
+        Ok(ApprovalResponse::Always { kind, scope })
+    }
+
+    fn read_approval_input(&mut self) -> Result<String, io::Error> {
+        Ok(self.read_line_trimmed()?.to_lowercase())
+    }
+
+    fn read_line_trimmed(&mut self) -> Result<String, io::Error> {
+        let mut input = String::new();
+        self.read_line(&mut input)?;
+        Ok(input.trim().to_string())
     }
 
     fn render_message(&mut self, message: &Message) -> Result<(), io::Error> {
@@ -277,6 +674,24 @@ impl Tui {
                 }
                 writeln!(self.writer)?;
             }
+            Message::Notice { content, is_error } => {
+                if *is_error {
+                    writeln!(self.writer, "{}", TextFormatter::red_italic(content))?;
+                } else {
+                    writeln!(self.writer, "{}", TextFormatter::gray_italic(content))?;
+                }
+                writeln!(self.writer)?;
+            }
+            Message::Attachments { chips } => {
+                let chips: Vec<String> = chips
+                    .iter()
+                    .map(|chip| {
+                        TextFormatter::colored_text(&format!("[{}]", chip), TextFormatter::cyan())
+                    })
+                    .collect();
+                writeln!(self.writer, "  {}", chips.join(" "))?;
+                writeln!(self.writer)?;
+            }
         }
         Ok(())
     }
@@ -290,6 +705,9 @@ impl Tui {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::session::attachment::{Attachment, AttachmentKind};
+    use crate::session::budget::BudgetLimit;
+    use crate::session::checkpoint::CheckpointSummary;
     use crate::session::event::Event;
     use std::collections::HashMap;
     use std::io::Cursor;
@@ -299,6 +717,7 @@ mod tests {
             state: TuiState::new(),
             writer,
             reader,
+            type_ahead: None,
         }
     }
 
@@ -343,6 +762,7 @@ mod tests {
         let message = "Hello, how are you?".to_string();
         tui.handle_event(Event::ReceivedUserMessage {
             message: message.clone(),
+            attachments: vec![],
         });
 
         assert_eq!(tui.state.messages.len(), 1);
@@ -414,6 +834,77 @@ mod tests {
         }
     }
 
+    #[test]
+    fn handle_event_checkpoint_restored_adds_notice() {
+        let mut tui = Tui::new();
+
+        tui.handle_event(Event::CheckpointRestored {
+            checkpoint: CheckpointSummary {
+                id: 2,
+                prompt: "Fix the bug".to_string(),
+                paths: vec!["src/lib.rs".to_string()],
+            },
+        });
+
+        assert_eq!(
+            tui.state.messages,
+            vec![Message::Notice {
+                content: "Restored 1 file(s) from checkpoint 2: src/lib.rs".to_string(),
+                is_error: false,
+            }]
+        );
+    }
+
+    #[test]
+    fn handle_event_budget_warning_adds_notice() {
+        let mut tui = Tui::new();
+
+        tui.handle_event(Event::BudgetWarning {
+            limit: BudgetLimit::Iterations { used: 40, max: 50 },
+        });
+
+        assert_eq!(
+            tui.state.messages,
+            vec![Message::Notice {
+                content: "Turn budget almost used up: iterations (40/50)".to_string(),
+                is_error: false,
+            }]
+        );
+    }
+
+    #[test]
+    fn handle_event_failed_verification_shows_last_output_lines() {
+        let mut tui = Tui::new();
+        let output: Vec<String> = (1..=12).map(|i| format!("line {}", i)).collect();
+
+        tui.handle_event(Event::VerificationStarted {
+            command: "cargo test".to_string(),
+        });
+        assert!(matches!(
+            tui.state.current_status,
+            Some(Status::Verifying { .. })
+        ));
+
+        tui.handle_event(Event::VerificationFinished {
+            command: "cargo test".to_string(),
+            passed: false,
+            output: output.join("\n"),
+            retrying: true,
+        });
+
+        assert!(tui.state.current_status.is_none());
+        assert_eq!(
+            tui.state.messages,
+            vec![Message::Notice {
+                content: format!(
+                    "Check failed, asking the assistant to fix it: cargo test\n{}",
+                    output[2..].join("\n")
+                ),
+                is_error: true,
+            }]
+        );
+    }
+
     #[test]
     fn handle_event_awaiting_assistant_response_sets_thinking_status() {
         let mut tui = Tui::new();
@@ -441,6 +932,210 @@ mod tests {
         assert_eq!(result.unwrap(), "test input");
     }
 
+    #[test]
+    fn handle_action_request_user_input_returns_error_at_end_of_input() {
+        let mut tui = tui_with_writer_and_reader(
+            Box::new(Cursor::new(Vec::new())),
+            Box::new(Cursor::new(Vec::new())),
+        );
+
+        let result = tui.read_user_input();
+
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn handle_event_queues_lines_typed_during_a_turn() {
+        let (sender, lines) = mpsc::channel();
+        let queue = InputQueue::new();
+        let mut tui = tui_with_writer_and_reader(
+            Box::new(Cursor::new(Vec::new())),
+            Box::new(Cursor::new(Vec::new())),
+        );
+        tui.type_ahead = Some(TypeAhead {
+            lines,
+            queue: queue.clone(),
+        });
+
+        sender.send("Use the new API".to_string()).unwrap();
+        sender.send("  ".to_string()).unwrap();
+        tui.handle_event(Event::AwaitingAssistantResponse);
+        sender.send("Hello".to_string()).unwrap();
+        let input = tui.read_user_input();
+
+        assert_eq!(queue.pop(), Some("Use the new API".to_string()));
+        assert!(queue.is_empty());
+        assert_eq!(
+            tui.state.messages,
+            vec![Message::Notice {
+                content: "Queued: Use the new API".to_string(),
+                is_error: false,
+            }]
+        );
+        assert_eq!(input.unwrap(), "Hello");
+
+        drop(sender);
+        assert_eq!(
+            tui.read_user_input().unwrap_err().kind(),
+            io::ErrorKind::UnexpectedEof
+        );
+    }
+
+    #[test]
+    fn request_user_approval_returns_always_allow_grant() {
+        let mut tui = tui_with_writer_and_reader(
+            Box::new(Cursor::new(Vec::new())),
+            Box::new(Cursor::new("c\np\n".as_bytes())),
+        );
+
+        let response = tui.request_user_approval(
+            "CodeG wants to execute command 'cargo test'",
+            &HashMap::from([("command".to_string(), "cargo test".to_string())]),
+            "execute_command requires approval by default",
+        );
+
+        assert_eq!(response.unwrap(), "always:command_prefix:project");
+    }
+
+    #[test]
+    fn handle_event_permission_mode_changed_updates_mode() {
+        let mut tui = tui_with_writer_and_reader(
+            Box::new(Cursor::new(Vec::new())),
+            Box::new(Cursor::new(Vec::new())),
+        );
+
+        tui.handle_event(Event::PermissionModeChanged {
+            mode: PermissionMode::Plan,
+        });
+
+        assert_eq!(tui.state.permission_mode, PermissionMode::Plan);
+        assert_eq!(
+            tui.state.messages,
+            vec![Message::Notice {
+                content: "Permission mode: plan (switch with /mode)".to_string(),
+                is_error: false,
+            }]
+        );
+    }
+
+    #[test]
+    fn request_plan_approval_returns_edited_steps() {
+        let mut tui = tui_with_writer_and_reader(
+            Box::new(Cursor::new(Vec::new())),
+            Box::new(Cursor::new(
+                "e\nAdd the module\nSkip the docs\n\n".as_bytes(),
+            )),
+        );
+
+        let response = tui.request_plan_approval(&Plan::parse("1. Add the module").unwrap());
+
+        assert_eq!(
+            response.unwrap(),
+            "edited:1. Add the module\n2. Skip the docs"
+        );
+    }
+
+    #[test]
+    fn handle_event_plan_updated_tracks_plan_until_complete() {
+        let mut tui = tui_with_writer_and_reader(
+            Box::new(Cursor::new(Vec::new())),
+            Box::new(Cursor::new(Vec::new())),
+        );
+        let mut plan = Plan::parse("1. Add the module").unwrap();
+
+        tui.handle_event(Event::PlanUpdated { plan: plan.clone() });
+        assert_eq!(tui.state.plan, Some(plan.clone()));
+
+        plan.complete_step(1).unwrap();
+        tui.handle_event(Event::PlanUpdated { plan });
+        assert_eq!(tui.state.plan, None);
+    }
+
+    #[test]
+    fn handle_event_todos_updated_shows_checklist_until_done() {
+        let mut tui = tui_with_writer_and_reader(
+            Box::new(Cursor::new(Vec::new())),
+            Box::new(Cursor::new(Vec::new())),
+        );
+        let todos = TodoList::parse(
+            r#"[{"content": "Add the module", "status": "in_progress"}, {"content": "Test it", "status": "pending"}]"#,
+        )
+        .unwrap();
+
+        tui.handle_event(Event::TodosUpdated {
+            todos: todos.clone(),
+        });
+        assert_eq!(tui.state.todos, todos);
+
+        let done = TodoList::parse(r#"[{"content": "Add the module", "status": "done"}]"#).unwrap();
+        tui.handle_event(Event::TodosUpdated { todos: done });
+        assert!(tui.state.todos.is_empty());
+        assert_eq!(
+            tui.state.messages,
+            vec![Message::Notice {
+                content: "All todos done:\n[x] Add the module".to_string(),
+                is_error: false,
+            }]
+        );
+    }
+
+    #[test]
+    fn request_user_approval_queues_lines_typed_before_the_prompt() {
+        let (sender, lines) = mpsc::channel();
+        let queue = InputQueue::new();
+        let mut tui = tui_with_writer_and_reader(
+            Box::new(Cursor::new(Vec::new())),
+            Box::new(Cursor::new(Vec::new())),
+        );
+        tui.type_ahead = Some(TypeAhead {
+            lines,
+            queue: queue.clone(),
+        });
+
+        sender.send("yes".to_string()).unwrap();
+        drop(sender);
+        let response = tui.request_user_approval(
+            "CodeG wants to execute command 'rm -rf target'",
+            &HashMap::from([("command".to_string(), "rm -rf target".to_string())]),
+            "execute_command requires approval by default",
+        );
+
+        assert_eq!(response.unwrap(), "declined");
+        assert_eq!(queue.pop(), Some("yes".to_string()));
+    }
+
+    #[test]
+    fn request_user_approval_returns_decline_reason() {
+        let mut tui = tui_with_writer_and_reader(
+            Box::new(Cursor::new(Vec::new())),
+            Box::new(Cursor::new("d\nRun the unit tests only\n".as_bytes())),
+        );
+
+        let response = tui.request_user_approval(
+            "CodeG wants to execute command 'cargo test'",
+            &HashMap::from([("command".to_string(), "cargo test".to_string())]),
+            "execute_command requires approval by default",
+        );
+
+        assert_eq!(response.unwrap(), "declined:Run the unit tests only");
+    }
+
+    #[test]
+    fn request_user_approval_declines_unavailable_grants() {
+        let mut tui = tui_with_writer_and_reader(
+            Box::new(Cursor::new(Vec::new())),
+            Box::new(Cursor::new("p\n".as_bytes())),
+        );
+
+        let response = tui.request_user_approval(
+            "CodeG wants to execute command 'ls'",
+            &HashMap::from([("command".to_string(), "ls".to_string())]),
+            "execute_command requires approval by default",
+        );
+
+        assert_eq!(response.unwrap(), "declined");
+    }
+
     #[test]
     fn render_message_formats_user_message_correctly() {
         let mut tui = Tui::new();
@@ -523,6 +1218,27 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn handle_event_received_user_message_adds_attachment_chips() {
+        let mut tui = Tui::new();
+
+        tui.handle_event(Event::ReceivedUserMessage {
+            message: "Explain @src/main.rs:1-20".to_string(),
+            attachments: vec![Attachment {
+                path: "src/main.rs".to_string(),
+                kind: AttachmentKind::Lines { start: 1, end: 20 },
+                truncated: false,
+            }],
+        });
+
+        assert_eq!(
+            tui.state.messages[1],
+            Message::Attachments {
+                chips: vec!["@src/main.rs:1-20".to_string()],
+            }
+        );
+    }
+
     #[test]
     fn handle_event_handles_multiple_events_in_sequence() {
         let mut tui = Tui::new();
@@ -531,6 +1247,7 @@ mod tests {
         tui.handle_event(Event::SessionStarted);
         tui.handle_event(Event::ReceivedUserMessage {
             message: "Hello".to_string(),
+            attachments: vec![],
         });
         tui.handle_event(Event::AwaitingAssistantResponse);
         tui.handle_event(Event::ReceivedAssistantMessage {
@@ -551,6 +1268,7 @@ mod tests {
 
         tui.handle_event(Event::ReceivedUserMessage {
             message: "test".to_string(),
+            attachments: vec![],
         });
         assert!(tui.state.current_status.is_none());
     }
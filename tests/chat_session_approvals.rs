@@ -42,6 +42,7 @@ async fn chat_session_handles_tool_call_with_approval() {
             Event::SessionStarted,
             Event::ReceivedUserMessage {
                 message: "Execute a command in my terminal: echo 'Hello, world!'".to_string(),
+                attachments: vec![],
             },
             Event::AwaitingAssistantResponse,
             Event::ReceivedToolCall {
@@ -139,6 +140,7 @@ async fn chat_session_handles_tool_call_with_approval_and_denied() {
             Event::SessionStarted,
             Event::ReceivedUserMessage {
                 message: "Execute a command in my terminal: echo 'Hello, world!'".to_string(),
+                attachments: vec![],
             },
             Event::AwaitingAssistantResponse,
             Event::ReceivedToolCall {
@@ -231,6 +233,7 @@ async fn chat_session_handles_tool_call_with_approval_and_invalid_approval() {
             Event::SessionStarted,
             Event::ReceivedUserMessage {
                 message: "Execute a command in my terminal: echo 'Hello, world!'".to_string(),
+                attachments: vec![],
             },
             Event::AwaitingAssistantResponse,
             Event::ReceivedToolCall {
@@ -287,3 +290,60 @@ async fn chat_session_handles_tool_call_with_approval_and_invalid_approval() {
         ],
     );
 }
+
+#[tokio::test]
+async fn chat_session_passes_decline_reason_to_the_assistant() {
+    let cancelled = "Operation cancelled by user: execute_command with parameters {\"command\": \"cargo test\"}\nReason given by the user: Only run the unit tests";
+    let scenario = ScenarioBuilder::new()
+        .inputs(["Run the tests"])
+        .approvals(["declined:Only run the unit tests", "approved"])
+        .add_mock_tool(
+            "execute_command",
+            "Execute a command in the terminal",
+            Parameters {
+                param_type: "object".to_string(),
+                properties: HashMap::new(),
+                required: vec!["command".to_string()],
+                additional_properties: false,
+            },
+            true,
+            true,
+            "AI wants to execute a command in the terminal. Do you approve?",
+            "Execute command {} was declined by user",
+            "ok",
+        )
+        .then_tool_call(
+            "1",
+            "execute_command",
+            HashMap::from([("command".to_string(), "cargo test".to_string())]),
+        )
+        .then_tool_call(
+            "2",
+            "execute_command",
+            HashMap::from([("command".to_string(), "cargo test --lib".to_string())]),
+        )
+        .then_message("The unit tests pass", true)
+        .run()
+        .await;
+
+    assert!(scenario.events.contains(&Event::ReceivedToolResponse {
+        tool_name: "execute_command".to_string(),
+        response: cancelled.to_string(),
+        parameters: HashMap::from([("command".to_string(), "cargo test".to_string())]),
+        approved: false,
+    }));
+
+    // The assistant revised its call in the same turn after reading the reason
+    assert_tool_calls(
+        &scenario.tool_calls,
+        &[(
+            "execute_command".to_string(),
+            HashMap::from([("command".to_string(), "cargo test --lib".to_string())]),
+        )],
+    );
+    assert!(scenario.last_client_call().1.contains(&ChatMessage::Tool {
+        content: cancelled.to_string(),
+        tool_call_id: "1".to_string(),
+        tool_name: "execute_command".to_string(),
+    }));
+}
@@ -0,0 +1,56 @@
+mod helpers;
+
+use code_g::client::models::ChatMessage;
+use code_g::session::attachment::{Attachment, AttachmentKind};
+use code_g::session::event::Event;
+use code_g::session::system_prompt::SystemPromptConfig;
+use helpers::scenario::ScenarioBuilder;
+use std::env;
+use std::fs;
+
+#[tokio::test]
+async fn chat_session_attaches_mentioned_files_to_the_user_message() {
+    let project_dir = env::temp_dir().join(format!("code_g_attachments_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&project_dir);
+    fs::create_dir_all(project_dir.join("src")).unwrap();
+    fs::write(
+        project_dir.join("src/lib.rs"),
+        "pub mod parser;\npub mod tools;\n",
+    )
+    .unwrap();
+
+    let message = "What does @src/lib.rs:2 export? See @src/";
+    let scenario = ScenarioBuilder::new()
+        .with_system_prompt_config(SystemPromptConfig::None)
+        .with_project_dir(project_dir.clone())
+        .inputs([message])
+        .then_message("The tools module", true)
+        .run()
+        .await;
+
+    assert!(scenario.events.contains(&Event::ReceivedUserMessage {
+        message: message.to_string(),
+        attachments: vec![
+            Attachment {
+                path: "src/lib.rs".to_string(),
+                kind: AttachmentKind::Lines { start: 2, end: 2 },
+                truncated: false,
+            },
+            Attachment {
+                path: "src/".to_string(),
+                kind: AttachmentKind::Directory,
+                truncated: false,
+            },
+        ],
+    }));
+    assert_eq!(
+        scenario.last_client_call().1,
+        vec![ChatMessage::User {
+            content: format!(
+                "{}\n\n<attachment path=\"src/lib.rs\" lines=\"2-2\">\npub mod tools;\n</attachment>\n\n<attachment path=\"src/\" type=\"directory\">\nlib.rs\n</attachment>",
+                message
+            ),
+        }]
+    );
+    fs::remove_dir_all(project_dir).unwrap();
+}
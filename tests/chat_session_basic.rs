@@ -20,6 +20,7 @@ async fn chat_session_handles_message() {
             Event::SessionStarted,
             Event::ReceivedUserMessage {
                 message: "Hello".to_string(),
+                attachments: vec![],
             },
             Event::AwaitingAssistantResponse,
             Event::ReceivedAssistantMessage {
@@ -60,6 +61,7 @@ async fn chat_session_handles_multiple_messages() {
             Event::SessionStarted,
             Event::ReceivedUserMessage {
                 message: "Hello".to_string(),
+                attachments: vec![],
             },
             Event::AwaitingAssistantResponse,
             Event::ReceivedAssistantMessage {
@@ -67,6 +69,7 @@ async fn chat_session_handles_multiple_messages() {
             },
             Event::ReceivedUserMessage {
                 message: "How are you?".to_string(),
+                attachments: vec![],
             },
             Event::AwaitingAssistantResponse,
             Event::ReceivedAssistantMessage {
@@ -74,6 +77,7 @@ async fn chat_session_handles_multiple_messages() {
             },
             Event::ReceivedUserMessage {
                 message: "I'm good, thank you!".to_string(),
+                attachments: vec![],
             },
             Event::AwaitingAssistantResponse,
             Event::ReceivedAssistantMessage {
@@ -125,7 +129,7 @@ async fn chat_session_handles_multiple_assistant_messages_per_turn() {
         &scenario.events,
         &[
             Event::SessionStarted,
-            Event::ReceivedUserMessage { message: "What is 1+1? Think about it real hard".to_string() },
+            Event::ReceivedUserMessage { message: "What is 1+1? Think about it real hard".to_string(), attachments: vec![] },
             Event::AwaitingAssistantResponse,
             Event::ReceivedAssistantMessage { message: "Okay lets see. The user is asking me what 1+1 is. I need to think about it real hard".to_string() },
             Event::AwaitingAssistantResponse,
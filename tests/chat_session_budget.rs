@@ -0,0 +1,150 @@
+mod helpers;
+
+use code_g::client::models::{ChatMessage, Parameters, Usage};
+use code_g::session::budget::{BudgetLimit, TurnBudget};
+use code_g::session::event::Event;
+use code_g::session::system_prompt::SystemPromptConfig;
+use helpers::assertions::assert_events;
+use helpers::scenario::ScenarioBuilder;
+use std::collections::HashMap;
+
+fn weather_scenario() -> ScenarioBuilder {
+    ScenarioBuilder::new()
+        .with_system_prompt_config(SystemPromptConfig::None)
+        .inputs(["What is the weather in Tokyo?"])
+        .add_mock_tool(
+            "get_weather",
+            "Get the weather in a city",
+            Parameters {
+                param_type: "object".to_string(),
+                properties: HashMap::new(),
+                required: vec!["city".to_string()],
+                additional_properties: false,
+            },
+            true,
+            false,
+            "AI wants to check the weather in Tokyo. Do you approve?",
+            "Get the weather in a city was declined by user",
+            "The weather in Tokyo is sunny",
+        )
+}
+
+// Each call asks for a different day so the calls are not detected as a loop
+fn weather_args(day: usize) -> HashMap<String, String> {
+    HashMap::from([
+        ("city".to_string(), "Tokyo".to_string()),
+        ("day".to_string(), day.to_string()),
+    ])
+}
+
+fn weather_tool_events(day: usize) -> Vec<Event> {
+    vec![
+        Event::AwaitingAssistantResponse,
+        Event::ReceivedToolCall {
+            tool_name: "get_weather".to_string(),
+            parameters: weather_args(day),
+        },
+        Event::ReceivedToolResponse {
+            tool_name: "get_weather".to_string(),
+            response: "The weather in Tokyo is sunny".to_string(),
+            parameters: weather_args(day),
+            approved: true,
+        },
+    ]
+}
+
+#[tokio::test]
+async fn chat_session_warns_and_asks_for_summary_when_token_budget_is_exhausted() {
+    let scenario = weather_scenario()
+        .with_turn_budget(TurnBudget {
+            max_tokens: Some(100),
+            ..TurnBudget::default()
+        })
+        .with_usage_per_call(Usage {
+            prompt_tokens: 40,
+            completion_tokens: 5,
+            total_tokens: 45,
+        })
+        .then_tool_call("1", "get_weather", weather_args(1))
+        .then_tool_call("2", "get_weather", weather_args(2))
+        .then_tool_call("3", "get_weather", weather_args(3))
+        .then_message("I checked the weather three times, it is sunny", false)
+        .run()
+        .await;
+
+    let mut expected = vec![
+        Event::SessionStarted,
+        Event::ReceivedUserMessage {
+            message: "What is the weather in Tokyo?".to_string(),
+            attachments: vec![],
+        },
+    ];
+    expected.extend(weather_tool_events(1));
+    expected.extend(weather_tool_events(2));
+    expected.push(Event::BudgetWarning {
+        limit: BudgetLimit::Tokens { used: 90, max: 100 },
+    });
+    expected.extend(weather_tool_events(3));
+    expected.extend([
+        Event::BudgetExceeded {
+            limit: BudgetLimit::Tokens {
+                used: 135,
+                max: 100,
+            },
+        },
+        Event::AwaitingAssistantResponse,
+        Event::ReceivedAssistantMessage {
+            message: "I checked the weather three times, it is sunny".to_string(),
+        },
+        Event::SessionEnded,
+    ]);
+    assert_events(&scenario.events, &expected);
+
+    // The summary is requested without tools, after a note asking to wrap up
+    let (_, history, tools) = scenario.last_client_call();
+    assert!(tools.is_empty());
+    assert!(matches!(
+        history.last(),
+        Some(ChatMessage::System { content }) if content.contains("budget")
+    ));
+}
+
+#[tokio::test]
+async fn chat_session_stops_tool_loop_at_iteration_budget() {
+    let mut scenario = weather_scenario().with_turn_budget(TurnBudget {
+        max_iterations: 5,
+        ..TurnBudget::default()
+    });
+    for id in 0..5 {
+        scenario = scenario.then_tool_call(id.to_string(), "get_weather", weather_args(id));
+    }
+    let scenario = scenario
+        .then_message("It is sunny in Tokyo", true)
+        .run()
+        .await;
+
+    let budget_events: Vec<Event> = scenario
+        .events
+        .iter()
+        .filter(|event| {
+            matches!(
+                event,
+                Event::BudgetWarning { .. } | Event::BudgetExceeded { .. }
+            )
+        })
+        .cloned()
+        .collect();
+    assert_events(
+        &budget_events,
+        &[
+            Event::BudgetWarning {
+                limit: BudgetLimit::Iterations { used: 4, max: 5 },
+            },
+            Event::BudgetExceeded {
+                limit: BudgetLimit::Iterations { used: 5, max: 5 },
+            },
+        ],
+    );
+    assert_eq!(scenario.tool_calls.lock().unwrap().len(), 5);
+    assert_eq!(scenario.client_calls.lock().unwrap().len(), 6);
+}
@@ -0,0 +1,155 @@
+mod helpers;
+
+use code_g::client::models::{ChatMessage, ChatResult, Model, Parameters, ToolCall, Usage};
+use code_g::client::providers::openai::schema::Model as OpenAiModel;
+use code_g::permissions::config::{PermissionDecision, PermissionRule, PermissionsConfig};
+use code_g::session::builder::ChatSessionBuilder;
+use code_g::session::system_prompt::SystemPromptConfig;
+use code_g::session::turn::{ToolCallDecision, ToolCallOutcome};
+use code_g::tools::traits::Tool;
+use helpers::mocks::chat_client::MockChatClient;
+use helpers::mocks::tool_registry::{MockTool, MockToolRegistry};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+
+fn mock_tool(name: &str, requires_approval: bool) -> Box<dyn Tool> {
+    Box::new(MockTool::new(
+        name.to_string(),
+        format!("Mock {}", name),
+        Parameters {
+            param_type: "object".to_string(),
+            properties: HashMap::new(),
+            required: vec![],
+            additional_properties: false,
+        },
+        true,
+        requires_approval,
+        format!("AI wants to use {}", name),
+        format!("{} was declined by user", name),
+        format!("{} output", name),
+    ))
+}
+
+fn tool_call(id: &str, name: &str) -> ChatResult {
+    ChatResult::ToolCalls(vec![ToolCall {
+        id: id.to_string(),
+        name: name.to_string(),
+        arguments: HashMap::new(),
+    }])
+}
+
+/// Usage of the given number of completions, each reporting 8 prompt and 2 completion tokens.
+fn usage(completions: u64) -> Usage {
+    Usage {
+        prompt_tokens: completions * 8,
+        completion_tokens: completions * 2,
+        total_tokens: completions * 10,
+    }
+}
+
+#[tokio::test]
+async fn send_message_returns_the_turn_result() {
+    let calls = Arc::new(Mutex::new(vec![]));
+    let client = MockChatClient::new(
+        vec![
+            Ok(tool_call("1", "read_file")),
+            Ok(tool_call("2", "write_file")),
+            Ok(ChatResult::Message {
+                content: "Read the file, could not write it".to_string(),
+                turn_over: true,
+            }),
+            Ok(ChatResult::Message {
+                content: "Hello again".to_string(),
+                turn_over: true,
+            }),
+        ],
+        calls.clone(),
+    )
+    .with_usage(usage(1));
+    let tools = MockToolRegistry::new(
+        vec![mock_tool("read_file", false), mock_tool("write_file", true)],
+        Arc::new(Mutex::new(vec![])),
+    );
+
+    let mut session = ChatSessionBuilder::new(Box::new(client))
+        .tools(Box::new(tools))
+        .system_prompt(SystemPromptConfig::Custom("Be brief.".to_string()))
+        .model(Model::OpenAi(OpenAiModel::Gpt4o))
+        .build();
+
+    let turn = session.send_message("Update the file").await.unwrap();
+
+    // Without an event handler, calls that need approval are declined
+    assert_eq!(turn.message, "Read the file, could not write it");
+    assert_eq!(
+        turn.tool_calls[0],
+        ToolCallOutcome {
+            id: "1".to_string(),
+            tool_name: "read_file".to_string(),
+            arguments: HashMap::new(),
+            response: "read_file output".to_string(),
+            decision: ToolCallDecision::Allowed,
+        }
+    );
+    assert_eq!(turn.tool_calls[1].decision, ToolCallDecision::Declined);
+    assert_eq!(turn.executed_tool_calls().count(), 1);
+    assert_eq!(turn.usage, usage(3));
+
+    // The next turn only reports its own tool calls and usage
+    let turn = session.send_message("Hello").await.unwrap();
+    assert_eq!(turn.message, "Hello again");
+    assert!(turn.tool_calls.is_empty());
+    assert_eq!(turn.usage, usage(1));
+    assert_eq!(session.usage(), usage(4));
+
+    let calls = calls.lock().unwrap().clone();
+    assert_eq!(calls[0].0, Model::OpenAi(OpenAiModel::Gpt4o));
+    assert_eq!(
+        calls[0].1[0],
+        ChatMessage::System {
+            content: "Be brief.".to_string(),
+        }
+    );
+}
+
+#[tokio::test]
+async fn send_message_reports_tool_calls_denied_by_policy() {
+    let client = MockChatClient::new(
+        vec![
+            Ok(tool_call("1", "execute_command")),
+            Ok(ChatResult::Message {
+                content: "Not allowed to run commands".to_string(),
+                turn_over: true,
+            }),
+        ],
+        Arc::new(Mutex::new(vec![])),
+    );
+    let tools = MockToolRegistry::new(
+        vec![mock_tool("execute_command", true)],
+        Arc::new(Mutex::new(vec![])),
+    );
+    let permissions = PermissionsConfig {
+        rules: vec![PermissionRule {
+            decision: PermissionDecision::Deny,
+            tool: "execute_command".to_string(),
+            arguments: BTreeMap::new(),
+            reason: None,
+        }],
+    };
+
+    let mut session = ChatSessionBuilder::new(Box::new(client))
+        .tools(Box::new(tools))
+        .system_prompt(SystemPromptConfig::None)
+        .permissions(permissions)
+        .build();
+
+    let turn = session.send_message("Run the tests").await.unwrap();
+
+    assert_eq!(turn.tool_calls.len(), 1);
+    assert_eq!(turn.tool_calls[0].decision, ToolCallDecision::Denied);
+    assert!(
+        turn.tool_calls[0]
+            .response
+            .starts_with("Tool call denied by permission policy")
+    );
+}
@@ -0,0 +1,98 @@
+mod helpers;
+
+use code_g::session::checkpoint::CheckpointSummary;
+use code_g::session::event::Event;
+use code_g::tools::write_file::WriteFile;
+use helpers::scenario::ScenarioBuilder;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+fn create_temp_dir() -> PathBuf {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let temp_dir =
+        std::env::temp_dir().join(format!("code_g_test_{}_{}", std::process::id(), nanos));
+    fs::create_dir_all(&temp_dir).expect("Failed to create temp directory");
+    temp_dir
+}
+
+#[tokio::test]
+async fn chat_session_undo_reverts_files_written_in_last_turn() {
+    let temp_dir = create_temp_dir();
+    let existing = temp_dir.join("existing.txt");
+    let created = temp_dir.join("created.txt");
+    fs::write(&existing, "original").unwrap();
+
+    let existing_path = existing.to_string_lossy().to_string();
+    let created_path = created.to_string_lossy().to_string();
+
+    let scenario = ScenarioBuilder::new()
+        .inputs(["Update the files", "/undo"])
+        .approvals(["approved", "approved"])
+        .add_tool(Box::new(WriteFile))
+        .then_tool_call(
+            "1",
+            "write_file",
+            HashMap::from([
+                ("path".to_string(), existing_path.clone()),
+                ("content".to_string(), "changed".to_string()),
+            ]),
+        )
+        .then_tool_call(
+            "2",
+            "write_file",
+            HashMap::from([
+                ("path".to_string(), created_path.clone()),
+                ("content".to_string(), "new".to_string()),
+            ]),
+        )
+        .then_message("Updated both files", true)
+        .run()
+        .await;
+
+    assert_eq!(fs::read_to_string(&existing).unwrap(), "original");
+    assert!(!created.exists());
+
+    assert_eq!(
+        &scenario.events[scenario.events.len() - 2..],
+        &[
+            Event::CheckpointRestored {
+                checkpoint: CheckpointSummary {
+                    id: 1,
+                    prompt: "Update the files".to_string(),
+                    paths: vec![existing_path, created_path],
+                },
+            },
+            Event::SessionEnded,
+        ]
+    );
+
+    fs::remove_dir_all(temp_dir).ok();
+}
+
+#[tokio::test]
+async fn chat_session_lists_checkpoints_without_calling_the_client() {
+    let scenario = ScenarioBuilder::new()
+        .inputs(["/checkpoints", "/undo"])
+        .run()
+        .await;
+
+    assert_eq!(
+        scenario.events,
+        vec![
+            Event::SessionStarted,
+            Event::CheckpointsListed {
+                checkpoints: vec![],
+            },
+            Event::CheckpointFailed {
+                message: "No file changes to undo".to_string(),
+            },
+            Event::SessionEnded,
+        ]
+    );
+    assert!(scenario.client_calls.lock().unwrap().is_empty());
+}
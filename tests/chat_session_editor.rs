@@ -0,0 +1,168 @@
+mod helpers;
+
+use code_g::client::models::{ChatMessage, Model, Parameters, Tool, Usage};
+use code_g::client::providers::openai::schema::Model as OpenAiModel;
+use code_g::session::budget::{BudgetLimit, TurnBudget};
+use code_g::session::editor::{EDITOR_PROMPT, HAND_OFF_EDITS_TOOL};
+use code_g::session::event::Event;
+use code_g::session::system_prompt::SystemPromptConfig;
+use helpers::scenario::ScenarioBuilder;
+use std::collections::HashMap;
+
+const EDITOR_MODEL: Model = Model::OpenAi(OpenAiModel::GptO4Mini);
+
+fn add_tool(scenario: ScenarioBuilder, name: &str, requires_approval: bool) -> ScenarioBuilder {
+    scenario.add_mock_tool(
+        name,
+        format!("Mock {}", name),
+        Parameters {
+            param_type: "object".to_string(),
+            properties: HashMap::new(),
+            required: vec![],
+            additional_properties: false,
+        },
+        true,
+        requires_approval,
+        format!("AI wants to use {}", name),
+        format!("{} was declined by user", name),
+        format!("{} done", name),
+    )
+}
+
+fn editor_scenario() -> ScenarioBuilder {
+    let scenario = ScenarioBuilder::new()
+        .with_system_prompt_config(SystemPromptConfig::None)
+        .with_editor(EDITOR_MODEL)
+        .inputs(["Rename parse to parse_args"]);
+    let scenario = add_tool(scenario, "read_file", false);
+    let scenario = add_tool(scenario, "edit_file", true);
+    add_tool(scenario, "write_file", true)
+        .then_tool_call(
+            "1",
+            HAND_OFF_EDITS_TOOL,
+            HashMap::from([
+                ("files".to_string(), "src/parser.rs".to_string()),
+                (
+                    "instructions".to_string(),
+                    "Rename the function parse to parse_args".to_string(),
+                ),
+            ]),
+        )
+        .then_tool_call(
+            "2",
+            "edit_file",
+            HashMap::from([("path".to_string(), "src/parser.rs".to_string())]),
+        )
+        .then_message("Renamed parse in src/parser.rs", true)
+        .then_message("Renamed the function", true)
+}
+
+fn tool_names(tools: &[Tool]) -> Vec<String> {
+    tools
+        .iter()
+        .map(|tool| tool.function.name.clone())
+        .collect()
+}
+
+#[tokio::test]
+async fn chat_session_hands_off_edits_to_the_editor_model() {
+    let scenario = editor_scenario().approvals(["approved"]).run().await;
+
+    let calls = scenario.client_calls.lock().unwrap().clone();
+    assert_eq!(calls.len(), 4);
+
+    // The architect cannot edit files itself, only hand off changes
+    let (_, _, architect_tools) = &calls[0];
+    assert_eq!(
+        tool_names(architect_tools),
+        vec!["read_file".to_string(), HAND_OFF_EDITS_TOOL.to_string()]
+    );
+
+    // The editor runs on its own model, prompt and edit tools
+    let (editor_model, editor_history, editor_tools) = &calls[1];
+    assert_eq!(editor_model, &EDITOR_MODEL);
+    assert_eq!(
+        editor_history[..2],
+        [
+            ChatMessage::System {
+                content: EDITOR_PROMPT.to_string(),
+            },
+            ChatMessage::User {
+                content: "Apply these changes.\n\nFiles:\n- src/parser.rs\n\nInstructions:\nRename the function parse to parse_args".to_string(),
+            },
+        ]
+    );
+    assert_eq!(
+        tool_names(editor_tools),
+        vec![
+            "read_file".to_string(),
+            "edit_file".to_string(),
+            "write_file".to_string()
+        ]
+    );
+
+    // The user approves the editor's edit and the architect gets a report of it
+    assert!(scenario.events.iter().any(|event| matches!(
+        event,
+        Event::ReceivedToolResponse { tool_name, approved: true, .. } if tool_name == "edit_file"
+    )));
+    let (architect_model, history, _) = scenario.last_client_call();
+    assert_eq!(architect_model, Model::OpenAi(OpenAiModel::Gpt4oMini));
+    assert_eq!(
+        history.last(),
+        Some(&ChatMessage::Tool {
+            content: "The editor made 1 edit(s):\n- edit_file src/parser.rs: edit_file done\nEditor's reply: Renamed parse in src/parser.rs".to_string(),
+            tool_call_id: "1".to_string(),
+            tool_name: HAND_OFF_EDITS_TOOL.to_string(),
+        })
+    );
+
+    // The handoff and the editor's reply stay out of the user's conversation
+    assert!(!scenario.events.contains(&Event::ReceivedAssistantMessage {
+        message: "Renamed parse in src/parser.rs".to_string(),
+    }));
+    assert!(scenario.events.contains(&Event::ReceivedAssistantMessage {
+        message: "Renamed the function".to_string(),
+    }));
+}
+
+#[tokio::test]
+async fn chat_session_reports_editor_edits_the_user_declined() {
+    let scenario = editor_scenario().approvals(["declined"]).run().await;
+
+    assert!(scenario.events.iter().any(|event| matches!(
+        event,
+        Event::ReceivedToolResponse { tool_name, approved: false, .. } if tool_name == "edit_file"
+    )));
+    let (_, history, _) = scenario.last_client_call();
+    assert!(matches!(
+        history.last(),
+        Some(ChatMessage::Tool { content, .. })
+            if content.starts_with("The editor made 1 edit(s):\n- edit_file src/parser.rs: not applied")
+    ));
+}
+
+#[tokio::test]
+async fn chat_session_counts_editor_usage_against_the_turn_budget() {
+    let scenario = editor_scenario()
+        .with_turn_budget(TurnBudget {
+            max_tokens: Some(250),
+            ..TurnBudget::default()
+        })
+        .with_usage_per_call(Usage {
+            prompt_tokens: 100,
+            completion_tokens: 0,
+            total_tokens: 100,
+        })
+        .approvals(["approved"])
+        .run()
+        .await;
+
+    assert!(scenario.events.contains(&Event::BudgetExceeded {
+        limit: BudgetLimit::Tokens {
+            used: 300,
+            max: 250,
+        },
+    }));
+    assert_eq!(scenario.client_calls.lock().unwrap().len(), 4);
+}
@@ -0,0 +1,139 @@
+mod helpers;
+
+use code_g::client::error::ChatClientError;
+use code_g::client::models::{AssistantMessage, ChatMessage};
+use code_g::session::event::Event;
+use code_g::session::system_prompt::SystemPromptConfig;
+use code_g::tools::write_file::WriteFile;
+use helpers::assertions::{assert_chat_history, assert_events};
+use helpers::scenario::ScenarioBuilder;
+use std::collections::HashMap;
+use std::fs;
+
+#[tokio::test]
+async fn chat_session_reports_error_and_continues_with_consistent_memory() {
+    let scenario = ScenarioBuilder::new()
+        .with_system_prompt_config(SystemPromptConfig::None)
+        .inputs(["Hello", "Are you there?"])
+        .then_error(ChatClientError::InvalidApiKey)
+        .then_message("Yes, I am here", true)
+        .run()
+        .await;
+
+    assert_events(
+        &scenario.events,
+        &[
+            Event::SessionStarted,
+            Event::ReceivedUserMessage {
+                message: "Hello".to_string(),
+                attachments: vec![],
+            },
+            Event::AwaitingAssistantResponse,
+            Event::Error {
+                message:
+                    "Chat Client error: Invalid API key. Type /retry to send the message again."
+                        .to_string(),
+            },
+            Event::ReceivedUserMessage {
+                message: "Are you there?".to_string(),
+                attachments: vec![],
+            },
+            Event::AwaitingAssistantResponse,
+            Event::ReceivedAssistantMessage {
+                message: "Yes, I am here".to_string(),
+            },
+            Event::SessionEnded,
+        ],
+    );
+
+    // The failed "Hello" turn was rolled back
+    assert_chat_history(
+        &scenario.last_client_call().1,
+        &[ChatMessage::User {
+            content: "Are you there?".to_string(),
+        }],
+    );
+}
+
+#[tokio::test]
+async fn chat_session_retries_failed_message() {
+    let scenario = ScenarioBuilder::new()
+        .with_system_prompt_config(SystemPromptConfig::None)
+        .inputs(["Hello", "/retry", "/retry", "Thanks"])
+        .then_error(ChatClientError::InvalidApiKey)
+        .then_message("Hello human", true)
+        .then_message("You're welcome", true)
+        .run()
+        .await;
+
+    assert!(scenario.events.contains(&Event::Error {
+        message: "There is no failed message to retry".to_string(),
+    }));
+
+    assert_chat_history(
+        &scenario.last_client_call().1,
+        &[
+            ChatMessage::User {
+                content: "Hello".to_string(),
+            },
+            ChatMessage::Assistant {
+                message: AssistantMessage::Content("Hello human".to_string()),
+            },
+            ChatMessage::User {
+                content: "Thanks".to_string(),
+            },
+        ],
+    );
+}
+
+#[tokio::test]
+async fn chat_session_keeps_tool_calls_that_ran_before_an_error() {
+    let dir = std::env::temp_dir().join(format!("code_g_errors_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("notes.txt").to_string_lossy().to_string();
+
+    let scenario = ScenarioBuilder::new()
+        .with_system_prompt_config(SystemPromptConfig::None)
+        .inputs(["Write the notes", "Did it work?"])
+        .approvals(["approved"])
+        .add_tool(Box::new(WriteFile))
+        .then_tool_call(
+            "call_1",
+            "write_file",
+            HashMap::from([
+                ("path".to_string(), path.clone()),
+                ("content".to_string(), "notes".to_string()),
+            ]),
+        )
+        .then_error(ChatClientError::InvalidApiKey)
+        .then_message("Yes, the notes are written", true)
+        .run()
+        .await;
+
+    let history = scenario.last_client_call().1;
+    assert_eq!(history.len(), 5);
+    assert!(matches!(
+        &history[1],
+        ChatMessage::Assistant {
+            message: AssistantMessage::ToolCalls(_)
+        }
+    ));
+    assert!(
+        matches!(&history[2], ChatMessage::Tool { tool_call_id, .. } if tool_call_id == "call_1")
+    );
+    match &history[3] {
+        ChatMessage::System { content } => {
+            assert!(content.contains("- write_file"));
+            assert!(content.contains(&format!("Files they modified: {}", path)));
+        }
+        message => panic!("Expected a system note, got {:?}", message),
+    }
+    assert_eq!(
+        history[4],
+        ChatMessage::User {
+            content: "Did it work?".to_string(),
+        }
+    );
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,137 @@
+mod helpers;
+
+use async_trait::async_trait;
+use code_g::client::models::{ChatResult, ToolCall};
+use code_g::session::builder::ChatSessionBuilder;
+use code_g::session::event::Event;
+use code_g::session::event_bus::EventSubscriber;
+use code_g::session::session::ChatSession;
+use code_g::session::sub_agent::TASK_TOOL;
+use code_g::session::system_prompt::SystemPromptConfig;
+use helpers::assertions::assert_events;
+use helpers::mocks::chat_client::MockChatClient;
+use helpers::mocks::event_handler::MockEventHandler;
+use helpers::mocks::tool_registry::MockToolRegistry;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+struct RecordingSubscriber {
+    events: Arc<Mutex<Vec<Event>>>,
+}
+
+#[async_trait]
+impl EventSubscriber for RecordingSubscriber {
+    async fn handle_event(&mut self, event: Event) {
+        self.events.lock().unwrap().push(event);
+    }
+}
+
+#[tokio::test]
+async fn chat_session_publishes_events_to_all_subscribers() {
+    let handler_events = Arc::new(Mutex::new(vec![]));
+    let event_handler =
+        MockEventHandler::new(handler_events.clone(), vec!["Hello".to_string()], vec![]);
+    let client = MockChatClient::new(
+        vec![Ok(ChatResult::Message {
+            content: "Hello human".to_string(),
+            turn_over: true,
+        })],
+        Arc::new(Mutex::new(vec![])),
+    );
+    let tools = MockToolRegistry::new(vec![], Arc::new(Mutex::new(vec![])));
+
+    let mut session = ChatSession::new(
+        Box::new(client),
+        Box::new(tools),
+        Box::new(event_handler),
+        SystemPromptConfig::None,
+    );
+
+    let transcript = Arc::new(Mutex::new(vec![]));
+    let metrics = Arc::new(Mutex::new(vec![]));
+    let handles = [
+        session.add_subscriber(Box::new(RecordingSubscriber {
+            events: transcript.clone(),
+        })),
+        session.add_subscriber(Box::new(RecordingSubscriber {
+            events: metrics.clone(),
+        })),
+    ];
+    let mut receiver = session.subscribe();
+
+    session.run().await.unwrap();
+    drop(session);
+    for handle in handles {
+        handle.await.unwrap();
+    }
+
+    let expected = [
+        Event::SessionStarted,
+        Event::ReceivedUserMessage {
+            message: "Hello".to_string(),
+            attachments: vec![],
+        },
+        Event::AwaitingAssistantResponse,
+        Event::ReceivedAssistantMessage {
+            message: "Hello human".to_string(),
+        },
+        Event::SessionEnded,
+    ];
+    assert_events(&handler_events.lock().unwrap(), &expected);
+    assert_events(&transcript.lock().unwrap(), &expected);
+    assert_events(&metrics.lock().unwrap(), &expected);
+
+    let mut received = vec![];
+    while let Ok(event) = receiver.recv().await {
+        received.push(event);
+    }
+    assert_events(&received, &expected);
+}
+
+#[tokio::test]
+async fn chat_session_publishes_sub_agent_events_on_its_bus() {
+    let client = MockChatClient::new(
+        vec![
+            Ok(ChatResult::ToolCalls(vec![ToolCall {
+                id: "1".to_string(),
+                name: TASK_TOOL.to_string(),
+                arguments: HashMap::from([("task".to_string(), "Find the parser".to_string())]),
+            }])),
+            Ok(ChatResult::Message {
+                content: "The parser is in src/parser.rs".to_string(),
+                turn_over: true,
+            }),
+            Ok(ChatResult::Message {
+                content: "It is in src/parser.rs".to_string(),
+                turn_over: true,
+            }),
+        ],
+        Arc::new(Mutex::new(vec![])),
+    );
+    let tools = MockToolRegistry::new(vec![], Arc::new(Mutex::new(vec![])));
+    let mut session = ChatSessionBuilder::new(Box::new(client))
+        .tools(Box::new(tools))
+        .event_handler(Box::new(MockEventHandler::new(
+            Arc::new(Mutex::new(vec![])),
+            vec![],
+            vec![],
+        )))
+        .system_prompt(SystemPromptConfig::None)
+        .sub_agents(true)
+        .build();
+    let mut receiver = session.subscribe();
+
+    session.send_message("Where is the parser?").await.unwrap();
+
+    let mut received = vec![];
+    while let Ok(event) = receiver.try_recv() {
+        received.push(event);
+    }
+    assert!(received.contains(&Event::ReceivedUserMessage {
+        message: "Find the parser".to_string(),
+        attachments: vec![],
+    }));
+    assert!(received.contains(&Event::ReceivedAssistantMessage {
+        message: "The parser is in src/parser.rs".to_string(),
+    }));
+}
@@ -181,6 +181,7 @@ async fn chat_session_handles_full_workflow() {
             // First user message
             Event::ReceivedUserMessage {
                 message: "I need a function to calculate the factorial of a number. Please implement it in a new file called math_utils.rs".to_string(),
+                attachments: vec![],
             },
             Event::AwaitingAssistantResponse,
             Event::ReceivedAssistantMessage {
@@ -255,6 +256,7 @@ async fn chat_session_handles_full_workflow() {
             // Second user message (follow-up)
             Event::ReceivedUserMessage {
                 message: "Great! Can you add input validation to make sure the number is non-negative?".to_string(),
+                attachments: vec![],
             },
             Event::AwaitingAssistantResponse,
             Event::ReceivedAssistantMessage {
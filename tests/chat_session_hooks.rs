@@ -0,0 +1,144 @@
+mod helpers;
+
+use code_g::client::models::{ChatMessage, Parameters};
+use code_g::hooks::config::{Hook, HooksConfig};
+use code_g::session::event::Event;
+use code_g::session::system_prompt::SystemPromptConfig;
+use helpers::assertions::{assert_events, assert_tool_calls};
+use helpers::scenario::ScenarioBuilder;
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+
+fn command_scenario(hooks: HooksConfig) -> ScenarioBuilder {
+    ScenarioBuilder::new()
+        .with_system_prompt_config(SystemPromptConfig::None)
+        .with_hooks(hooks)
+        .inputs(["Clean up the build"])
+        .add_mock_tool(
+            "execute_command",
+            "Execute a command in the terminal",
+            Parameters {
+                param_type: "object".to_string(),
+                properties: HashMap::new(),
+                required: vec!["command".to_string()],
+                additional_properties: false,
+            },
+            true,
+            false,
+            "AI wants to execute a command",
+            "Execute command was declined by user",
+            "done",
+        )
+        .then_tool_call(
+            "1",
+            "execute_command",
+            HashMap::from([("command".to_string(), "rm -rf target".to_string())]),
+        )
+        .then_message("Cleaned up", true)
+}
+
+fn hook(command: &str) -> Hook {
+    Hook {
+        command: command.to_string(),
+        tools: vec!["execute_command".to_string()],
+    }
+}
+
+fn tool_result(history: &[ChatMessage]) -> String {
+    history
+        .iter()
+        .find_map(|message| match message {
+            ChatMessage::Tool { content, .. } => Some(content.clone()),
+            _ => None,
+        })
+        .unwrap()
+}
+
+#[tokio::test]
+async fn chat_session_pre_tool_hook_blocks_tool_call_with_reason() {
+    let scenario = command_scenario(HooksConfig {
+        pre_tool_use: vec![hook(
+            "grep -q 'rm -rf' && echo 'Recursive deletes are not allowed' >&2 && exit 2",
+        )],
+        ..HooksConfig::default()
+    })
+    .run()
+    .await;
+
+    assert_tool_calls(&scenario.tool_calls, &[]);
+    assert!(scenario.events.contains(&Event::ToolCallBlocked {
+        tool_name: "execute_command".to_string(),
+        reason: "Recursive deletes are not allowed".to_string(),
+    }));
+    assert_eq!(
+        tool_result(&scenario.last_client_call().1),
+        "Tool call blocked by hook: Recursive deletes are not allowed"
+    );
+}
+
+#[tokio::test]
+async fn chat_session_post_tool_hook_receives_tool_call_and_feeds_back_output() {
+    let payload_path = env::temp_dir().join(format!("code_g_hook_payload_{}", std::process::id()));
+    let scenario = command_scenario(HooksConfig {
+        post_tool_use: vec![hook(&format!(
+            "cat > '{}' && echo 'warning: unused variable' && exit 2",
+            payload_path.display()
+        ))],
+        ..HooksConfig::default()
+    })
+    .run()
+    .await;
+
+    let payload: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&payload_path).unwrap()).unwrap();
+    fs::remove_file(&payload_path).unwrap();
+
+    assert_eq!(payload["hook_event"], "post_tool_use");
+    assert_eq!(payload["tool_call"]["name"], "execute_command");
+    assert_eq!(
+        payload["tool_call"]["arguments"]["command"],
+        "rm -rf target"
+    );
+    assert_eq!(payload["tool_response"], "done");
+    assert_eq!(
+        tool_result(&scenario.last_client_call().1),
+        "done\n\nPost-tool hook feedback:\nwarning: unused variable"
+    );
+}
+
+#[tokio::test]
+async fn chat_session_reports_failing_hooks_and_continues() {
+    let scenario = ScenarioBuilder::new()
+        .with_system_prompt_config(SystemPromptConfig::None)
+        .with_hooks(HooksConfig {
+            turn_end: vec![Hook {
+                command: "exit 1".to_string(),
+                tools: vec![],
+            }],
+            ..HooksConfig::default()
+        })
+        .inputs(["Hello"])
+        .then_message("Hello human", true)
+        .run()
+        .await;
+
+    assert_events(
+        &scenario.events,
+        &[
+            Event::SessionStarted,
+            Event::ReceivedUserMessage {
+                message: "Hello".to_string(),
+                attachments: vec![],
+            },
+            Event::AwaitingAssistantResponse,
+            Event::ReceivedAssistantMessage {
+                message: "Hello human".to_string(),
+            },
+            Event::HookFailed {
+                message: "Hook 'exit 1' failed with status 1: ".to_string(),
+            },
+            Event::SessionEnded,
+        ],
+    );
+}
@@ -49,7 +49,7 @@ async fn chat_session_live_handles_basic_message() {
     // Ensure our user message is captured
     assert!(events_vec.iter().any(|e| matches!(
         e,
-        Event::ReceivedUserMessage { message } if message == "Hello"
+        Event::ReceivedUserMessage { message, .. } if message == "Hello"
     )));
 
     // Ensure we received at least one assistant message with non-empty content
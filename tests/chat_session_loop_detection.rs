@@ -0,0 +1,135 @@
+mod helpers;
+
+use code_g::client::models::{ChatMessage, Parameters};
+use code_g::session::event::Event;
+use code_g::session::loop_detector::LoopPattern;
+use code_g::session::system_prompt::SystemPromptConfig;
+use helpers::assertions::assert_events;
+use helpers::scenario::ScenarioBuilder;
+use std::collections::HashMap;
+
+fn read_scenario() -> ScenarioBuilder {
+    ScenarioBuilder::new()
+        .with_system_prompt_config(SystemPromptConfig::None)
+        .inputs(["Why does main.rs not compile?"])
+        .add_mock_tool(
+            "read_file",
+            "Read a file",
+            Parameters {
+                param_type: "object".to_string(),
+                properties: HashMap::new(),
+                required: vec!["path".to_string()],
+                additional_properties: false,
+            },
+            true,
+            false,
+            "AI wants to read a file",
+            "Read file was declined by user",
+            "fn main() {",
+        )
+}
+
+fn read_args() -> HashMap<String, String> {
+    HashMap::from([("path".to_string(), "src/main.rs".to_string())])
+}
+
+fn read_tool_events() -> Vec<Event> {
+    vec![
+        Event::AwaitingAssistantResponse,
+        Event::ReceivedToolCall {
+            tool_name: "read_file".to_string(),
+            parameters: read_args(),
+        },
+        Event::ReceivedToolResponse {
+            tool_name: "read_file".to_string(),
+            response: "fn main() {".to_string(),
+            parameters: read_args(),
+            approved: true,
+        },
+    ]
+}
+
+fn repeated_read() -> LoopPattern {
+    LoopPattern::RepeatedCall {
+        tool_name: "read_file".to_string(),
+        count: 3,
+    }
+}
+
+#[tokio::test]
+async fn chat_session_corrects_repeated_tool_calls() {
+    let scenario = read_scenario()
+        .then_tool_call("1", "read_file", read_args())
+        .then_tool_call("2", "read_file", read_args())
+        .then_tool_call("3", "read_file", read_args())
+        .then_message("main.rs is missing a closing brace", true)
+        .run()
+        .await;
+
+    let mut expected = vec![
+        Event::SessionStarted,
+        Event::ReceivedUserMessage {
+            message: "Why does main.rs not compile?".to_string(),
+            attachments: vec![],
+        },
+    ];
+    for _ in 0..3 {
+        expected.extend(read_tool_events());
+    }
+    expected.extend([
+        Event::LoopDetected {
+            pattern: repeated_read(),
+        },
+        Event::AwaitingAssistantResponse,
+        Event::ReceivedAssistantMessage {
+            message: "main.rs is missing a closing brace".to_string(),
+        },
+        Event::SessionEnded,
+    ]);
+    assert_events(&scenario.events, &expected);
+
+    let (_, history, _) = scenario.last_client_call();
+    assert!(matches!(
+        history.last(),
+        Some(ChatMessage::System { content }) if content.contains(&repeated_read().to_string())
+    ));
+}
+
+#[tokio::test]
+async fn chat_session_stops_turn_when_loop_persists_after_correction() {
+    let scenario = read_scenario()
+        .then_tool_call("1", "read_file", read_args())
+        .then_tool_call("2", "read_file", read_args())
+        .then_tool_call("3", "read_file", read_args())
+        .then_tool_call("4", "read_file", read_args())
+        .run()
+        .await;
+
+    let loop_events: Vec<Event> = scenario
+        .events
+        .iter()
+        .filter(|event| {
+            matches!(
+                event,
+                Event::LoopDetected { .. } | Event::LoopAborted { .. }
+            )
+        })
+        .cloned()
+        .collect();
+    assert_events(
+        &loop_events,
+        &[
+            Event::LoopDetected {
+                pattern: repeated_read(),
+            },
+            Event::LoopAborted {
+                pattern: LoopPattern::RepeatedCall {
+                    tool_name: "read_file".to_string(),
+                    count: 4,
+                },
+            },
+        ],
+    );
+    assert_eq!(scenario.tool_calls.lock().unwrap().len(), 4);
+    assert_eq!(scenario.client_calls.lock().unwrap().len(), 4);
+}
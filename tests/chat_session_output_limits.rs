@@ -0,0 +1,116 @@
+mod helpers;
+
+use code_g::client::models::{ChatMessage, Parameters};
+use code_g::session::event::Event;
+use code_g::session::output_limit::READ_TOOL_OUTPUT_TOOL;
+use code_g::session::system_prompt::SystemPromptConfig;
+use helpers::scenario::ScenarioBuilder;
+use std::collections::HashMap;
+
+fn large_output() -> String {
+    (1..=100).map(|n| format!("log line {}\n", n)).collect()
+}
+
+fn scenario_with_log_tool() -> ScenarioBuilder {
+    ScenarioBuilder::new()
+        .with_system_prompt_config(SystemPromptConfig::None)
+        .add_mock_tool(
+            "read_log",
+            "Read the build log".to_string(),
+            Parameters {
+                param_type: "object".to_string(),
+                properties: HashMap::new(),
+                required: vec![],
+                additional_properties: false,
+            },
+            true,
+            false,
+            "AI wants to read the log".to_string(),
+            "read_log was declined by user".to_string(),
+            large_output(),
+        )
+}
+
+fn tool_contents(history: &[ChatMessage]) -> Vec<String> {
+    history
+        .iter()
+        .filter_map(|message| match message {
+            ChatMessage::Tool { content, .. } => Some(content.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn chat_session_truncates_large_tool_outputs_and_pages_through_them() {
+    let scenario = scenario_with_log_tool()
+        .with_output_limit(20)
+        .inputs(["Why did the build fail?"])
+        .then_tool_call("1", "read_log", HashMap::new())
+        .then_tool_call(
+            "2",
+            READ_TOOL_OUTPUT_TOOL,
+            HashMap::from([
+                ("output_id".to_string(), "1".to_string()),
+                ("start_line".to_string(), "50".to_string()),
+                ("end_line".to_string(), "51".to_string()),
+            ]),
+        )
+        .then_message("Line 50 failed", true)
+        .run()
+        .await;
+
+    let calls = scenario.client_calls.lock().unwrap().clone();
+    assert_eq!(calls.len(), 3);
+
+    // The paging tool is only offered once an output was truncated
+    let tool_names = |index: usize| -> Vec<String> {
+        calls[index]
+            .2
+            .iter()
+            .map(|tool| tool.function.name.clone())
+            .collect()
+    };
+    assert!(!tool_names(0).contains(&READ_TOOL_OUTPUT_TOOL.to_string()));
+    assert!(tool_names(1).contains(&READ_TOOL_OUTPUT_TOOL.to_string()));
+
+    let (_, history, _) = scenario.last_client_call();
+    let contents = tool_contents(&history);
+    assert_eq!(contents.len(), 2);
+    assert!(
+        contents[0]
+            .starts_with("log line 1\nlog line 2\nlog line 3\n[... 94 of 100 lines omitted.")
+    );
+    assert!(contents[0].contains("Call read_tool_output with output_id \"1\""));
+    assert!(contents[0].ends_with("log line 98\nlog line 99\nlog line 100"));
+    assert_eq!(
+        contents[1],
+        "Lines 50-51 of 100 of output 1:\n50: log line 50\n51: log line 51"
+    );
+
+    // The paging tool is handled by the session, not the tool registry
+    let tool_calls = scenario.tool_calls.lock().unwrap().clone();
+    assert_eq!(tool_calls.len(), 1);
+    assert!(scenario.events.iter().any(|event| matches!(
+        event,
+        Event::ReceivedToolResponse { tool_name, approved: true, .. } if tool_name == READ_TOOL_OUTPUT_TOOL
+    )));
+}
+
+#[tokio::test]
+async fn chat_session_keeps_tool_outputs_within_the_limit() {
+    let scenario = scenario_with_log_tool()
+        .inputs(["Why did the build fail?"])
+        .then_tool_call("1", "read_log", HashMap::new())
+        .then_message("It did not", true)
+        .run()
+        .await;
+
+    let (_, history, tools) = scenario.last_client_call();
+    assert_eq!(tool_contents(&history), vec![large_output()]);
+    assert!(
+        !tools
+            .iter()
+            .any(|tool| tool.function.name == READ_TOOL_OUTPUT_TOOL)
+    );
+}
@@ -0,0 +1,134 @@
+mod helpers;
+
+use code_g::client::models::{ChatMessage, Parameters};
+use code_g::permissions::mode::PermissionMode;
+use code_g::session::event::Event;
+use code_g::session::system_prompt::SystemPromptConfig;
+use helpers::assertions::assert_tool_calls;
+use helpers::scenario::ScenarioBuilder;
+use std::collections::HashMap;
+
+fn add_tool(scenario: ScenarioBuilder, name: &str, requires_approval: bool) -> ScenarioBuilder {
+    scenario.add_mock_tool(
+        name,
+        format!("Mock {}", name),
+        Parameters {
+            param_type: "object".to_string(),
+            properties: HashMap::new(),
+            required: vec![],
+            additional_properties: false,
+        },
+        true,
+        requires_approval,
+        format!("AI wants to use {}", name),
+        format!("{} was declined by user", name),
+        "ok",
+    )
+}
+
+fn scenario_with_tools() -> ScenarioBuilder {
+    let scenario = ScenarioBuilder::new().with_system_prompt_config(SystemPromptConfig::None);
+    let scenario = add_tool(scenario, "read_file", false);
+    let scenario = add_tool(scenario, "write_file", true);
+    add_tool(scenario, "execute_command", true)
+}
+
+fn path_args(path: &str) -> HashMap<String, String> {
+    HashMap::from([("path".to_string(), path.to_string())])
+}
+
+#[tokio::test]
+async fn chat_session_plan_mode_only_offers_read_only_tools() {
+    let scenario = scenario_with_tools()
+        .inputs(["/mode plan", "Add a README"])
+        .approvals(["declined"])
+        .then_tool_call("1", "write_file", path_args("README.md"))
+        .then_message("1. Read the project\n2. Write README.md", true)
+        .run()
+        .await;
+
+    let (_, history, tools) = scenario.last_client_call();
+    let tool_names: Vec<String> = tools
+        .iter()
+        .map(|tool| tool.function.name.clone())
+        .collect();
+
+    assert_eq!(tool_names, vec!["read_file".to_string()]);
+    assert_eq!(
+        history[0],
+        ChatMessage::System {
+            content: PermissionMode::Plan.system_note().to_string(),
+        }
+    );
+    assert!(scenario.events.contains(&Event::PermissionModeChanged {
+        mode: PermissionMode::Plan,
+    }));
+    // A call to a hidden tool is denied rather than run
+    assert_tool_calls(&scenario.tool_calls, &[]);
+    assert!(scenario.events.contains(&Event::ToolCallDenied {
+        tool_name: "write_file".to_string(),
+        reason: "write_file is not available in plan mode".to_string(),
+    }));
+}
+
+#[tokio::test]
+async fn chat_session_accept_edits_mode_still_asks_for_commands() {
+    // Only the command asks for approval, so a single answer is queued
+    let scenario = scenario_with_tools()
+        .inputs(["/mode accept-edits", "Add a README and commit it"])
+        .approvals(["declined"])
+        .then_tool_call("1", "write_file", path_args("README.md"))
+        .then_tool_call(
+            "2",
+            "execute_command",
+            HashMap::from([("command".to_string(), "git commit -am README".to_string())]),
+        )
+        .then_message("README added, commit declined", true)
+        .run()
+        .await;
+
+    assert_tool_calls(
+        &scenario.tool_calls,
+        &[("write_file".to_string(), path_args("README.md"))],
+    );
+}
+
+#[tokio::test]
+async fn chat_session_reports_invalid_mode_command_instead_of_sending_it() {
+    let scenario = scenario_with_tools().inputs(["/mode yolo"]).run().await;
+
+    assert!(scenario.client_calls.lock().unwrap().is_empty());
+    assert!(
+        scenario.events.contains(&Event::Error {
+            message:
+                "Invalid arguments for /mode, usage: /mode [default|plan|accept-edits|full-auto]"
+                    .to_string(),
+        })
+    );
+}
+
+#[tokio::test]
+async fn chat_session_mode_command_cycles_through_modes() {
+    let scenario = scenario_with_tools()
+        .inputs(["/mode", "/mode", "/mode"])
+        .run()
+        .await;
+
+    let modes: Vec<PermissionMode> = scenario
+        .events
+        .iter()
+        .filter_map(|event| match event {
+            Event::PermissionModeChanged { mode } => Some(*mode),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(
+        modes,
+        vec![
+            PermissionMode::Plan,
+            PermissionMode::AcceptEdits,
+            PermissionMode::FullAuto,
+        ]
+    );
+}
@@ -0,0 +1,191 @@
+mod helpers;
+
+use code_g::client::models::{ChatMessage, Parameters};
+use code_g::config::settings::{SETTINGS_PATH, Settings};
+use code_g::permissions::config::{PermissionDecision, PermissionRule, PermissionsConfig};
+use code_g::permissions::grant::GrantScope;
+use code_g::session::event::Event;
+use code_g::session::system_prompt::SystemPromptConfig;
+use helpers::assertions::assert_tool_calls;
+use helpers::scenario::ScenarioBuilder;
+use std::collections::{BTreeMap, HashMap};
+use std::env;
+use std::fs;
+
+fn command_scenario(rules: Vec<PermissionRule>, commands: &[&str]) -> ScenarioBuilder {
+    let scenario = ScenarioBuilder::new()
+        .with_system_prompt_config(SystemPromptConfig::None)
+        .with_permissions(PermissionsConfig { rules })
+        .inputs(["Run the tests"])
+        .add_mock_tool(
+            "execute_command",
+            "Execute a command in the terminal",
+            Parameters {
+                param_type: "object".to_string(),
+                properties: HashMap::new(),
+                required: vec!["command".to_string()],
+                additional_properties: false,
+            },
+            true,
+            true,
+            "AI wants to execute a command",
+            "Execute command was declined by user",
+            "ok",
+        );
+
+    commands
+        .iter()
+        .enumerate()
+        .fold(scenario, |scenario, (i, command)| {
+            scenario.then_tool_call(
+                (i + 1).to_string(),
+                "execute_command",
+                HashMap::from([("command".to_string(), command.to_string())]),
+            )
+        })
+        .then_message("Done", true)
+}
+
+fn rule(decision: PermissionDecision, command: &str) -> PermissionRule {
+    PermissionRule {
+        decision,
+        tool: "execute_command".to_string(),
+        arguments: BTreeMap::from([("command".to_string(), command.to_string())]),
+        reason: None,
+    }
+}
+
+fn tool_result(history: &[ChatMessage]) -> String {
+    history
+        .iter()
+        .find_map(|message| match message {
+            ChatMessage::Tool { content, .. } => Some(content.clone()),
+            _ => None,
+        })
+        .unwrap()
+}
+
+#[tokio::test]
+async fn chat_session_runs_allowed_tool_calls_without_approval() {
+    // No approvals are queued, so asking for one would fail the test
+    let scenario = command_scenario(
+        vec![rule(PermissionDecision::Allow, "cargo test*")],
+        &["cargo test --workspace"],
+    )
+    .run()
+    .await;
+
+    assert_tool_calls(
+        &scenario.tool_calls,
+        &[(
+            "execute_command".to_string(),
+            HashMap::from([("command".to_string(), "cargo test --workspace".to_string())]),
+        )],
+    );
+    assert_eq!(tool_result(&scenario.last_client_call().1), "ok");
+}
+
+#[tokio::test]
+async fn chat_session_denies_tool_calls_matching_deny_rules() {
+    let scenario = command_scenario(
+        vec![
+            rule(PermissionDecision::Allow, "*"),
+            rule(PermissionDecision::Deny, "rm -rf *"),
+        ],
+        &["rm -rf target"],
+    )
+    .run()
+    .await;
+
+    let reason = r#"matched rule 'deny execute_command command="rm -rf *"'"#;
+    assert_tool_calls(&scenario.tool_calls, &[]);
+    assert!(scenario.events.contains(&Event::ToolCallDenied {
+        tool_name: "execute_command".to_string(),
+        reason: reason.to_string(),
+    }));
+    assert_eq!(
+        tool_result(&scenario.last_client_call().1),
+        format!("Tool call denied by permission policy: {}", reason)
+    );
+}
+
+#[tokio::test]
+async fn chat_session_asks_for_approval_when_no_rule_matches() {
+    let scenario = command_scenario(
+        vec![rule(PermissionDecision::Allow, "cargo test*")],
+        &["cargo build"],
+    )
+    .approvals(["approved"])
+    .run()
+    .await;
+
+    assert_tool_calls(
+        &scenario.tool_calls,
+        &[(
+            "execute_command".to_string(),
+            HashMap::from([("command".to_string(), "cargo build".to_string())]),
+        )],
+    );
+    assert!(
+        !scenario
+            .events
+            .iter()
+            .any(|event| matches!(event, Event::ToolCallDenied { .. }))
+    );
+}
+
+fn command_call(command: &str) -> (String, HashMap<String, String>) {
+    (
+        "execute_command".to_string(),
+        HashMap::from([("command".to_string(), command.to_string())]),
+    )
+}
+
+#[tokio::test]
+async fn chat_session_remembers_always_allow_for_the_session() {
+    // Only one approval is queued, so the second call must not ask again
+    let scenario = command_scenario(vec![], &["cargo test --lib", "cargo test --doc"])
+        .approvals(["always:command_prefix:session"])
+        .run()
+        .await;
+
+    assert_tool_calls(
+        &scenario.tool_calls,
+        &[
+            command_call("cargo test --lib"),
+            command_call("cargo test --doc"),
+        ],
+    );
+    assert!(scenario.events.contains(&Event::PermissionGranted {
+        rule: r#"allow execute_command command="cargo test *""#.to_string(),
+        scope: GrantScope::Session,
+    }));
+}
+
+#[tokio::test]
+async fn chat_session_saves_always_allow_for_the_project() {
+    let project_dir = env::temp_dir().join(format!("code_g_grant_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&project_dir);
+
+    let scenario = command_scenario(vec![], &["cargo build"])
+        .with_project_dir(project_dir.clone())
+        .approvals(["always:tool:project"])
+        .run()
+        .await;
+
+    let settings = Settings::load_file(&project_dir.join(SETTINGS_PATH))
+        .unwrap()
+        .unwrap();
+    fs::remove_dir_all(&project_dir).unwrap();
+
+    assert_tool_calls(&scenario.tool_calls, &[command_call("cargo build")]);
+    assert_eq!(
+        settings.permissions.rules,
+        vec![PermissionRule {
+            decision: PermissionDecision::Allow,
+            tool: "execute_command".to_string(),
+            arguments: BTreeMap::new(),
+            reason: None,
+        }]
+    );
+}
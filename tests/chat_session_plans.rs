@@ -0,0 +1,121 @@
+mod helpers;
+
+use code_g::client::models::Parameters;
+use code_g::permissions::mode::PermissionMode;
+use code_g::session::event::Event;
+use code_g::session::plan::{COMPLETE_PLAN_STEP_TOOL, Plan};
+use code_g::session::system_prompt::SystemPromptConfig;
+use helpers::assertions::assert_tool_calls;
+use helpers::scenario::ScenarioBuilder;
+use std::collections::HashMap;
+
+fn scenario_with_write_tool() -> ScenarioBuilder {
+    ScenarioBuilder::new()
+        .with_system_prompt_config(SystemPromptConfig::None)
+        .add_mock_tool(
+            "write_file",
+            "Mock write_file",
+            Parameters {
+                param_type: "object".to_string(),
+                properties: HashMap::new(),
+                required: vec![],
+                additional_properties: false,
+            },
+            true,
+            true,
+            "AI wants to use write_file",
+            "write_file was declined by user",
+            "ok",
+        )
+}
+
+fn step_args(step: &str) -> HashMap<String, String> {
+    HashMap::from([("step".to_string(), step.to_string())])
+}
+
+fn plan_updates(events: &[Event]) -> Vec<String> {
+    events
+        .iter()
+        .filter_map(|event| match event {
+            Event::PlanUpdated { plan } => Some(plan.to_string()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn chat_session_carries_out_approved_plan_step_by_step() {
+    let path_args = HashMap::from([("path".to_string(), "README.md".to_string())]);
+    let scenario = scenario_with_write_tool()
+        .inputs(["/mode plan", "Add a README"])
+        .approvals(["approved", "approved"])
+        .then_message("My plan:\n1. Write README.md\n2. Review it", true)
+        .then_tool_call("1", "write_file", path_args.clone())
+        .then_tool_call("2", COMPLETE_PLAN_STEP_TOOL, step_args("1"))
+        .then_tool_call("3", COMPLETE_PLAN_STEP_TOOL, step_args("2"))
+        .then_message("Done", true)
+        .run()
+        .await;
+
+    // The plan step tool is handled by the session, only the write is run
+    assert_tool_calls(
+        &scenario.tool_calls,
+        &[("write_file".to_string(), path_args)],
+    );
+    assert_eq!(
+        plan_updates(&scenario.events),
+        vec![
+            "1. [ ] Write README.md\n2. [ ] Review it",
+            "1. [x] Write README.md\n2. [ ] Review it",
+            "1. [x] Write README.md\n2. [x] Review it",
+        ]
+    );
+    assert!(scenario.events.contains(&Event::PermissionModeChanged {
+        mode: PermissionMode::Default,
+    }));
+
+    let (_, _, tools) = scenario.last_client_call();
+    assert!(
+        tools
+            .iter()
+            .all(|tool| tool.function.name != COMPLETE_PLAN_STEP_TOOL)
+    );
+}
+
+#[tokio::test]
+async fn chat_session_uses_edited_plan() {
+    let scenario = scenario_with_write_tool()
+        .inputs(["/mode plan", "Add a README"])
+        .approvals(["edited:1. Write README.md"])
+        .then_message("1. Write README.md\n2. Write CONTRIBUTING.md", true)
+        .then_message("Starting", true)
+        .run()
+        .await;
+
+    assert_eq!(
+        plan_updates(&scenario.events),
+        vec![Plan::parse("1. Write README.md").unwrap().to_string()]
+    );
+    let (_, _, tools) = scenario.last_client_call();
+    assert!(
+        tools
+            .iter()
+            .any(|tool| tool.function.name == COMPLETE_PLAN_STEP_TOOL)
+    );
+}
+
+#[tokio::test]
+async fn chat_session_stays_in_plan_mode_when_plan_is_rejected() {
+    let scenario = scenario_with_write_tool()
+        .inputs(["/mode plan", "Add a README"])
+        .approvals(["declined"])
+        .then_message("1. Write README.md", true)
+        .run()
+        .await;
+
+    assert!(scenario.events.contains(&Event::PlanRejected));
+    assert!(plan_updates(&scenario.events).is_empty());
+    assert!(!scenario.events.contains(&Event::PermissionModeChanged {
+        mode: PermissionMode::Default,
+    }));
+}
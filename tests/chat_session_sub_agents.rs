@@ -0,0 +1,181 @@
+mod helpers;
+
+use code_g::client::models::{AssistantMessage, ChatMessage, Parameters, Usage};
+use code_g::hooks::config::{Hook, HooksConfig};
+use code_g::session::budget::{BudgetLimit, TurnBudget};
+use code_g::session::event::Event;
+use code_g::session::sub_agent::{SUB_AGENT_PROMPT, TASK_TOOL};
+use code_g::session::system_prompt::SystemPromptConfig;
+use helpers::scenario::ScenarioBuilder;
+use std::collections::HashMap;
+
+fn add_tool(scenario: ScenarioBuilder, name: &str, requires_approval: bool) -> ScenarioBuilder {
+    scenario.add_mock_tool(
+        name,
+        format!("Mock {}", name),
+        Parameters {
+            param_type: "object".to_string(),
+            properties: HashMap::new(),
+            required: vec![],
+            additional_properties: false,
+        },
+        true,
+        requires_approval,
+        format!("AI wants to use {}", name),
+        format!("{} was declined by user", name),
+        "fn parse() {}",
+    )
+}
+
+fn scenario_with_tools() -> ScenarioBuilder {
+    let scenario = ScenarioBuilder::new().with_system_prompt_config(SystemPromptConfig::None);
+    let scenario = add_tool(scenario, "read_file", false);
+    add_tool(scenario, "write_file", true)
+}
+
+fn tool_names(tools: &[code_g::client::models::Tool]) -> Vec<String> {
+    tools
+        .iter()
+        .map(|tool| tool.function.name.clone())
+        .collect()
+}
+
+#[tokio::test]
+async fn chat_session_returns_only_the_sub_agent_summary() {
+    let scenario = scenario_with_tools()
+        .with_sub_agents()
+        .inputs(["Where is the parser?"])
+        .then_tool_call(
+            "1",
+            TASK_TOOL,
+            HashMap::from([("task".to_string(), "Find the parser".to_string())]),
+        )
+        .then_tool_call(
+            "2",
+            "read_file",
+            HashMap::from([("path".to_string(), "src/parser.rs".to_string())]),
+        )
+        .then_message("The parser is in src/parser.rs", true)
+        .then_message("It is in src/parser.rs", true)
+        .run()
+        .await;
+
+    let calls = scenario.client_calls.lock().unwrap().clone();
+    assert_eq!(calls.len(), 4);
+
+    // The sub-agent starts from its own prompt and only gets read-only tools
+    let (_, sub_agent_history, sub_agent_tools) = &calls[1];
+    assert_eq!(
+        sub_agent_history[..2],
+        [
+            ChatMessage::System {
+                content: SUB_AGENT_PROMPT.to_string(),
+            },
+            ChatMessage::User {
+                content: "Find the parser".to_string(),
+            },
+        ]
+    );
+    assert_eq!(tool_names(sub_agent_tools), vec!["read_file".to_string()]);
+
+    // The parent only sees the summary, not the sub-agent's tool calls
+    let (_, history, tools) = scenario.last_client_call();
+    assert!(tool_names(&tools).contains(&TASK_TOOL.to_string()));
+    assert_eq!(
+        history.last(),
+        Some(&ChatMessage::Tool {
+            content: "The parser is in src/parser.rs".to_string(),
+            tool_call_id: "1".to_string(),
+            tool_name: TASK_TOOL.to_string(),
+        })
+    );
+    assert!(!history.iter().any(|message| matches!(
+        message,
+        ChatMessage::Assistant {
+            message: AssistantMessage::ToolCalls(calls),
+        } if calls.iter().any(|call| call.name == "read_file")
+    )));
+}
+
+#[tokio::test]
+async fn chat_session_does_not_offer_task_tool_without_sub_agents() {
+    let scenario = scenario_with_tools()
+        .inputs(["Where is the parser?"])
+        .then_message("I don't know", true)
+        .run()
+        .await;
+
+    let (_, _, tools) = scenario.last_client_call();
+    assert_eq!(
+        tool_names(&tools),
+        vec!["read_file".to_string(), "write_file".to_string()]
+    );
+}
+
+fn find_the_parser(scenario: ScenarioBuilder) -> ScenarioBuilder {
+    scenario
+        .with_sub_agents()
+        .inputs(["Where is the parser?"])
+        .then_tool_call(
+            "1",
+            TASK_TOOL,
+            HashMap::from([("task".to_string(), "Find the parser".to_string())]),
+        )
+        .then_tool_call(
+            "2",
+            "read_file",
+            HashMap::from([("path".to_string(), "src/parser.rs".to_string())]),
+        )
+        .then_message("The parser is in src/parser.rs", true)
+}
+
+#[tokio::test]
+async fn chat_session_runs_tool_hooks_for_sub_agent_tool_calls() {
+    let scenario = find_the_parser(scenario_with_tools())
+        .with_hooks(HooksConfig {
+            pre_tool_use: vec![Hook {
+                command: "echo 'Reading is paused' >&2 && exit 2".to_string(),
+                tools: vec!["read_file".to_string()],
+            }],
+            ..HooksConfig::default()
+        })
+        .then_message("It is in src/parser.rs", true)
+        .run()
+        .await;
+
+    let calls = scenario.client_calls.lock().unwrap().clone();
+    let (_, sub_agent_history, _) = &calls[2];
+    assert_eq!(
+        sub_agent_history.last(),
+        Some(&ChatMessage::Tool {
+            content: "Tool call blocked by hook: Reading is paused".to_string(),
+            tool_call_id: "2".to_string(),
+            tool_name: "read_file".to_string(),
+        })
+    );
+}
+
+#[tokio::test]
+async fn chat_session_counts_sub_agent_usage_against_the_turn_budget() {
+    let scenario = find_the_parser(scenario_with_tools())
+        .with_turn_budget(TurnBudget {
+            max_tokens: Some(250),
+            ..TurnBudget::default()
+        })
+        .with_usage_per_call(Usage {
+            prompt_tokens: 100,
+            completion_tokens: 0,
+            total_tokens: 100,
+        })
+        .then_message("The parser is in src/parser.rs, I ran out of budget", true)
+        .run()
+        .await;
+
+    assert!(scenario.events.contains(&Event::BudgetExceeded {
+        limit: BudgetLimit::Tokens {
+            used: 300,
+            max: 250,
+        },
+    }));
+    assert_eq!(scenario.client_calls.lock().unwrap().len(), 4);
+}
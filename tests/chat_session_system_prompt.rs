@@ -1,10 +1,13 @@
 mod helpers;
 
 use code_g::client::models::ChatMessage;
+use code_g::session::environment::PromptEnvironment;
 use code_g::session::event::Event;
 use code_g::session::system_prompt::{SYSTEM_PROMPT, SystemPromptConfig};
+use code_g::tools::read_file::ReadFile;
 use helpers::assertions::{assert_chat_history, assert_events, assert_tool_calls};
 use helpers::scenario::ScenarioBuilder;
+use std::path::PathBuf;
 
 #[tokio::test]
 async fn system_prompt_default_is_included() {
@@ -21,6 +24,7 @@ async fn system_prompt_default_is_included() {
             Event::SessionStarted,
             Event::ReceivedUserMessage {
                 message: "Hello".to_string(),
+                attachments: vec![],
             },
             Event::AwaitingAssistantResponse,
             Event::ReceivedAssistantMessage {
@@ -60,6 +64,7 @@ async fn system_prompt_none_is_excluded() {
             Event::SessionStarted,
             Event::ReceivedUserMessage {
                 message: "Hello".to_string(),
+                attachments: vec![],
             },
             Event::AwaitingAssistantResponse,
             Event::ReceivedAssistantMessage {
@@ -95,6 +100,7 @@ async fn system_prompt_custom_is_used() {
             Event::SessionStarted,
             Event::ReceivedUserMessage {
                 message: "Hello".to_string(),
+                attachments: vec![],
             },
             Event::AwaitingAssistantResponse,
             Event::ReceivedAssistantMessage {
@@ -116,3 +122,56 @@ async fn system_prompt_custom_is_used() {
 
     assert_tool_calls(&scenario.tool_calls, &[]);
 }
+
+fn environment() -> PromptEnvironment {
+    PromptEnvironment {
+        cwd: PathBuf::from("/home/user/project"),
+        os: "linux".to_string(),
+        shell: "/bin/bash".to_string(),
+        date: "2026-10-18".to_string(),
+        git_branch: Some("main".to_string()),
+        git_status: Some("clean".to_string()),
+        file_tree: "    src/".to_string(),
+        tools: vec![],
+    }
+}
+
+#[tokio::test]
+async fn system_prompt_custom_variables_describe_the_environment() {
+    let scenario = ScenarioBuilder::new()
+        .with_system_prompt_config(SystemPromptConfig::Custom(
+            "Work in {{cwd}} on {{git_branch}} with {{tools}}.".to_string(),
+        ))
+        .add_tool(Box::new(ReadFile))
+        .with_environment(environment())
+        .inputs(["Hello"])
+        .then_message("Hello human", true)
+        .run()
+        .await;
+
+    assert_eq!(
+        scenario.last_client_call().1[0],
+        ChatMessage::System {
+            content: "Work in /home/user/project on main with read_file.".to_string(),
+        }
+    );
+}
+
+#[tokio::test]
+async fn system_prompt_default_describes_the_environment() {
+    let scenario = ScenarioBuilder::new()
+        .with_system_prompt_config(SystemPromptConfig::Default)
+        .with_environment(environment())
+        .inputs(["Hello"])
+        .then_message("Hello human", true)
+        .run()
+        .await;
+
+    let ChatMessage::System { content } = &scenario.last_client_call().1[0] else {
+        panic!("Expected a system prompt");
+    };
+    assert!(content.starts_with(SYSTEM_PROMPT.trim_end()));
+    assert!(content.contains("    - Working directory: /home/user/project\n"));
+    assert!(content.contains("    - Git status: clean\n"));
+    assert!(content.ends_with("Project files:\n    src/"));
+}
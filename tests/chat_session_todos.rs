@@ -0,0 +1,99 @@
+mod helpers;
+
+use code_g::client::models::ChatMessage;
+use code_g::session::event::Event;
+use code_g::session::system_prompt::SystemPromptConfig;
+use code_g::session::todo::{TODO_READ_TOOL, TODO_WRITE_TOOL, TodoList};
+use helpers::scenario::ScenarioBuilder;
+use std::collections::HashMap;
+
+const TODOS: &str = r#"[{"content": "Add the module", "status": "done"}, {"content": "Test it", "status": "in_progress"}]"#;
+
+fn tool_contents(history: &[ChatMessage]) -> Vec<String> {
+    history
+        .iter()
+        .filter_map(|message| match message {
+            ChatMessage::Tool { content, .. } => Some(content.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn chat_session_keeps_the_todo_list_written_by_the_assistant() {
+    let scenario = ScenarioBuilder::new()
+        .with_system_prompt_config(SystemPromptConfig::None)
+        .with_todos()
+        .inputs(["Add a module"])
+        .then_tool_call(
+            "1",
+            TODO_WRITE_TOOL,
+            HashMap::from([("todos".to_string(), TODOS.to_string())]),
+        )
+        .then_tool_call("2", TODO_READ_TOOL, HashMap::new())
+        .then_message("Testing the module", true)
+        .run()
+        .await;
+
+    let (_, history, tools) = scenario.last_client_call();
+    let tool_names: Vec<String> = tools
+        .iter()
+        .map(|tool| tool.function.name.clone())
+        .collect();
+    assert!(tool_names.contains(&TODO_WRITE_TOOL.to_string()));
+    assert!(tool_names.contains(&TODO_READ_TOOL.to_string()));
+    assert_eq!(
+        tool_contents(&history),
+        vec![
+            "Todo list updated".to_string(),
+            "[x] Add the module\n[~] Test it".to_string(),
+        ]
+    );
+
+    // The session handles the todo tools itself and reports the new list
+    assert!(scenario.tool_calls.lock().unwrap().is_empty());
+    assert!(scenario.events.contains(&Event::TodosUpdated {
+        todos: TodoList::parse(TODOS).unwrap(),
+    }));
+}
+
+#[tokio::test]
+async fn chat_session_rejects_invalid_todo_lists() {
+    let scenario = ScenarioBuilder::new()
+        .with_system_prompt_config(SystemPromptConfig::None)
+        .with_todos()
+        .inputs(["Add a module"])
+        .then_tool_call(
+            "1",
+            TODO_WRITE_TOOL,
+            HashMap::from([("todos".to_string(), "1. Add the module".to_string())]),
+        )
+        .then_tool_call("2", TODO_READ_TOOL, HashMap::new())
+        .then_message("Done", true)
+        .run()
+        .await;
+
+    let (_, history, _) = scenario.last_client_call();
+    let contents = tool_contents(&history);
+    assert!(contents[0].starts_with("Error: Invalid todo list:"));
+    assert_eq!(contents[1], "The todo list is empty");
+    assert!(
+        !scenario
+            .events
+            .iter()
+            .any(|event| matches!(event, Event::TodosUpdated { .. }))
+    );
+}
+
+#[tokio::test]
+async fn chat_session_offers_no_todo_tools_by_default() {
+    let scenario = ScenarioBuilder::new()
+        .with_system_prompt_config(SystemPromptConfig::None)
+        .inputs(["Hello"])
+        .then_message("Hi", true)
+        .run()
+        .await;
+
+    let (_, _, tools) = scenario.last_client_call();
+    assert!(tools.is_empty());
+}
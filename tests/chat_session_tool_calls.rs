@@ -41,6 +41,7 @@ async fn chat_session_handles_tool_call() {
             Event::SessionStarted,
             Event::ReceivedUserMessage {
                 message: "What is the weather in Tokyo?".to_string(),
+                attachments: vec![],
             },
             Event::AwaitingAssistantResponse,
             Event::ReceivedToolCall {
@@ -150,6 +151,7 @@ async fn chat_session_handles_multiple_tool_calls() {
             Event::SessionStarted,
             Event::ReceivedUserMessage {
                 message: "Fix all errors in the main.rs file".to_string(),
+                attachments: vec![],
             },
             Event::AwaitingAssistantResponse,
             Event::ReceivedAssistantMessage {
@@ -268,6 +270,7 @@ async fn chat_session_handles_tool_call_when_no_tools_are_available() {
             Event::SessionStarted,
             Event::ReceivedUserMessage {
                 message: "What is the weather in Tokyo?".to_string(),
+                attachments: vec![],
             },
             Event::AwaitingAssistantResponse,
             Event::ReceivedToolCall {
@@ -355,6 +358,7 @@ async fn chat_session_handles_invalid_tool_call_parameters() {
             Event::SessionStarted,
             Event::ReceivedUserMessage {
                 message: "What is the weather in Tokyo?".to_string(),
+                attachments: vec![],
             },
             Event::AwaitingAssistantResponse,
             Event::ReceivedToolCall {
@@ -0,0 +1,45 @@
+mod helpers;
+
+use code_g::client::models::ChatMessage;
+use code_g::session::event::Event;
+use code_g::session::system_prompt::SystemPromptConfig;
+use helpers::scenario::ScenarioBuilder;
+use std::env;
+use std::fs;
+
+#[tokio::test]
+async fn chat_session_exports_transcript_to_project_dir() {
+    let project_dir = env::temp_dir().join(format!("code_g_transcript_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&project_dir);
+
+    let scenario = ScenarioBuilder::new()
+        .with_system_prompt_config(SystemPromptConfig::None)
+        .with_project_dir(project_dir.clone())
+        .inputs(["Hello", "/export json review/session.json", "/export md"])
+        .then_message("Hello human", true)
+        .run()
+        .await;
+
+    let path = project_dir.join("review/session.json");
+    assert!(
+        scenario
+            .events
+            .contains(&Event::TranscriptExported { path: path.clone() })
+    );
+    let json: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    let messages: Vec<ChatMessage> = serde_json::from_value(json["messages"].clone()).unwrap();
+    assert_eq!(messages.len(), 2);
+
+    let markdown = scenario.events.iter().find_map(|event| match event {
+        Event::TranscriptExported { path } if path.extension().unwrap() == "md" => {
+            Some(fs::read_to_string(path).unwrap())
+        }
+        _ => None,
+    });
+    assert_eq!(
+        markdown.unwrap(),
+        "# CodeG transcript\n\n## User\n\nHello\n\n## Assistant\n\nHello human\n"
+    );
+    fs::remove_dir_all(project_dir).unwrap();
+}
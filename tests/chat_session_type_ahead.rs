@@ -0,0 +1,93 @@
+mod helpers;
+
+use code_g::client::models::{ChatMessage, Parameters};
+use code_g::session::event::Event;
+use code_g::session::system_prompt::SystemPromptConfig;
+use helpers::scenario::ScenarioBuilder;
+use std::collections::HashMap;
+
+fn scenario_with_tool() -> ScenarioBuilder {
+    ScenarioBuilder::new()
+        .with_system_prompt_config(SystemPromptConfig::None)
+        .add_mock_tool(
+            "read_file",
+            "Read a file".to_string(),
+            Parameters {
+                param_type: "object".to_string(),
+                properties: HashMap::new(),
+                required: vec![],
+                additional_properties: false,
+            },
+            true,
+            false,
+            "AI wants to read a file".to_string(),
+            "read_file was declined by user".to_string(),
+            "fn main() {}",
+        )
+}
+
+fn user_messages(history: &[ChatMessage]) -> Vec<String> {
+    history
+        .iter()
+        .filter_map(|message| match message {
+            ChatMessage::User { content } => Some(content.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn chat_session_injects_messages_typed_during_a_turn() {
+    let scenario = scenario_with_tool()
+        .type_ahead(["Only look at main.rs"])
+        .inputs(["Fix the bug"])
+        .then_tool_call("1", "read_file", HashMap::new())
+        .then_message("Fixed main.rs", true)
+        .run()
+        .await;
+
+    let calls = scenario.client_calls.lock().unwrap().clone();
+    assert_eq!(calls.len(), 2);
+
+    // The typed message follows the tool response and precedes the next completion
+    let (_, history, _) = scenario.last_client_call();
+    assert!(matches!(history[2], ChatMessage::Tool { .. }));
+    assert_eq!(
+        history[3],
+        ChatMessage::User {
+            content: "Only look at main.rs".to_string(),
+        }
+    );
+    assert!(scenario.events.contains(&Event::ReceivedUserMessage {
+        message: "Only look at main.rs".to_string(),
+        attachments: vec![],
+    }));
+}
+
+#[tokio::test]
+async fn chat_session_sends_queued_commands_and_late_messages_as_next_turns() {
+    let scenario = scenario_with_tool()
+        .type_ahead(["/mode default", "And add a test"])
+        .inputs(["Fix the bug"])
+        .then_tool_call("1", "read_file", HashMap::new())
+        .then_message("Fixed main.rs", true)
+        .then_message("Added a test", true)
+        .run()
+        .await;
+
+    let calls = scenario.client_calls.lock().unwrap().clone();
+    assert_eq!(calls.len(), 3);
+
+    // The command stops the steering, so both run after the turn without asking for input
+    assert_eq!(user_messages(&calls[1].1), vec!["Fix the bug".to_string()]);
+    assert_eq!(
+        user_messages(&calls[2].1),
+        vec!["Fix the bug".to_string(), "And add a test".to_string()]
+    );
+    assert!(
+        scenario
+            .events
+            .iter()
+            .any(|event| matches!(event, Event::PermissionModeChanged { .. }))
+    );
+}
@@ -0,0 +1,187 @@
+mod helpers;
+
+use code_g::client::models::ChatMessage;
+use code_g::session::event::Event;
+use code_g::session::verification::VerificationConfig;
+use code_g::tools::execute_command::ExecuteCommand;
+use code_g::tools::write_file::WriteFile;
+use helpers::scenario::ScenarioBuilder;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const CHECK: &str = "grep fixed status.txt";
+
+fn create_temp_dir(name: &str) -> PathBuf {
+    let temp_dir = std::env::temp_dir().join(format!(
+        "code_g_verification_{}_{}",
+        name,
+        std::process::id()
+    ));
+    let _ = fs::remove_dir_all(&temp_dir);
+    fs::create_dir_all(&temp_dir).unwrap();
+    temp_dir
+}
+
+fn write_status(scenario: ScenarioBuilder, id: &str, dir: &Path, content: &str) -> ScenarioBuilder {
+    scenario.then_tool_call(
+        id,
+        "write_file",
+        HashMap::from([
+            (
+                "path".to_string(),
+                dir.join("status.txt").to_string_lossy().to_string(),
+            ),
+            ("content".to_string(), content.to_string()),
+        ]),
+    )
+}
+
+fn verification_scenario(dir: &Path, max_retries: usize) -> ScenarioBuilder {
+    ScenarioBuilder::new()
+        .with_project_dir(dir.to_path_buf())
+        .with_verification(VerificationConfig {
+            command: Some(CHECK.to_string()),
+            max_retries,
+            ..VerificationConfig::default()
+        })
+        .inputs(["Fix the status"])
+        .approvals(["approved", "approved"])
+        .add_tool(Box::new(WriteFile))
+}
+
+fn verification_events(events: &[Event]) -> Vec<Event> {
+    events
+        .iter()
+        .filter(|event| {
+            matches!(
+                event,
+                Event::VerificationStarted { .. } | Event::VerificationFinished { .. }
+            )
+        })
+        .cloned()
+        .collect()
+}
+
+#[tokio::test]
+async fn chat_session_sends_failing_checks_back_until_they_pass() {
+    let dir = create_temp_dir("retry");
+
+    let scenario = verification_scenario(&dir, 3);
+    let scenario = write_status(scenario, "1", &dir, "broken");
+    let scenario = write_status(scenario.then_message("Done", true), "2", &dir, "fixed");
+    let scenario = scenario.then_message("Fixed it for real", true).run().await;
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(
+        verification_events(&scenario.events),
+        vec![
+            Event::VerificationStarted {
+                command: CHECK.to_string(),
+            },
+            Event::VerificationFinished {
+                command: CHECK.to_string(),
+                passed: false,
+                output: String::new(),
+                retrying: true,
+            },
+            Event::VerificationStarted {
+                command: CHECK.to_string(),
+            },
+            Event::VerificationFinished {
+                command: CHECK.to_string(),
+                passed: true,
+                output: "fixed\n".to_string(),
+                retrying: false,
+            },
+        ]
+    );
+    assert!(scenario.events.contains(&Event::ReceivedAssistantMessage {
+        message: "Fixed it for real".to_string(),
+    }));
+
+    let memory = scenario.last_client_call().1;
+    assert!(memory.iter().any(|message| matches!(
+        message,
+        ChatMessage::System { content }
+            if content.starts_with("Your changes do not pass the project's checks yet")
+                && content.contains(CHECK)
+    )));
+}
+
+#[tokio::test]
+async fn chat_session_ends_the_turn_when_check_retries_are_used_up() {
+    let dir = create_temp_dir("exhausted");
+
+    let scenario = verification_scenario(&dir, 1);
+    let scenario = write_status(scenario, "1", &dir, "broken");
+    let scenario = write_status(
+        scenario.then_message("Done", true),
+        "2",
+        &dir,
+        "still broken",
+    );
+    let scenario = scenario
+        .then_message("I could not fix it", true)
+        .run()
+        .await;
+    fs::remove_dir_all(&dir).unwrap();
+
+    let events = verification_events(&scenario.events);
+    assert_eq!(events.len(), 4);
+    assert_eq!(
+        events[3],
+        Event::VerificationFinished {
+            command: CHECK.to_string(),
+            passed: false,
+            output: String::new(),
+            retrying: false,
+        }
+    );
+    assert!(scenario.events.contains(&Event::ReceivedAssistantMessage {
+        message: "I could not fix it".to_string(),
+    }));
+}
+
+#[tokio::test]
+async fn chat_session_skips_the_check_when_no_files_were_modified() {
+    let dir = create_temp_dir("unmodified");
+
+    let scenario = verification_scenario(&dir, 3)
+        .then_message("Nothing to change", true)
+        .run()
+        .await;
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert!(verification_events(&scenario.events).is_empty());
+}
+
+#[tokio::test]
+async fn chat_session_checks_changes_made_by_commands() {
+    let dir = create_temp_dir("command");
+
+    let scenario = verification_scenario(&dir, 3)
+        .add_tool(Box::new(ExecuteCommand))
+        .then_tool_call(
+            "1",
+            "execute_command",
+            HashMap::from([(
+                "command".to_string(),
+                format!("echo fixed > {}", dir.join("status.txt").display()),
+            )]),
+        )
+        .then_message("Done", true)
+        .run()
+        .await;
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(
+        verification_events(&scenario.events)[1],
+        Event::VerificationFinished {
+            command: CHECK.to_string(),
+            passed: true,
+            output: "fixed\n".to_string(),
+            retrying: false,
+        }
+    );
+}
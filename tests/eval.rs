@@ -0,0 +1,178 @@
+mod helpers;
+
+use code_g::client::models::{ChatResult, Model, Parameters, ToolCall, Usage};
+use code_g::client::providers::openai::schema::Model as OpenAiModel;
+use code_g::eval::models::{EvalReport, EvalSuite, EvalTask};
+use code_g::eval::runner::run_eval;
+use code_g::session::budget::TurnBudget;
+use code_g::tools::registry::Registry;
+use code_g::tools::traits::ToolRegistry;
+use code_g::tools::write_file::WriteFile;
+use helpers::mocks::chat_client::MockChatClient;
+use helpers::mocks::tool_registry::{MockTool, MockToolRegistry};
+use std::collections::HashMap;
+use std::env;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+fn task(name: &str, verify: &str) -> EvalTask {
+    EvalTask {
+        name: name.to_string(),
+        fixture: PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/eval_repo"),
+        prompt: "Make sure greeting.txt greets the world".to_string(),
+        verify: verify.to_string(),
+    }
+}
+
+fn client() -> MockChatClient {
+    MockChatClient::new(
+        vec![
+            Ok(ChatResult::ToolCalls(vec![ToolCall {
+                id: "1".to_string(),
+                name: "write_file".to_string(),
+                arguments: HashMap::new(),
+            }])),
+            Ok(ChatResult::Message {
+                content: "Done".to_string(),
+                turn_over: true,
+            }),
+        ],
+        Arc::new(Mutex::new(vec![])),
+    )
+    .with_usage(Usage {
+        prompt_tokens: 400_000,
+        completion_tokens: 100_000,
+        total_tokens: 500_000,
+    })
+}
+
+fn tools() -> Box<dyn ToolRegistry> {
+    Box::new(MockToolRegistry::new(
+        vec![Box::new(MockTool::new(
+            "write_file".to_string(),
+            "Write a file".to_string(),
+            Parameters {
+                param_type: "object".to_string(),
+                properties: HashMap::new(),
+                required: vec![],
+                additional_properties: false,
+            },
+            true,
+            true,
+            "AI wants to write a file".to_string(),
+            "Write file was declined by user".to_string(),
+            "File written".to_string(),
+        ))],
+        Arc::new(Mutex::new(vec![])),
+    ))
+}
+
+#[tokio::test]
+async fn run_eval_verifies_each_task_on_a_copy_of_its_fixture() {
+    let cwd = env::current_dir().unwrap();
+    let suite = EvalSuite {
+        tasks: vec![
+            task("greets", "grep Hello greeting.txt"),
+            task("adds-file", "cat farewell.txt"),
+        ],
+    };
+
+    let report = run_eval(
+        &suite,
+        &[Model::OpenAi(OpenAiModel::Gpt4o)],
+        TurnBudget::default(),
+        || Box::new(client()),
+        tools,
+    )
+    .await;
+
+    assert_eq!(env::current_dir().unwrap(), cwd);
+    assert_eq!(report.exit_code, EvalReport::EXIT_FAILED);
+
+    let greets = &report.results[0];
+    assert!(greets.passed);
+    assert_eq!(greets.model, "gpt-4o");
+    assert_eq!(greets.iterations, 2);
+    assert_eq!(greets.usage.total_tokens, 1_000_000);
+    assert_eq!(greets.cost, 4.0);
+    assert_eq!(greets.response, Some("Done".to_string()));
+    assert_eq!(greets.verify_output, "Hello, world!\n");
+
+    let adds_file = &report.results[1];
+    assert!(!adds_file.passed);
+    assert!(adds_file.error.is_none());
+    assert!(adds_file.verify_output.contains("farewell.txt"));
+}
+
+#[tokio::test]
+async fn run_eval_fails_tasks_whose_fixture_is_missing() {
+    let mut missing = task("missing", "true");
+    missing.fixture = PathBuf::from("tests/fixtures/no_such_repo");
+
+    let report = run_eval(
+        &EvalSuite {
+            tasks: vec![missing],
+        },
+        &[Model::OpenAi(OpenAiModel::Gpt4oMini)],
+        TurnBudget::default(),
+        || Box::new(client()),
+        tools,
+    )
+    .await;
+
+    assert!(!report.results[0].passed);
+    assert_eq!(report.results[0].iterations, 0);
+    assert!(
+        report.results[0]
+            .error
+            .as_ref()
+            .unwrap()
+            .starts_with("Failed to copy fixture tests/fixtures/no_such_repo")
+    );
+}
+
+fn write_call(id: &str, path: &str) -> ToolCall {
+    ToolCall {
+        id: id.to_string(),
+        name: "write_file".to_string(),
+        arguments: HashMap::from([
+            ("path".to_string(), path.to_string()),
+            ("content".to_string(), "Goodbye!".to_string()),
+        ]),
+    }
+}
+
+fn writing_client() -> MockChatClient {
+    MockChatClient::new(
+        vec![
+            Ok(ChatResult::ToolCalls(vec![
+                write_call("1", "farewell.txt"),
+                write_call("2", "../code_g_eval_escape.txt"),
+            ])),
+            Ok(ChatResult::Message {
+                content: "Done".to_string(),
+                turn_over: true,
+            }),
+        ],
+        Arc::new(Mutex::new(vec![])),
+    )
+}
+
+#[tokio::test]
+async fn run_eval_works_in_the_copy_and_denies_paths_outside_of_it() {
+    let report = run_eval(
+        &EvalSuite {
+            tasks: vec![task("adds-file", "cat farewell.txt")],
+        },
+        &[Model::OpenAi(OpenAiModel::Gpt4oMini)],
+        TurnBudget::default(),
+        || Box::new(writing_client()),
+        || Box::new(Registry::from_tools(vec![Box::new(WriteFile)])),
+    )
+    .await;
+
+    assert!(report.results[0].passed);
+    assert_eq!(report.results[0].verify_output, "Goodbye!");
+    assert!(!PathBuf::from("farewell.txt").exists());
+    assert!(!env::temp_dir().join("code_g_eval_escape.txt").exists());
+}
@@ -0,0 +1,266 @@
+mod helpers;
+
+use code_g::client::error::ChatClientError;
+use code_g::client::models::{ChatResult, Model, Parameters, ToolCall};
+use code_g::client::providers::openai::schema::Model as OpenAiModel;
+use code_g::config::settings::Settings;
+use code_g::headless::models::{ApprovalPolicy, HeadlessReport, ToolCallRecord};
+use code_g::headless::runner::run_headless;
+use code_g::permissions::config::{PermissionDecision, PermissionRule, PermissionsConfig};
+use code_g::session::budget::TurnBudget;
+use code_g::session::system_prompt::SystemPromptConfig;
+use helpers::mocks::chat_client::MockChatClient;
+use helpers::mocks::tool_registry::{MockTool, MockToolRegistry};
+use std::collections::{BTreeMap, HashMap};
+use std::env;
+use std::sync::{Arc, Mutex};
+
+fn command_tool() -> MockTool {
+    MockTool::new(
+        "execute_command".to_string(),
+        "Execute a command in the terminal".to_string(),
+        Parameters {
+            param_type: "object".to_string(),
+            properties: HashMap::new(),
+            required: vec!["command".to_string()],
+            additional_properties: false,
+        },
+        true,
+        true,
+        "AI wants to execute a command".to_string(),
+        "Execute command was declined by user".to_string(),
+        "ok".to_string(),
+    )
+}
+
+fn command_call(id: &str) -> ChatResult {
+    ChatResult::ToolCalls(vec![ToolCall {
+        id: id.to_string(),
+        name: "execute_command".to_string(),
+        arguments: HashMap::from([("command".to_string(), "cargo test".to_string())]),
+    }])
+}
+
+// Each call runs a different test so the calls are not detected as a loop
+fn numbered_command_call(id: usize) -> ChatResult {
+    ChatResult::ToolCalls(vec![ToolCall {
+        id: id.to_string(),
+        name: "execute_command".to_string(),
+        arguments: HashMap::from([("command".to_string(), format!("cargo test case_{}", id))]),
+    }])
+}
+
+async fn run(
+    results: Vec<Result<ChatResult, ChatClientError>>,
+    approval_policy: ApprovalPolicy,
+) -> (
+    HeadlessReport,
+    Arc<Mutex<Vec<(String, HashMap<String, String>)>>>,
+) {
+    let client = MockChatClient::new(results, Arc::new(Mutex::new(vec![])));
+    let registry_calls = Arc::new(Mutex::new(vec![]));
+    let registry = MockToolRegistry::new(vec![Box::new(command_tool())], registry_calls.clone());
+
+    let report = run_headless(
+        Box::new(client),
+        Box::new(registry),
+        SystemPromptConfig::None,
+        None,
+        "Run the tests",
+        approval_policy,
+        TurnBudget::default(),
+        Settings::default(),
+    )
+    .await;
+
+    (report, registry_calls)
+}
+
+#[tokio::test]
+async fn headless_run_returns_final_message_and_approved_tool_calls() {
+    let (report, registry_calls) = run(
+        vec![
+            Ok(command_call("1")),
+            Ok(ChatResult::Message {
+                content: "All tests pass".to_string(),
+                turn_over: true,
+            }),
+        ],
+        ApprovalPolicy::Approve,
+    )
+    .await;
+
+    assert_eq!(report.exit_code, HeadlessReport::EXIT_SUCCESS);
+    assert_eq!(report.message, Some("All tests pass".to_string()));
+    assert_eq!(
+        report.tool_calls,
+        vec![ToolCallRecord {
+            tool_name: "execute_command".to_string(),
+            parameters: HashMap::from([("command".to_string(), "cargo test".to_string())]),
+            response: "ok".to_string(),
+            approved: true,
+        }]
+    );
+    assert_eq!(registry_calls.lock().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn headless_run_uses_given_model() {
+    let calls = Arc::new(Mutex::new(vec![]));
+    let client = MockChatClient::new(
+        vec![Ok(ChatResult::Message {
+            content: "All tests pass".to_string(),
+            turn_over: true,
+        })],
+        calls.clone(),
+    );
+    let registry = MockToolRegistry::new(vec![], Arc::new(Mutex::new(vec![])));
+
+    let report = run_headless(
+        Box::new(client),
+        Box::new(registry),
+        SystemPromptConfig::None,
+        Some(Model::OpenAi(OpenAiModel::Gpt4o)),
+        "Run the tests",
+        ApprovalPolicy::Deny,
+        TurnBudget::default(),
+        Settings::default(),
+    )
+    .await;
+
+    assert_eq!(report.exit_code, HeadlessReport::EXIT_SUCCESS);
+    assert_eq!(
+        calls.lock().unwrap()[0].0,
+        Model::OpenAi(OpenAiModel::Gpt4o)
+    );
+}
+
+#[tokio::test]
+async fn headless_run_declines_tools_with_deny_policy() {
+    let (report, registry_calls) = run(
+        vec![
+            Ok(command_call("1")),
+            Ok(ChatResult::Message {
+                content: "I could not run the tests".to_string(),
+                turn_over: true,
+            }),
+        ],
+        ApprovalPolicy::Deny,
+    )
+    .await;
+
+    assert_eq!(report.exit_code, HeadlessReport::EXIT_SUCCESS);
+    assert!(!report.tool_calls[0].approved);
+    assert!(registry_calls.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn headless_run_reports_fatal_client_errors() {
+    let (report, _) = run(
+        vec![Err(ChatClientError::InvalidApiKey)],
+        ApprovalPolicy::Deny,
+    )
+    .await;
+
+    assert_eq!(report.exit_code, HeadlessReport::EXIT_CLIENT_ERROR);
+    assert_eq!(report.message, None);
+    assert!(report.error.unwrap().contains("Invalid API key"));
+}
+
+#[tokio::test]
+async fn headless_run_returns_summary_when_iterations_are_exhausted() {
+    let mut results: Vec<_> = (0..50).map(|i| Ok(numbered_command_call(i))).collect();
+    results.push(Ok(ChatResult::Message {
+        content: "Ran the tests 50 times, they still fail".to_string(),
+        turn_over: true,
+    }));
+
+    let (report, registry_calls) = run(results, ApprovalPolicy::Approve).await;
+
+    assert_eq!(report.exit_code, HeadlessReport::EXIT_MAX_ITERATIONS);
+    assert!(report.budget_exhausted);
+    assert_eq!(
+        report.message,
+        Some("Ran the tests 50 times, they still fail".to_string())
+    );
+    assert_eq!(registry_calls.lock().unwrap().len(), 50);
+}
+
+#[tokio::test]
+async fn headless_run_reports_max_iterations_exceeded_without_summary() {
+    let results = (0..51).map(|i| Ok(numbered_command_call(i))).collect();
+
+    let (report, registry_calls) = run(results, ApprovalPolicy::Approve).await;
+
+    assert_eq!(report.exit_code, HeadlessReport::EXIT_MAX_ITERATIONS);
+    assert_eq!(registry_calls.lock().unwrap().len(), 50);
+}
+
+#[tokio::test]
+async fn headless_run_applies_deny_rules_to_absolute_paths() {
+    let client = MockChatClient::new(
+        vec![
+            Ok(ChatResult::ToolCalls(vec![ToolCall {
+                id: "1".to_string(),
+                name: "read_file".to_string(),
+                arguments: HashMap::from([(
+                    "path".to_string(),
+                    env::current_dir()
+                        .unwrap()
+                        .join(".git/config")
+                        .to_string_lossy()
+                        .to_string(),
+                )]),
+            }])),
+            Ok(ChatResult::Message {
+                content: "The config is off limits".to_string(),
+                turn_over: true,
+            }),
+        ],
+        Arc::new(Mutex::new(vec![])),
+    );
+    let registry_calls = Arc::new(Mutex::new(vec![]));
+    let read_file = MockTool::new(
+        "read_file".to_string(),
+        "Read a file".to_string(),
+        Parameters {
+            param_type: "object".to_string(),
+            properties: HashMap::new(),
+            required: vec!["path".to_string()],
+            additional_properties: false,
+        },
+        true,
+        true,
+        "AI wants to read a file".to_string(),
+        "Read file was declined by user".to_string(),
+        "secrets".to_string(),
+    );
+    let registry = MockToolRegistry::new(vec![Box::new(read_file)], registry_calls.clone());
+    let settings = Settings {
+        permissions: PermissionsConfig {
+            rules: vec![PermissionRule {
+                decision: PermissionDecision::Deny,
+                tool: "read_file".to_string(),
+                arguments: BTreeMap::from([("path".to_string(), ".git/*".to_string())]),
+                reason: None,
+            }],
+        },
+        ..Settings::default()
+    };
+
+    let report = run_headless(
+        Box::new(client),
+        Box::new(registry),
+        SystemPromptConfig::None,
+        None,
+        "Show the git config",
+        ApprovalPolicy::Approve,
+        TurnBudget::default(),
+        settings,
+    )
+    .await;
+
+    assert_eq!(report.exit_code, HeadlessReport::EXIT_SUCCESS);
+    assert!(!report.tool_calls[0].approved);
+    assert!(registry_calls.lock().unwrap().is_empty());
+}
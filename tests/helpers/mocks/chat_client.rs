@@ -3,7 +3,7 @@
 use async_trait::async_trait;
 use code_g::client::{
     error::ChatClientError,
-    models::{ChatMessage, ChatResult, Model, Tool},
+    models::{ChatMessage, ChatResult, Model, Tool, Usage},
     traits::ChatClient,
 };
 use std::sync::{Arc, Mutex};
@@ -12,6 +12,7 @@ use std::sync::{Arc, Mutex};
 pub struct MockChatClient {
     queue: Arc<Mutex<Vec<Result<ChatResult, ChatClientError>>>>,
     calls: Arc<Mutex<Vec<(Model, Vec<ChatMessage>, Vec<Tool>)>>>,
+    usage: Option<Usage>,
 }
 
 impl MockChatClient {
@@ -32,9 +33,24 @@ impl MockChatClient {
         Self {
             queue: Arc::new(Mutex::new(queue)),
             calls,
+            usage: None,
         }
     }
 
+    /// Reports the given usage for every successful completion.
+    ///
+    /// # Arguments
+    ///
+    /// * `usage` - The usage to report per call.
+    ///
+    /// # Returns
+    ///
+    /// The mock chat client reporting the given usage.
+    pub fn with_usage(mut self, usage: Usage) -> Self {
+        self.usage = Some(usage);
+        self
+    }
+
     /// Pushes a result to the queue.
     ///
     /// # Arguments
@@ -74,4 +90,16 @@ impl ChatClient for MockChatClient {
             Err(e) => Err(e),
         }
     }
+
+    async fn create_chat_completion_with_usage(
+        &self,
+        model: &Model,
+        chat_history: &[ChatMessage],
+        tools: &[Tool],
+    ) -> Result<(ChatResult, Option<Usage>), ChatClientError> {
+        let result = self
+            .create_chat_completion(model, chat_history, tools)
+            .await?;
+        Ok((result, self.usage))
+    }
 }
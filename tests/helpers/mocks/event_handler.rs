@@ -1,6 +1,7 @@
 #![allow(dead_code)]
 
 use code_g::session::event::{Action, Event, EventHandler};
+use code_g::session::input_queue::InputQueue;
 use std::io;
 use std::sync::{Arc, Mutex};
 
@@ -13,6 +14,7 @@ pub struct MockEventHandler {
     events: Arc<Mutex<Vec<Event>>>,
     inputs: Arc<Mutex<Vec<String>>>,
     approvals: Arc<Mutex<Vec<String>>>,
+    type_ahead: Option<(InputQueue, Vec<String>)>,
 }
 
 impl MockEventHandler {
@@ -21,12 +23,17 @@ impl MockEventHandler {
     /// # Arguments
     ///
     /// * `inputs` - The message to be returned on handle_action for RequestUserInput.
-    /// * `approvals` - The approval message to be returned on handle_action for RequestUserApproval.
+    /// * `approvals` - The approval message to be returned on handle_action for RequestUserApproval
+    ///   and RequestPlanApproval.
     ///
     /// # Returns
     ///
     /// A new `MockEventHandler` instance.
-    pub fn new(events: Arc<Mutex<Vec<Event>>>, inputs: Vec<String>, approvals: Vec<String>) -> Self {
+    pub fn new(
+        events: Arc<Mutex<Vec<Event>>>,
+        inputs: Vec<String>,
+        approvals: Vec<String>,
+    ) -> Self {
         // Add "exit" to the inputs to simulate the user exiting the chat.
         let mut inputs_with_exit = inputs;
         inputs_with_exit.push("exit".to_string());
@@ -35,9 +42,25 @@ impl MockEventHandler {
             events,
             inputs: Arc::new(Mutex::new(inputs_with_exit)),
             approvals: Arc::new(Mutex::new(approvals)),
+            type_ahead: None,
         }
     }
 
+    /// Type messages while the assistant is working.
+    ///
+    /// # Arguments
+    ///
+    /// * `queue` - The input queue shared with the session.
+    /// * `messages` - The messages pushed to the queue when the first tool call is received.
+    ///
+    /// # Returns
+    ///
+    /// The `MockEventHandler` typing the messages during the first turn with a tool call.
+    pub fn with_type_ahead(mut self, queue: InputQueue, messages: Vec<String>) -> Self {
+        self.type_ahead = Some((queue, messages));
+        self
+    }
+
     /// Get the events that have been handled.
     ///
     /// # Returns
@@ -68,6 +91,13 @@ impl MockEventHandler {
 
 impl EventHandler for MockEventHandler {
     fn handle_event(&mut self, event: Event) {
+        if let (Event::ReceivedToolCall { .. }, Some((queue, messages))) =
+            (&event, &mut self.type_ahead)
+        {
+            for message in messages.drain(..) {
+                queue.push(message);
+            }
+        }
         self.events.lock().unwrap().push(event);
     }
 
@@ -77,7 +107,7 @@ impl EventHandler for MockEventHandler {
                 let input = self.inputs.lock().unwrap().remove(0);
                 Ok(input)
             }
-            Action::RequestUserApproval { .. } => {
+            Action::RequestUserApproval { .. } | Action::RequestPlanApproval { .. } => {
                 let approval = self.approvals.lock().unwrap().remove(0);
                 Ok(approval)
             }
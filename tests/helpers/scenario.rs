@@ -6,12 +6,19 @@ use crate::helpers::mocks::{
     tool_registry::{MockTool, MockToolRegistry},
 };
 use code_g::client::error::ChatClientError;
-use code_g::client::models::{ChatMessage, ChatResult, Model, Parameters, Tool, ToolCall};
+use code_g::client::models::{ChatMessage, ChatResult, Model, Parameters, Tool, ToolCall, Usage};
+use code_g::hooks::config::HooksConfig;
+use code_g::permissions::config::PermissionsConfig;
+use code_g::session::budget::TurnBudget;
+use code_g::session::environment::PromptEnvironment;
 use code_g::session::event::Event;
+use code_g::session::input_queue::InputQueue;
 use code_g::session::session::ChatSession;
 use code_g::session::system_prompt::SystemPromptConfig;
+use code_g::session::verification::VerificationConfig;
 use code_g::tools::traits::Tool as ToolTrait;
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 
 /// A lightweight builder for end-to-end chat session scenarios.
@@ -21,6 +28,18 @@ pub struct ScenarioBuilder {
     approval_inputs: Vec<String>,
     queued_results: Vec<Result<ChatResult, ChatClientError>>,
     tools: Vec<Box<dyn ToolTrait>>,
+    turn_budget: TurnBudget,
+    usage_per_call: Option<Usage>,
+    hooks: HooksConfig,
+    permissions: PermissionsConfig,
+    project_dir: Option<PathBuf>,
+    sub_agents: bool,
+    todos: bool,
+    environment: Option<PromptEnvironment>,
+    output_limit: Option<usize>,
+    type_ahead: Vec<String>,
+    verification: VerificationConfig,
+    editor: Option<Model>,
 }
 
 impl Default for ScenarioBuilder {
@@ -31,6 +50,18 @@ impl Default for ScenarioBuilder {
             approval_inputs: Vec::new(),
             queued_results: Vec::new(),
             tools: Vec::new(),
+            turn_budget: TurnBudget::default(),
+            usage_per_call: None,
+            hooks: HooksConfig::default(),
+            permissions: PermissionsConfig::default(),
+            project_dir: None,
+            sub_agents: false,
+            todos: false,
+            environment: None,
+            output_limit: None,
+            type_ahead: vec![],
+            verification: VerificationConfig::default(),
+            editor: None,
         }
     }
 }
@@ -59,6 +90,170 @@ impl ScenarioBuilder {
         self
     }
 
+    /// Set the turn budget of the session.
+    ///
+    /// # Arguments
+    ///
+    /// * `budget` - The turn budget to set.
+    ///
+    /// # Returns
+    ///
+    /// A ScenarioBuilder with the turn budget set.
+    pub fn with_turn_budget(mut self, budget: TurnBudget) -> Self {
+        self.turn_budget = budget;
+        self
+    }
+
+    /// Set the permission rules of the session.
+    ///
+    /// # Arguments
+    ///
+    /// * `permissions` - The rules deciding which tool calls run, need approval or are denied.
+    ///
+    /// # Returns
+    ///
+    /// A ScenarioBuilder with the permission rules set.
+    pub fn with_permissions(mut self, permissions: PermissionsConfig) -> Self {
+        self.permissions = permissions;
+        self
+    }
+
+    /// Set the project directory that "always allow" grants are saved to.
+    ///
+    /// # Arguments
+    ///
+    /// * `project_dir` - The directory containing the project's `.code-g` folder.
+    ///
+    /// # Returns
+    ///
+    /// A ScenarioBuilder with the project directory set.
+    pub fn with_project_dir(mut self, project_dir: PathBuf) -> Self {
+        self.project_dir = Some(project_dir);
+        self
+    }
+
+    /// Let the assistant delegate tasks to sub-agents.
+    ///
+    /// # Returns
+    ///
+    /// A ScenarioBuilder with sub-agents enabled.
+    pub fn with_sub_agents(mut self) -> Self {
+        self.sub_agents = true;
+        self
+    }
+
+    /// Let the assistant keep a todo list.
+    ///
+    /// # Returns
+    ///
+    /// A ScenarioBuilder with the todo tools enabled.
+    pub fn with_todos(mut self) -> Self {
+        self.todos = true;
+        self
+    }
+
+    /// Describe the given environment in the system prompt.
+    ///
+    /// # Arguments
+    ///
+    /// * `environment` - The environment to fill the system prompt in with.
+    ///
+    /// # Returns
+    ///
+    /// A ScenarioBuilder with the environment set.
+    pub fn with_environment(mut self, environment: PromptEnvironment) -> Self {
+        self.environment = Some(environment);
+        self
+    }
+
+    /// Limit the size of tool outputs added to the conversation.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_tokens` - Maximum size of a tool output, in tokens.
+    ///
+    /// # Returns
+    ///
+    /// A ScenarioBuilder with the output limit set.
+    pub fn with_output_limit(mut self, max_tokens: usize) -> Self {
+        self.output_limit = Some(max_tokens);
+        self
+    }
+
+    /// Set the check command run after turns that modified files.
+    ///
+    /// # Arguments
+    ///
+    /// * `verification` - The check command and its retry budget.
+    ///
+    /// # Returns
+    ///
+    /// A ScenarioBuilder with the verification set.
+    pub fn with_verification(mut self, verification: VerificationConfig) -> Self {
+        self.verification = verification;
+        self
+    }
+
+    /// Pair the assistant with an editor that shares the queued results.
+    ///
+    /// # Arguments
+    ///
+    /// * `model` - The model of the editor.
+    ///
+    /// # Returns
+    ///
+    /// A ScenarioBuilder with the editor set.
+    pub fn with_editor(mut self, model: Model) -> Self {
+        self.editor = Some(model);
+        self
+    }
+
+    /// Type messages while the assistant is working on the first turn with a tool call.
+    ///
+    /// # Arguments
+    ///
+    /// * `messages` - The messages typed when the first tool call is received.
+    ///
+    /// # Returns
+    ///
+    /// A ScenarioBuilder with the typed-ahead messages set.
+    pub fn type_ahead<I, S>(mut self, messages: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.type_ahead = messages.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Set the lifecycle hooks of the session.
+    ///
+    /// # Arguments
+    ///
+    /// * `hooks` - The hooks to run.
+    ///
+    /// # Returns
+    ///
+    /// A ScenarioBuilder with the hooks set.
+    pub fn with_hooks(mut self, hooks: HooksConfig) -> Self {
+        self.hooks = hooks;
+        self
+    }
+
+    /// Report the given usage for every client call.
+    ///
+    /// # Arguments
+    ///
+    /// * `usage` - The usage to report per call.
+    ///
+    /// # Returns
+    ///
+    /// A ScenarioBuilder with the usage set.
+    pub fn with_usage_per_call(mut self, usage: Usage) -> Self {
+        self.usage_per_call = Some(usage);
+        self
+    }
+
     /// Queue a user message.
     ///
     /// # Arguments
@@ -104,6 +299,20 @@ impl ScenarioBuilder {
         self
     }
 
+    /// Queue a chat client error.
+    ///
+    /// # Arguments
+    ///
+    /// * `error` - The error the client returns.
+    ///
+    /// # Returns
+    ///
+    /// A ScenarioBuilder with the error queued.
+    pub fn then_error(mut self, error: ChatClientError) -> Self {
+        self.queued_results.push(Err(error));
+        self
+    }
+
     /// Queue an assistant tool call.
     ///
     /// # Arguments
@@ -170,6 +379,20 @@ impl ScenarioBuilder {
         self
     }
 
+    /// Add a real tool available to the registry.
+    ///
+    /// # Arguments
+    ///
+    /// * `tool` - The tool to add.
+    ///
+    /// # Returns
+    ///
+    /// A ScenarioBuilder with the tool added.
+    pub fn add_tool(mut self, tool: Box<dyn ToolTrait>) -> Self {
+        self.tools.push(tool);
+        self
+    }
+
     /// Runs the scenario end-to-end and returns artifacts for assertions.
     ///
     /// # Returns
@@ -181,24 +404,52 @@ impl ScenarioBuilder {
     /// Panics if the events queue is locked.
     pub async fn run(self) -> ScenarioResult {
         let events = Arc::new(Mutex::new(vec![]));
+        let input_queue = InputQueue::new();
         let event_handler =
-            MockEventHandler::new(events.clone(), self.user_inputs, self.approval_inputs);
+            MockEventHandler::new(events.clone(), self.user_inputs, self.approval_inputs)
+                .with_type_ahead(input_queue.clone(), self.type_ahead);
 
         let client_calls: Arc<Mutex<Vec<(Model, Vec<ChatMessage>, Vec<Tool>)>>> =
             Arc::new(Mutex::new(vec![]));
 
-        let chat_client = MockChatClient::new(self.queued_results, client_calls.clone());
+        let mut chat_client = MockChatClient::new(self.queued_results, client_calls.clone());
+        if let Some(usage) = self.usage_per_call {
+            chat_client = chat_client.with_usage(usage);
+        }
 
         let registry_calls: Arc<Mutex<Vec<(String, HashMap<String, String>)>>> =
             Arc::new(Mutex::new(vec![]));
         let tool_registry = MockToolRegistry::new(self.tools, registry_calls.clone());
 
-        let mut session = ChatSession::new(
+        let session = ChatSession::new(
             Box::new(chat_client.clone()),
             Box::new(tool_registry),
             Box::new(event_handler),
             self.system_prompt_config,
-        );
+        )
+        .with_turn_budget(self.turn_budget)
+        .with_hooks(self.hooks)
+        .with_permissions(self.permissions)
+        .with_sub_agents(self.sub_agents)
+        .with_todos(self.todos)
+        .with_verification(self.verification)
+        .with_input_queue(input_queue);
+        let session = match self.project_dir {
+            Some(project_dir) => session.with_project_dir(project_dir),
+            None => session,
+        };
+        let session = match self.editor {
+            Some(model) => session.with_editor(Box::new(chat_client.clone()), model),
+            None => session,
+        };
+        let session = match self.output_limit {
+            Some(max_tokens) => session.with_output_limit(max_tokens),
+            None => session,
+        };
+        let mut session = match self.environment {
+            Some(environment) => session.with_environment(environment),
+            None => session,
+        };
 
         // Drive the session by running the loop until "exit" (MockEventHandler appends it).
         let _ = session.run().await;
@@ -0,0 +1,151 @@
+mod helpers;
+
+use code_g::client::error::ChatClientError;
+use code_g::client::models::{ChatResult, Parameters, ToolCall};
+use code_g::config::settings::Settings;
+use code_g::headless::models::OutputFormat;
+use code_g::script::models::{Script, ScriptReport};
+use code_g::script::runner::run_script;
+use code_g::session::budget::TurnBudget;
+use code_g::session::system_prompt::SystemPromptConfig;
+use code_g::session::turn::ToolCallDecision;
+use code_g::tools::traits::Tool;
+use helpers::mocks::chat_client::MockChatClient;
+use helpers::mocks::tool_registry::{MockTool, MockToolRegistry};
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+fn write_file_tool() -> Box<dyn Tool> {
+    Box::new(MockTool::new(
+        "write_file".to_string(),
+        "Write a file".to_string(),
+        Parameters {
+            param_type: "object".to_string(),
+            properties: HashMap::new(),
+            required: vec![],
+            additional_properties: false,
+        },
+        true,
+        true,
+        "AI wants to write a file".to_string(),
+        "Write file was declined by user".to_string(),
+        "File written".to_string(),
+    ))
+}
+
+fn message(content: &str) -> Result<ChatResult, ChatClientError> {
+    Ok(ChatResult::Message {
+        content: content.to_string(),
+        turn_over: true,
+    })
+}
+
+fn write_call() -> Result<ChatResult, ChatClientError> {
+    Ok(ChatResult::ToolCalls(vec![ToolCall {
+        id: "1".to_string(),
+        name: "write_file".to_string(),
+        arguments: HashMap::new(),
+    }]))
+}
+
+fn project_dir(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("code_g_script_{}_{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("hello.txt"), "hi there").unwrap();
+    dir
+}
+
+async fn run(
+    responses: Vec<Result<ChatResult, ChatClientError>>,
+    script: &str,
+    dir: &Path,
+) -> ScriptReport {
+    let client = MockChatClient::new(responses, Arc::new(Mutex::new(vec![])));
+    let tools = MockToolRegistry::new(vec![write_file_tool()], Arc::new(Mutex::new(vec![])));
+
+    run_script(
+        Box::new(client),
+        Box::new(tools),
+        SystemPromptConfig::None,
+        &Script::parse_yaml(script).unwrap(),
+        TurnBudget::default(),
+        Settings::default(),
+        dir,
+    )
+    .await
+}
+
+#[tokio::test]
+async fn run_script_answers_approvals_and_stops_at_the_first_failed_step() {
+    let dir = project_dir("steps");
+    let script = r#"
+steps:
+  - message: Write hello.txt
+    approvals:
+      - tool: write_file
+        answer: approve
+    assert:
+      - file_exists: hello.txt
+      - file_contains: { path: hello.txt, text: hi }
+      - command_output_contains: { command: cat hello.txt, text: there }
+  - message: Say goodbye
+    assert:
+      - response_contains: Goodbye
+  - message: Never sent
+"#;
+
+    let report = run(
+        vec![write_call(), message("Wrote it"), message("See you")],
+        script,
+        &dir,
+    )
+    .await;
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(report.exit_code, ScriptReport::EXIT_FAILED);
+    assert_eq!(report.steps.len(), 2);
+    assert!(report.steps[0].passed());
+    assert_eq!(
+        report.steps[0].tool_calls[0].decision,
+        ToolCallDecision::Approved
+    );
+    assert!(!report.steps[1].passed());
+    assert_eq!(
+        report.render(OutputFormat::Text).lines().last(),
+        Some("1 of 3 steps passed")
+    );
+}
+
+#[tokio::test]
+async fn run_script_declines_and_reports_unexpected_approvals() {
+    let dir = project_dir("approvals");
+    let script = r#"
+steps:
+  - message: Write hello.txt
+    assert:
+      - response_contains: Could not
+"#;
+
+    let report = run(
+        vec![write_call(), message("Could not write it")],
+        script,
+        &dir,
+    )
+    .await;
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(report.exit_code, ScriptReport::EXIT_FAILED);
+    assert_eq!(
+        report.steps[0].tool_calls[0].decision,
+        ToolCallDecision::Declined
+    );
+    assert_eq!(
+        report.steps[0].approval_failures,
+        vec!["unexpected approval request for write_file".to_string()]
+    );
+    assert!(report.steps[0].assertions[0].passed);
+}
@@ -93,3 +93,28 @@ fn execute_command_tool_combines_stdout_and_stderr() {
     let pos_stderr = output.find("STDERR:").unwrap();
     assert!(pos_out < pos_stderr);
 }
+
+#[test]
+fn execute_command_tool_runs_in_the_given_directory() {
+    let tool = ExecuteCommand;
+    let dir = std::env::temp_dir();
+
+    let args = HashMap::from([("command".to_string(), "pwd".to_string())]);
+    let result = tool.call_in_dir(args, &dir);
+
+    assert_eq!(
+        result.unwrap().trim(),
+        dir.canonicalize().unwrap().to_string_lossy()
+    );
+}
+
+#[test]
+fn execute_command_tool_only_reports_changes_for_commands_that_can_modify_files() {
+    let tool = ExecuteCommand;
+    let args = |command: &str| HashMap::from([("command".to_string(), command.to_string())]);
+
+    assert!(!tool.modifies_files(&args("git status")));
+    assert!(!tool.modifies_files(&args("cat Cargo.toml")));
+    assert!(tool.modifies_files(&args("cargo fmt")));
+    assert!(tool.modifies_files(&args("cat Cargo.toml > copy.toml")));
+}